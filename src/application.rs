@@ -1,5 +1,6 @@
 use crate::{
-    egl::{get_surface, init_egl},
+    conversion::modifiers_to_native,
+    backend::{Backend, GlBackend, GlSettings},
     error::{self, Error},
     event_loop::{
         self,
@@ -9,7 +10,8 @@ use crate::{
         SctkEventLoop,
     },
     sctk_event::{
-        IcedSctkEvent, KeyboardEventVariant, LayerSurfaceEventVariant, PopupEventVariant, SctkEvent,
+        CustomSurfaceEventVariant, IcedSctkEvent, KeyboardEventVariant, LayerSurfaceEventVariant,
+        PopupEventVariant, SctkEvent, SeatEventVariant, TouchEventVariant,
     },
     settings, Command, Debug, Executor, Runtime, Size, Subscription,
 };
@@ -18,20 +20,36 @@ use iced_native::{
     application::{self, StyleSheet},
     clipboard::{self, Null},
     command::platform_specific,
+    event::{wayland, PlatformSpecific},
+    keyboard,
     mouse::{self, Interaction},
+    touch,
     widget::operation,
     Element, Renderer,
 };
 
 use sctk::{
-    reexports::client::Proxy,
-    seat::{keyboard::Modifiers, pointer::PointerEventKind},
+    reexports::client::{
+        protocol::{wl_output::Transform, wl_surface::WlSurface},
+        Proxy,
+    },
+    seat::{
+        keyboard::{keysyms, KeyEvent, Modifiers},
+        pointer::PointerEventKind,
+    },
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    marker::PhantomData,
+    time::{Duration, Instant},
 };
-use std::{collections::HashMap, ffi::CString, fmt, marker::PhantomData, num::NonZeroU32};
 use wayland_backend::client::ObjectId;
 
-use glutin::{api::egl, prelude::*, surface::WindowSurface};
 use iced_graphics::{compositor, renderer, window, Color, Point, Viewport};
+use iced_native::command::platform_specific::wayland::{
+    layer_surface::IcedOutput, CompositorInfo, FrameStats,
+};
 use iced_native::user_interface::{self, UserInterface};
 use iced_native::window::Id as SurfaceId;
 use std::mem::ManuallyDrop;
@@ -50,13 +68,107 @@ pub enum Event<Message> {
     Window(platform_specific::wayland::window::Action<Message>),
     /// popup requests from the client
     Popup(platform_specific::wayland::popup::Action<Message>),
+    /// output requests from the client
+    Output(platform_specific::wayland::output::Action<Message>),
+    /// requests from the client which apply to any kind of surface
+    Surface(platform_specific::wayland::surface::Action<Message>),
+    /// idle notification requests from the client
+    IdleNotify(platform_specific::wayland::idle_notify::Action<Message>),
+    /// dmabuf requests from the client
+    Dmabuf(platform_specific::wayland::dmabuf::Action<Message>),
+    /// data device requests from the client
+    DataDevice(platform_specific::wayland::data_device::Action<Message>),
+    /// pointer requests from the client
+    Pointer(platform_specific::wayland::pointer::Action<Message>),
+    /// keyboard requests from the client
+    Keyboard(platform_specific::wayland::keyboard::Action<Message>),
+    /// seat requests from the client
+    Seat(platform_specific::wayland::seat::Action<Message>),
+
+    /// request sctk to read the text in the clipboard of the active seat
+    ReadClipboard(Box<dyn Fn(Option<String>) -> Message>),
 
     /// request sctk to set the cursor of the active pointer
     SetCursor(Interaction),
+    /// request sctk to send the pending requests to the compositor immediately
+    Flush,
+    /// request sctk to cap the frame rate of the surfaces
+    SetMaxFrameRate(Option<u32>),
+    /// request sctk to check whether an optional global is supported
+    Supports(String, Box<dyn FnOnce(bool) -> Message>),
+    /// request sctk to fetch the name of the compositor and its globals
+    FetchCompositorInfo(Box<dyn FnOnce(CompositorInfo) -> Message>),
+    /// request sctk to exit the event loop
+    Exit,
+    /// request sctk to suppress the redraws of the surfaces until the batch ends
+    BeginBatch,
+    /// request sctk to end the batch, and redraw the surfaces whose redraw was suppressed
+    EndBatch,
+    /// request sctk to update the opaque region of a surface
+    SetOpaque { id: SurfaceId, opaque: bool },
+    /// request sctk to negotiate the decorations of a window
+    SetDecorations { id: SurfaceId, decorations: Decorations },
+    /// request sctk to change the mode of a window
+    SetMode { id: SurfaceId, mode: iced_native::window::Mode },
+}
+
+/// Which side draws the decorations of a window.
+///
+/// <https://wayland.app/protocols/xdg-decoration-unstable-v1#zxdg_toplevel_decoration_v1:enum:mode>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Decorations {
+    /// The compositor draws the decorations.
+    ServerSide,
+    /// The application draws its own decorations, and server side decorations are never
+    /// requested.
+    ClientSide,
+    /// Server side decorations are preferred, but the compositor decides. The mode it
+    /// chose is reported with a `wayland::WindowEvent::ServerSideDecorations` event.
+    #[default]
+    Auto,
 }
 
 pub struct IcedSctkState;
 
+/// Whether a key was pressed, repeated or released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyState {
+    /// The key was pressed.
+    Pressed,
+    /// The key is held down, and repeats.
+    Repeated,
+    /// The key was released.
+    Released,
+}
+
+/// A key event as it was received from the compositor, before it is turned into a
+/// keyboard event of the widgets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawKeyEvent {
+    /// Whether the key was pressed, repeated or released.
+    pub state: KeyState,
+    /// The keysym of the key, with the current keymap and modifiers applied.
+    pub keysym: u32,
+    /// The scancode of the key.
+    pub scancode: u32,
+    /// The modifiers which are active.
+    pub modifiers: Modifiers,
+    /// The text produced by the key, if any.
+    pub utf8: Option<String>,
+}
+
+/// What happens to a key event after it was filtered by [`Application::on_raw_key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyFilter {
+    /// The key event reaches the widgets unchanged.
+    Pass,
+    /// The key event is dropped, and produces no event for the widgets.
+    Consume,
+    /// The key event is replaced by another one. Its modifiers are ignored, as they
+    /// are tracked separately.
+    Replace(RawKeyEvent),
+}
+
 /// An interactive, native cross-platform application.
 ///
 /// This trait is the main entrypoint of Iced. Once implemented, you can run
@@ -67,7 +179,7 @@ pub struct IcedSctkState;
 /// [`Command`] in some of its methods.
 ///
 /// When using an [`Application`] with the `debug` feature enabled, a debug view
-/// can be toggled by pressing `F12`.
+/// can be toggled by pressing `F12`, unless [`settings::DebugOverlay`] says otherwise.
 pub trait Application: Sized
 where
     <Self::Renderer as crate::Renderer>::Theme: StyleSheet,
@@ -115,6 +227,16 @@ where
     /// Returns the current [`Theme`] of the [`Application`].
     fn theme(&self) -> <Self::Renderer as crate::Renderer>::Theme;
 
+    /// Returns the [`Theme`] of the surface with the given id.
+    ///
+    /// It can be used to give a single surface, like a tooltip, a different
+    /// theme than the rest of the application.
+    ///
+    /// By default, it returns the [`Application::theme`].
+    fn surface_theme(&self, _id: SurfaceIdWrapper) -> <Self::Renderer as crate::Renderer>::Theme {
+        self.theme()
+    }
+
     /// Returns the [`Style`] variation of the [`Theme`].
     fn style(&self) -> <<Self::Renderer as crate::Renderer>::Theme as StyleSheet>::Style {
         Default::default()
@@ -146,6 +268,16 @@ where
         1.0
     }
 
+    /// Returns the scale factor of the surface with the given id.
+    ///
+    /// It is combined with the scale factor of the compositor, and can be used
+    /// to zoom a single surface.
+    ///
+    /// By default, it returns the [`Application::scale_factor`].
+    fn surface_scale_factor(&self, _id: SurfaceIdWrapper) -> f64 {
+        self.scale_factor()
+    }
+
     /// Returns whether the [`Application`] should be terminated.
     ///
     /// By default, it returns `false`.
@@ -153,8 +285,62 @@ where
         false
     }
 
+    /// Called when [`Application::should_exit`] returns `true`, or the last surface was
+    /// closed with `exit_on_close_request` set, before the application exits.
+    ///
+    /// Returning a [`Command`] cancels the exit and runs the command instead, e.g. to ask
+    /// the user for confirmation. The application then exits with
+    /// [`exit`](crate::commands::connection::exit), or when [`Application::should_exit`]
+    /// returns `true` again and the exit is not vetoed. The condition of
+    /// [`Application::should_exit`] should be reset when the exit is vetoed, as it is checked
+    /// after every update.
+    ///
+    /// By default, it returns `None`.
+    fn veto_exit(&mut self) -> Option<Command<Self::Message>> {
+        None
+    }
+
     /// TODO
     fn close_requested(&self, id: SurfaceIdWrapper) -> Self::Message;
+
+    /// Returns whether the window with the given id stays open after the compositor asked
+    /// to close it, even if the application exits on close requests.
+    ///
+    /// The window keeps being drawn, e.g. to play a closing animation, until the
+    /// application destroys it.
+    ///
+    /// By default, it returns `false`.
+    fn defer_close(&self, _id: SurfaceIdWrapper) -> bool {
+        false
+    }
+
+    /// Filters a key event before it reaches the widgets, e.g. to remap keys or to
+    /// implement sticky keys.
+    ///
+    /// A key which is consumed when it is pressed should usually also be consumed when
+    /// it is released.
+    ///
+    /// By default, every key event passes.
+    fn on_raw_key(&mut self, _event: &RawKeyEvent) -> KeyFilter {
+        KeyFilter::Pass
+    }
+
+    /// Returns which side draws the decorations of the window with the given id.
+    ///
+    /// It replaces the decorations of the window settings once the window is created.
+    ///
+    /// By default, it returns [`Decorations::Auto`].
+    fn decorations(&self, _id: SurfaceId) -> Decorations {
+        Decorations::Auto
+    }
+
+    /// Returns the message produced after the connection to the compositor was
+    /// lost and re-established.
+    ///
+    /// By default, no message is produced.
+    fn reconnected(&self) -> Option<Self::Message> {
+        None
+    }
 }
 
 /// Runs an [`Application`] with an executor, compositor, and the provided
@@ -169,20 +355,67 @@ where
     C: window::GLCompositor<Renderer = A::Renderer> + 'static,
     <A::Renderer as iced_native::Renderer>::Theme: StyleSheet,
     A::Flags: Clone,
+{
+    let backend_settings = GlSettings {
+        compositor: compositor_settings,
+        egl: settings.egl,
+    };
+    run_with_backend::<A, E, GlBackend<C>>(settings, backend_settings)
+}
+
+/// Runs an [`Application`] with an executor, a [`window::Compositor`] such as
+/// [`iced_wgpu::window::Compositor`], and the provided settings.
+#[cfg(feature = "wgpu")]
+pub fn run_wgpu<A, E, C>(
+    settings: settings::Settings<A::Flags>,
+    compositor_settings: C::Settings,
+) -> Result<(), error::Error>
+where
+    A: Application + 'static,
+    E: Executor + 'static,
+    C: window::Compositor<Renderer = A::Renderer> + 'static,
+    <A::Renderer as iced_native::Renderer>::Theme: StyleSheet,
+    A::Flags: Clone,
+{
+    run_with_backend::<A, E, crate::backend::WgpuBackend<C>>(settings, compositor_settings)
+}
+
+fn run_with_backend<A, E, B>(
+    settings: settings::Settings<A::Flags>,
+    backend_settings: B::Settings,
+) -> Result<(), error::Error>
+where
+    A: Application + 'static,
+    E: Executor + 'static,
+    B: Backend<Renderer = A::Renderer> + 'static,
+    <A::Renderer as iced_native::Renderer>::Theme: StyleSheet,
+    A::Flags: Clone,
 {
     let mut debug = Debug::new();
     debug.startup_started();
 
     let flags = settings.flags.clone();
     let exit_on_close_request = settings.exit_on_close_request;
+    let draw_before_configure = settings.draw_before_configure;
+    let debug_overlay = settings.debug_overlay;
+    // the overlay starts hidden
+    if debug_overlay == settings::DebugOverlay::AlwaysOn {
+        debug.toggle();
+    }
     let is_layer_surface = matches!(settings.surface, settings::InitialSurface::LayerSurface(_));
     let mut event_loop =
         SctkEventLoop::<A::Message>::new(&settings).expect("Failed to initialize the event loop");
 
     let (object_id, native_id, wl_surface) = match &settings.surface {
         settings::InitialSurface::LayerSurface(l) => {
-            // TODO ASHLEY should an application panic if it's initial surface can't be created?
-            let (native_id, surface) = event_loop.get_layer_surface(l.clone()).unwrap();
+            let mut l = l.clone();
+            if let Some(selector) = settings.initial_output.as_ref() {
+                match event_loop.resolve_output(selector) {
+                    Some(output) => l.output = IcedOutput::Output(output),
+                    None => log::warn!("No output matches {:?}, the compositor picks one", selector),
+                }
+            }
+            let (native_id, surface) = event_loop.get_layer_surface(l)?;
             (
                 surface.id(),
                 SurfaceIdWrapper::LayerSurface(native_id),
@@ -195,6 +428,10 @@ where
         }
     };
 
+    for (id, handler) in settings.custom_surfaces {
+        let _ = event_loop.get_custom_surface(id, handler);
+    }
+
     let surface_ids = HashMap::from([(object_id.clone(), native_id)]);
 
     let (runtime, ev_proxy) = {
@@ -210,35 +447,51 @@ where
         runtime.enter(|| A::new(flags))
     };
 
-    let (display, context, config, surface) = init_egl(&wl_surface, 100, 100);
-
-    let context = context.make_current(&surface).unwrap();
-    let egl_surfaces = HashMap::from([(native_id.inner(), surface)]);
-
-    #[allow(unsafe_code)]
-    let (compositor, renderer) = unsafe {
-        C::new(compositor_settings, |name| {
-            let name = CString::new(name).unwrap();
-            display.get_proc_address(name.as_c_str())
-        })?
+    // the surface is resized once it is configured, until then the requested size is the
+    // best guess
+    let (width, height) = match &settings.surface {
+        settings::InitialSurface::LayerSurface(l) => {
+            (l.size.0.unwrap_or(1), l.size.1.unwrap_or(1))
+        }
+        settings::InitialSurface::XdgWindow(w) => w.iced_settings.size,
+    };
+    // a layer surface with a fixed size is drawn at it before it is configured
+    let init_size = match &settings.surface {
+        settings::InitialSurface::LayerSurface(l) if draw_before_configure => match l.size {
+            (Some(w), Some(h)) if w > 0 && h > 0 => Some((w, h)),
+            _ => None,
+        },
+        _ => None,
     };
+    let reconnect_settings = B::reconnect_settings(&backend_settings);
+    let (backend, renderer, surface) = B::new(
+        backend_settings,
+        &wl_surface,
+        width.max(1),
+        height.max(1),
+    )?;
+    let backend_surfaces = HashMap::from([(native_id.inner(), surface)]);
+
     let (mut sender, receiver) = mpsc::unbounded::<IcedSctkEvent<A::Message>>();
+    let (control_sender, mut control_receiver) = mpsc::unbounded::<ControlFlow>();
 
-    let mut instance = Box::pin(run_instance::<A, E, C>(
+    let mut instance = Box::pin(run_instance::<A, E, B>(
         application,
-        compositor,
+        backend,
+        reconnect_settings,
         renderer,
         runtime,
         ev_proxy,
         debug,
         receiver,
-        egl_surfaces,
+        control_sender,
+        backend_surfaces,
         surface_ids,
-        display,
-        context,
-        config,
         init_command,
         exit_on_close_request,
+        draw_before_configure,
+        debug_overlay,
+        init_size,
         if is_layer_surface {
             SurfaceIdWrapper::LayerSurface(native_id.inner())
         } else {
@@ -247,6 +500,8 @@ where
     ));
 
     let mut context = task::Context::from_waker(task::noop_waker_ref());
+    // the control flow last requested by the instance
+    let mut requested_control_flow = ControlFlow::Wait;
 
     let _ = event_loop.run_return(move |event, event_loop, control_flow| {
         if let ControlFlow::ExitWithCode(_) = control_flow {
@@ -258,52 +513,118 @@ where
         let poll = instance.as_mut().poll(&mut context);
 
         *control_flow = match poll {
-            task::Poll::Pending => ControlFlow::Wait,
-            task::Poll::Ready(_) => ControlFlow::ExitWithCode(1),
+            task::Poll::Pending => {
+                while let Ok(Some(flow)) = control_receiver.try_next() {
+                    requested_control_flow = flow;
+                }
+                event_loop.control_flow(requested_control_flow)
+            }
+            task::Poll::Ready(Ok(())) => ControlFlow::ExitWithCode(0),
+            task::Poll::Ready(Err(_)) => ControlFlow::ExitWithCode(1),
         };
     });
 
     Ok(())
 }
 
-fn subscription_map<A, E, C>(e: A::Message) -> Event<A::Message>
+/// Keeps the event loop busy while redraws are outstanding, so that animations are not
+/// stalled, and lets it sleep until the next event otherwise. The event loop refines it with
+/// [`SctkState::control_flow`] once it knows what the redraws wait for.
+fn control_flow_for(pending_redraws: &HashSet<ObjectId>) -> ControlFlow {
+    if pending_redraws.is_empty() {
+        ControlFlow::Wait
+    } else {
+        ControlFlow::Poll
+    }
+}
+
+/// Updates the modifiers known to a surface to the ones of the keyboard, and returns the
+/// event notifying its widgets if they changed.
+fn sync_modifiers(surface: &mut Modifiers, current: Modifiers) -> Option<iced_native::Event> {
+    if *surface == current {
+        return None;
+    }
+    *surface = current;
+    Some(iced_native::Event::Keyboard(keyboard::Event::ModifiersChanged(
+        modifiers_to_native(current),
+    )))
+}
+
+/// Converts a position in surface local coordinates, already scaled by the compositor, to
+/// the logical coordinates of the [`UserInterface`] drawn in buffers with `transform`, which
+/// are left to scale by the `scale_factor` of the [`Application`].
+fn surface_to_interface(
+    transform: Transform,
+    size: Size<f64>,
+    scale_factor: f64,
+    x: f64,
+    y: f64,
+) -> (f64, f64) {
+    let (x, y) = surface_to_buffer(transform, size, x, y);
+    (x / scale_factor, y / scale_factor)
+}
+
+/// Converts a position in surface local coordinates to the orientation of the buffers, in
+/// which the [`UserInterface`] is drawn. `size` is the size of the surface.
+///
+/// <https://wayland.app/protocols/wayland#wl_surface:request:set_buffer_transform>
+fn surface_to_buffer(transform: Transform, size: Size<f64>, x: f64, y: f64) -> (f64, f64) {
+    let Size { width, height } = size;
+    match transform {
+        Transform::_90 => (y, width - x),
+        Transform::_180 => (width - x, height - y),
+        Transform::_270 => (height - y, x),
+        Transform::Flipped => (width - x, y),
+        Transform::Flipped90 => (y, x),
+        Transform::Flipped180 => (x, height - y),
+        Transform::Flipped270 => (height - y, width - x),
+        _ => (x, y),
+    }
+}
+
+fn subscription_map<A, E, B>(e: A::Message) -> Event<A::Message>
 where
     A: Application + 'static,
     E: Executor + 'static,
-    C: window::GLCompositor<Renderer = A::Renderer> + 'static,
+    B: Backend<Renderer = A::Renderer> + 'static,
     <A::Renderer as iced_native::Renderer>::Theme: StyleSheet,
 {
     Event::SctkEvent(IcedSctkEvent::UserEvent(e))
 }
 
-// XXX Ashley careful, A, E, C must be exact same as in update, or the subscription map type will have a different hash
-async fn run_instance<A, E, C>(
+// XXX Ashley careful, A, E, B must be exact same as in update, or the subscription map type will have a different hash
+async fn run_instance<A, E, B>(
     mut application: A,
-    mut compositor: C,
+    mut backend: B,
+    reconnect_settings: B::Settings,
     mut renderer: A::Renderer,
     mut runtime: Runtime<E, proxy::Proxy<Event<A::Message>>, Event<A::Message>>,
     mut ev_proxy: proxy::Proxy<Event<A::Message>>,
     mut debug: Debug,
     mut receiver: mpsc::UnboundedReceiver<IcedSctkEvent<A::Message>>,
-    mut egl_surfaces: HashMap<SurfaceId, glutin::api::egl::surface::Surface<WindowSurface>>,
+    mut control_sender: mpsc::UnboundedSender<ControlFlow>,
+    mut backend_surfaces: HashMap<SurfaceId, B::Surface>,
     mut surface_ids: HashMap<ObjectId, SurfaceIdWrapper>,
-    mut egl_display: egl::display::Display,
-    mut egl_context: egl::context::PossiblyCurrentContext,
-    mut egl_config: glutin::api::egl::config::Config,
     init_command: Command<A::Message>,
     exit_on_close_request: bool,
+    draw_before_configure: bool,
+    debug_overlay: settings::DebugOverlay,
+    init_size: Option<(u32, u32)>,
     init_id: SurfaceIdWrapper,
 ) -> Result<(), Error>
 where
     A: Application + 'static,
     E: Executor + 'static,
-    C: window::GLCompositor<Renderer = A::Renderer> + 'static,
+    B: Backend<Renderer = A::Renderer> + 'static,
     <A::Renderer as iced_native::Renderer>::Theme: StyleSheet,
 {
     let mut cache = user_interface::Cache::default();
 
     let init_id_inner = init_id.inner();
-    let state = State::new(&application, init_id);
+    let mut state = State::new(&application, init_id);
+    if let Some((width, height)) = init_size {
+        state.set_logical_size(width as f64, height as f64);
+    }
 
     let user_interface = build_user_interface(
         &application,
@@ -313,6 +634,15 @@ where
         &mut debug,
         init_id,
     );
+    ev_proxy.send_event(Event::SetOpaque {
+        id: init_id_inner,
+        opaque: state.is_opaque(),
+    });
+    if let SurfaceIdWrapper::Window(id) = init_id {
+        let decorations = application.decorations(id);
+        ev_proxy.send_event(Event::SetDecorations { id, decorations });
+    }
+    let mut frame_timer = FrameTimer::default();
     let mut states = HashMap::from([(init_id_inner, state)]);
     let mut interfaces = ManuallyDrop::new(HashMap::from([(init_id_inner, user_interface)]));
 
@@ -328,21 +658,33 @@ where
             &mut runtime,
             &mut ev_proxy,
             &mut debug,
-            || compositor.fetch_information(),
+            &frame_timer,
+            || backend.fetch_information(),
         );
     }
-    runtime.track(application.subscription().map(subscription_map::<A, E, C>));
+    runtime.track(application.subscription().map(subscription_map::<A, E, B>));
 
     let mut mouse_interaction = mouse::Interaction::default();
     let mut events: Vec<SctkEvent> = Vec::new();
     let mut messages: Vec<A::Message> = Vec::new();
     debug.startup_finished();
 
-    let mut current_context_window = init_id_inner;
-
     let mut kbd_surface_id: Option<ObjectId> = None;
+    // the surface the pointer is over
+    let mut ptr_surface_id: Option<ObjectId> = None;
     let mut mods = Modifiers::default();
     let mut destroyed_surface_ids: HashMap<ObjectId, SurfaceIdWrapper> = Default::default();
+    // surfaces with a requested redraw, which has not been drawn yet
+    let mut pending_redraws: HashSet<ObjectId> = HashSet::new();
+    // the buffer scales of the surfaces, which are set right before the next buffer drawn
+    // at that scale is committed, as the size of a buffer must be a multiple of its scale
+    let mut pending_buffer_scales: HashMap<ObjectId, (WlSurface, i32)> = HashMap::new();
+    // the buffer transforms of the surfaces, which are set with the next buffer drawn for them
+    let mut pending_buffer_transforms: HashMap<ObjectId, (WlSurface, Transform)> =
+        HashMap::new();
+    // whether the last surface was closed with `exit_on_close_request`, which exits unless
+    // the application vetoes it
+    let mut last_surface_closed = false;
 
     'main: while let Some(event) = receiver.next().await {
         match event {
@@ -351,6 +693,10 @@ where
                 messages.push(message);
             }
             IcedSctkEvent::SctkEvent(event) => {
+                let event = match filter_key_event(&mut application, event, mods) {
+                    Some(event) => event,
+                    None => continue,
+                };
                 events.push(event.clone());
                 match event {
                     SctkEvent::SeatEvent { .. } => {} // TODO Ashley: handle later possibly if multiseat support is wanted
@@ -358,7 +704,29 @@ where
                         variant,
                         ptr_id,
                         seat_id,
+                        ..
                     } => {
+                        // A surface may be covered by a popup without the pointer leaving it
+                        // first, so entering another surface also leaves the previous one.
+                        if let PointerEventKind::Enter { .. } = variant.kind {
+                            let entered = variant.surface.id();
+                            if let Some(left) = ptr_surface_id
+                                .replace(entered.clone())
+                                .filter(|left| left != &entered)
+                            {
+                                if let Some(state) =
+                                    surface_ids.get(&left).and_then(|id| states.get_mut(&id.inner()))
+                                {
+                                    state.set_cursor_position(None);
+                                    if pending_redraws.insert(left.clone()) {
+                                        ev_proxy.send_event(Event::SctkEvent(
+                                            IcedSctkEvent::RedrawRequested(left),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                        let is_focused = ptr_surface_id.as_ref() == Some(&variant.surface.id());
                         let (state, _native_id) = match surface_ids
                             .get(&variant.surface.id())
                             .and_then(|id| states.get_mut(&id.inner()).map(|state| (state, id)))
@@ -368,80 +736,139 @@ where
                         };
                         match variant.kind {
                             PointerEventKind::Enter { .. } => {
-                                state.set_cursor_position(Point::new(
-                                    variant.position.0 as f32,
-                                    variant.position.1 as f32,
-                                ));
+                                state.set_surface_cursor_position(Some(variant.position));
                             }
                             PointerEventKind::Leave { .. } => {
-                                state.set_cursor_position(Point::new(-1.0, -1.0));
+                                state.set_cursor_position(None);
+                                if is_focused {
+                                    ptr_surface_id.take();
+                                }
                             }
+                            // motion of a surface which was left already arrived out of order
+                            PointerEventKind::Motion { .. } if !is_focused => {}
                             PointerEventKind::Motion { .. } => {
-                                state.set_cursor_position(Point::new(
-                                    variant.position.0 as f32,
-                                    variant.position.1 as f32,
-                                ));
+                                state.set_surface_cursor_position(Some(variant.position));
                             }
                             PointerEventKind::Press { .. }
                             | PointerEventKind::Release { .. }
                             | PointerEventKind::Axis { .. } => {}
                         }
                     }
+                    // the cursor stays where the touch point was lifted, like a mouse would
+                    SctkEvent::TouchEvent {
+                        variant:
+                            TouchEventVariant::Down { position, .. }
+                            | TouchEventVariant::Motion { position, .. },
+                        surface,
+                        primary: true,
+                        ..
+                    } => {
+                        if let Some(state) = surface_ids
+                            .get(&surface.id())
+                            .and_then(|id| states.get_mut(&id.inner()))
+                        {
+                            state.set_surface_cursor_position(Some(position));
+                        }
+                    }
+                    SctkEvent::TouchEvent { .. } => {}
                     SctkEvent::KeyboardEvent { variant, .. } => match variant {
-                        KeyboardEventVariant::Leave(_) => {
+                        // focus rings and carets change with the focus, so the surface must be
+                        // redrawn even if nothing else happens
+                        KeyboardEventVariant::Leave(surface) => {
                             kbd_surface_id.take();
+                            if pending_redraws.insert(surface.id()) {
+                                ev_proxy.send_event(Event::SctkEvent(
+                                    IcedSctkEvent::RedrawRequested(surface.id()),
+                                ));
+                            }
                         }
-                        KeyboardEventVariant::Enter(object_id) => {
-                            kbd_surface_id.replace(object_id.id());
+                        KeyboardEventVariant::Enter(surface) => {
+                            kbd_surface_id.replace(surface.id());
+                            if pending_redraws.insert(surface.id()) {
+                                ev_proxy.send_event(Event::SctkEvent(
+                                    IcedSctkEvent::RedrawRequested(surface.id()),
+                                ));
+                            }
+                        }
+                        KeyboardEventVariant::Press(key_event)
+                            if key_event.keysym == keysyms::XKB_KEY_F12
+                                && debug_overlay == settings::DebugOverlay::Toggle =>
+                        {
+                            debug.toggle();
+                            if let Some(surface) = kbd_surface_id.clone() {
+                                if pending_redraws.insert(surface.clone()) {
+                                    ev_proxy.send_event(Event::SctkEvent(
+                                        IcedSctkEvent::RedrawRequested(surface),
+                                    ));
+                                }
+                            }
                         }
                         KeyboardEventVariant::Press(_)
                         | KeyboardEventVariant::Release(_)
                         | KeyboardEventVariant::Repeat(_) => {}
-                        KeyboardEventVariant::Modifiers(mods) => {
+                        KeyboardEventVariant::Modifiers(new_mods) => {
+                            mods = new_mods;
                             if let Some(state) = kbd_surface_id
                                 .as_ref()
                                 .and_then(|id| surface_ids.get(&id))
                                 .and_then(|id| states.get_mut(&id.inner()))
                             {
-                                state.modifiers = mods;
+                                state.modifiers = new_mods;
                             }
                         }
                     },
                     SctkEvent::WindowEvent { variant, id } => match variant {
                         crate::sctk_event::WindowEventVariant::Created(id, native_id) => {
                             surface_ids.insert(id, SurfaceIdWrapper::Window(native_id));
+                            ev_proxy.send_event(Event::SetDecorations {
+                                id: native_id,
+                                decorations: application.decorations(native_id),
+                            });
+                        }
+                        crate::sctk_event::WindowEventVariant::CloseRequested => {
+                            if let Some(surface_id) = surface_ids.get(&id.id()) {
+                                messages.push(application.close_requested(*surface_id));
+                                if exit_on_close_request && !application.defer_close(*surface_id) {
+                                    ev_proxy.send_event(Event::Window(
+                                        platform_specific::wayland::window::Action::Destroy(
+                                            surface_id.inner(),
+                                        ),
+                                    ));
+                                }
+                            }
                         }
                         crate::sctk_event::WindowEventVariant::Close => {
+                            // the application was already notified with the close request
                             if let Some(surface_id) = surface_ids.remove(&id.id()) {
-                                drop(egl_surfaces.remove(&surface_id.inner()));
+                                drop(backend_surfaces.remove(&surface_id.inner()));
                                 interfaces.remove(&surface_id.inner());
                                 states.remove(&surface_id.inner());
-                                messages.push(application.close_requested(surface_id));
                                 destroyed_surface_ids.insert(id.id(), surface_id);
-                                if exit_on_close_request && surface_id == init_id {
-                                    break 'main;
+                                if exit_on_close_request && surface_ids.is_empty() {
+                                    last_surface_closed = true;
                                 }
                             }
                         }
                         crate::sctk_event::WindowEventVariant::WmCapabilities(_)
-                        | crate::sctk_event::WindowEventVariant::ConfigureBounds { .. } => {}
-                        crate::sctk_event::WindowEventVariant::Configure(
+                        | crate::sctk_event::WindowEventVariant::ConfigureBounds { .. }
+                        | crate::sctk_event::WindowEventVariant::Decorations(_)
+                        | crate::sctk_event::WindowEventVariant::ResizeFinished { .. } => {}
+                        crate::sctk_event::WindowEventVariant::Configure {
                             configure,
-                            wl_surface,
+                            surface: wl_surface,
                             first,
-                        ) => {
+                            ..
+                        } => {
                             if let Some(id) = surface_ids.get(&id.id()) {
                                 let new_size = configure.new_size.unwrap();
 
-                                if first && !egl_surfaces.contains_key(&id.inner()) {
-                                    let egl_surface = get_surface(
-                                        &egl_display,
-                                        &egl_config,
+                                if first && !backend_surfaces.contains_key(&id.inner()) {
+                                    let surface = backend.create_surface(
                                         &wl_surface,
                                         new_size.0,
                                         new_size.1,
                                     );
-                                    egl_surfaces.insert(id.inner(), egl_surface);
+                                    backend_surfaces.insert(id.inner(), surface);
                                     let state = State::new(&application, *id);
 
                                     let user_interface = build_user_interface(
@@ -452,6 +879,10 @@ where
                                         &mut debug,
                                         *id,
                                     );
+                                    ev_proxy.send_event(Event::SetOpaque {
+                                        id: id.inner(),
+                                        opaque: state.is_opaque(),
+                                    });
                                     states.insert(id.inner(), state);
                                     interfaces.insert(id.inner(), user_interface);
                                 }
@@ -462,32 +893,62 @@ where
                         }
                     },
                     SctkEvent::LayerSurfaceEvent { variant, id } => match variant {
-                        LayerSurfaceEventVariant::Created(id, native_id) => {
-                            surface_ids.insert(id, SurfaceIdWrapper::LayerSurface(native_id));
+                        LayerSurfaceEventVariant::Created(object_id, native_id, size) => {
+                            let surface_id = SurfaceIdWrapper::LayerSurface(native_id);
+                            surface_ids.insert(object_id, surface_id);
+                            // prepared at the fixed size, and drawn with the first configure
+                            if let Some((width, height)) = size.filter(|_| draw_before_configure) {
+                                let surface = backend.create_surface(&id, width, height);
+                                backend_surfaces.insert(native_id, surface);
+                                let mut state = State::new(&application, surface_id);
+                                state.set_logical_size(width as f64, height as f64);
+                                let user_interface = build_user_interface(
+                                    &application,
+                                    user_interface::Cache::default(),
+                                    &mut renderer,
+                                    state.logical_size(),
+                                    &mut debug,
+                                    surface_id,
+                                );
+                                ev_proxy.send_event(Event::SetOpaque {
+                                    id: native_id,
+                                    opaque: state.is_opaque(),
+                                });
+                                states.insert(native_id, state);
+                                interfaces.insert(native_id, user_interface);
+                            }
                         }
                         LayerSurfaceEventVariant::Done => {
                             if let Some(surface_id) = surface_ids.remove(&id.id()) {
-                                drop(egl_surfaces.remove(&surface_id.inner()));
+                                drop(backend_surfaces.remove(&surface_id.inner()));
                                 interfaces.remove(&surface_id.inner());
                                 states.remove(&surface_id.inner());
                                 messages.push(application.close_requested(surface_id));
                                 destroyed_surface_ids.insert(id.id(), surface_id);
-                                if exit_on_close_request && surface_id == init_id {
-                                    break 'main;
+                                if exit_on_close_request && surface_ids.is_empty() {
+                                    last_surface_closed = true;
                                 }
                             }
                         }
-                        LayerSurfaceEventVariant::Configure(configure, wl_surface, first) => {
+                        // the application is expected to recreate the surface on another output,
+                        // so losing it doesn't end the application
+                        LayerSurfaceEventVariant::OutputRemoved(_) => {
+                            if let Some(surface_id) = surface_ids.remove(&id.id()) {
+                                drop(backend_surfaces.remove(&surface_id.inner()));
+                                interfaces.remove(&surface_id.inner());
+                                states.remove(&surface_id.inner());
+                                destroyed_surface_ids.insert(id.id(), surface_id);
+                            }
+                        }
+                        LayerSurfaceEventVariant::Configure(configure, wl_surface, first, _) => {
                             if let Some(id) = surface_ids.get(&id.id()) {
-                                if first && !egl_surfaces.contains_key(&id.inner()) {
-                                    let egl_surface = get_surface(
-                                        &egl_display,
-                                        &egl_config,
+                                if first && !backend_surfaces.contains_key(&id.inner()) {
+                                    let surface = backend.create_surface(
                                         &wl_surface,
                                         configure.new_size.0,
                                         configure.new_size.1,
                                     );
-                                    egl_surfaces.insert(id.inner(), egl_surface);
+                                    backend_surfaces.insert(id.inner(), surface);
                                     let state = State::new(&application, *id);
 
                                     let user_interface = build_user_interface(
@@ -498,6 +959,10 @@ where
                                         &mut debug,
                                         *id,
                                     );
+                                    ev_proxy.send_event(Event::SetOpaque {
+                                        id: id.inner(),
+                                        opaque: state.is_opaque(),
+                                    });
                                     states.insert(id.inner(), state);
                                     interfaces.insert(id.inner(), user_interface);
                                 }
@@ -521,25 +986,26 @@ where
                         }
                         PopupEventVariant::Done => {
                             if let Some(surface_id) = surface_ids.remove(&id.id()) {
-                                drop(egl_surfaces.remove(&surface_id.inner()));
+                                drop(backend_surfaces.remove(&surface_id.inner()));
                                 interfaces.remove(&surface_id.inner());
                                 states.remove(&surface_id.inner());
                                 messages.push(application.close_requested(surface_id));
                                 destroyed_surface_ids.insert(id.id(), surface_id);
+                                if exit_on_close_request && surface_ids.is_empty() {
+                                    last_surface_closed = true;
+                                }
                             }
                         }
                         PopupEventVariant::WmCapabilities(_) => {}
                         PopupEventVariant::Configure(configure, wl_surface, first) => {
                             if let Some(id) = surface_ids.get(&id.id()) {
-                                if first && !egl_surfaces.contains_key(&id.inner()) {
-                                    let egl_surface = get_surface(
-                                        &egl_display,
-                                        &egl_config,
+                                if first && !backend_surfaces.contains_key(&id.inner()) {
+                                    let surface = backend.create_surface(
                                         &wl_surface,
                                         configure.width as u32,
                                         configure.height as u32,
                                     );
-                                    egl_surfaces.insert(id.inner(), egl_surface);
+                                    backend_surfaces.insert(id.inner(), surface);
                                     let state = State::new(&application, *id);
 
                                     let user_interface = build_user_interface(
@@ -550,6 +1016,10 @@ where
                                         &mut debug,
                                         *id,
                                     );
+                                    ev_proxy.send_event(Event::SetOpaque {
+                                        id: id.inner(),
+                                        opaque: state.is_opaque(),
+                                    });
                                     states.insert(id.inner(), state);
                                     interfaces.insert(id.inner(), user_interface);
                                 }
@@ -563,6 +1033,54 @@ where
                         }
                         PopupEventVariant::RepositionionedPopup { .. } => {}
                     },
+                    SctkEvent::CustomSurfaceEvent { variant, id } => match variant {
+                        CustomSurfaceEventVariant::Created(id, native_id) => {
+                            surface_ids.insert(id, SurfaceIdWrapper::Custom(native_id));
+                        }
+                        CustomSurfaceEventVariant::Done => {
+                            if let Some(surface_id) = surface_ids.remove(&id.id()) {
+                                drop(backend_surfaces.remove(&surface_id.inner()));
+                                interfaces.remove(&surface_id.inner());
+                                states.remove(&surface_id.inner());
+                                messages.push(application.close_requested(surface_id));
+                                destroyed_surface_ids.insert(id.id(), surface_id);
+                                if exit_on_close_request && surface_ids.is_empty() {
+                                    last_surface_closed = true;
+                                }
+                            }
+                        }
+                        CustomSurfaceEventVariant::Configure(new_size, wl_surface, first) => {
+                            if let Some(id) = surface_ids.get(&id.id()) {
+                                if first && !backend_surfaces.contains_key(&id.inner()) {
+                                    let surface = backend.create_surface(
+                                        &wl_surface,
+                                        new_size.0,
+                                        new_size.1,
+                                    );
+                                    backend_surfaces.insert(id.inner(), surface);
+                                    let state = State::new(&application, *id);
+
+                                    let user_interface = build_user_interface(
+                                        &application,
+                                        user_interface::Cache::default(),
+                                        &mut renderer,
+                                        state.logical_size(),
+                                        &mut debug,
+                                        *id,
+                                    );
+                                    ev_proxy.send_event(Event::SetOpaque {
+                                        id: id.inner(),
+                                        opaque: state.is_opaque(),
+                                    });
+                                    states.insert(id.inner(), state);
+                                    interfaces.insert(id.inner(), user_interface);
+                                }
+                                if let Some(state) = states.get_mut(&id.inner()) {
+                                    state.set_logical_size(new_size.0 as f64, new_size.1 as f64);
+                                }
+                            }
+                        }
+                    },
                     // TODO forward these events to an application which requests them?
                     SctkEvent::NewOutput { id, info } => {
                         events.push(SctkEvent::NewOutput { id, info });
@@ -574,22 +1092,116 @@ where
                         events.push(SctkEvent::RemovedOutput(id));
                     }
                     SctkEvent::Draw(_) => unimplemented!(), // probably should never be forwarded here...
-                    SctkEvent::ScaleFactorChanged {
-                        factor,
-                        id,
-                        inner_size: _,
-                    } => {
+                    SctkEvent::BufferReleased { .. } => {}
+                    SctkEvent::SurfaceOutputsChanged { .. } => {}
+                    SctkEvent::BufferTransformChanged { id, transform } => {
                         if let Some(state) = surface_ids
                             .get(&id.id())
                             .and_then(|id| states.get_mut(&id.inner()))
                         {
+                            // the transform is committed with the buffer of the new size
+                            state.set_buffer_transform(transform);
+                            pending_buffer_transforms.insert(id.id(), (id.clone(), transform));
+                            if pending_redraws.insert(id.id()) {
+                                ev_proxy.send_event(Event::SctkEvent(
+                                    IcedSctkEvent::RedrawRequested(id.id()),
+                                ));
+                            }
+                        }
+                    }
+                    SctkEvent::SurfaceRecreateRequested { id: wl_surface } => {
+                        if let Some(id) = surface_ids.get(&wl_surface.id()) {
+                            // surfaces which were not configured yet have nothing to recover
+                            if let (Some(state), Some(surface)) =
+                                (states.get(&id.inner()), backend_surfaces.remove(&id.inner()))
+                            {
+                                drop(surface);
+                                let size = state.physical_size();
+                                let surface = backend.create_surface(
+                                    &wl_surface,
+                                    size.width.max(1),
+                                    size.height.max(1),
+                                );
+                                backend_surfaces.insert(id.inner(), surface);
+                                if pending_redraws.insert(wl_surface.id()) {
+                                    ev_proxy.send_event(Event::SctkEvent(
+                                        IcedSctkEvent::RedrawRequested(wl_surface.id()),
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    SctkEvent::ScaleFactorChanged { factor, id } => {
+                        if let Some(state) = surface_ids
+                            .get(&id.id())
+                            .and_then(|id| states.get_mut(&id.inner()))
+                        {
+                            // the new size of the buffer is committed with the new scale
                             state.set_scale_factor(factor);
+                            pending_buffer_scales.insert(id.id(), (id.clone(), factor as i32));
+                            if pending_redraws.insert(id.id()) {
+                                ev_proxy.send_event(Event::SctkEvent(
+                                    IcedSctkEvent::RedrawRequested(id.id()),
+                                ));
+                            }
+                        }
+                    }
+                    SctkEvent::DndOffer { .. } | SctkEvent::DndSource(_) => {}
+                    SctkEvent::Reconnected(surfaces) => {
+                        // surfaces that could not be recreated are gone for good
+                        let recreated: Vec<_> =
+                            surfaces.iter().map(|(_, id, _)| id.inner()).collect();
+                        for (object_id, surface_id) in surface_ids.drain() {
+                            if !recreated.contains(&surface_id.inner()) {
+                                interfaces.remove(&surface_id.inner());
+                                states.remove(&surface_id.inner());
+                                messages.push(application.close_requested(surface_id));
+                                destroyed_surface_ids.insert(object_id, surface_id);
+                            }
+                        }
+                        // the render targets belong to the old connection
+                        backend_surfaces.clear();
+
+                        for (object_id, id, _) in &surfaces {
+                            surface_ids.insert(object_id.clone(), *id);
+                        }
+                        // surfaces without a state are set up once they are configured
+                        let mut surfaces = surfaces
+                            .into_iter()
+                            .filter(|(_, id, _)| states.contains_key(&id.inner()));
+                        if let Some((_, id, wl_surface)) = surfaces.next() {
+                            let size = states[&id.inner()].physical_size();
+                            let (new_backend, new_renderer, surface) = B::new(
+                                B::reconnect_settings(&reconnect_settings),
+                                &wl_surface,
+                                size.width.max(1),
+                                size.height.max(1),
+                            )?;
+                            backend = new_backend;
+                            renderer = new_renderer;
+                            backend_surfaces.insert(id.inner(), surface);
+
+                            for (_, id, wl_surface) in surfaces {
+                                let size = states[&id.inner()].physical_size();
+                                let surface = backend.create_surface(
+                                    &wl_surface,
+                                    size.width.max(1),
+                                    size.height.max(1),
+                                );
+                                backend_surfaces.insert(id.inner(), surface);
+                            }
+                        }
+                        for state in states.values_mut() {
+                            state.viewport_changed = true;
+                        }
+                        if let Some(message) = application.reconnected() {
+                            messages.push(message);
                         }
                     }
                 }
             }
             IcedSctkEvent::MainEventsCleared => {
-                if surface_ids.is_empty() && !messages.is_empty() {
+                if surface_ids.is_empty() && (!messages.is_empty() || last_surface_closed) {
                     // Update application
                     let pure_states: HashMap<_, _> = ManuallyDrop::into_inner(interfaces)
                         .drain()
@@ -597,7 +1209,7 @@ where
                         .collect();
 
                     // Update application
-                    update::<A, E, C>(
+                    update::<A, E, B>(
                         &mut application,
                         &mut cache,
                         None,
@@ -605,8 +1217,9 @@ where
                         &mut runtime,
                         &mut ev_proxy,
                         &mut debug,
+                        &mut frame_timer,
                         &mut messages,
-                        || compositor.fetch_information(),
+                        || backend.fetch_information(),
                     );
 
                     interfaces = ManuallyDrop::new(build_user_interfaces(
@@ -617,30 +1230,71 @@ where
                         pure_states,
                     ));
 
-                    if application.should_exit() {
-                        break 'main;
+                    if application.should_exit() || std::mem::take(&mut last_surface_closed) {
+                        match application.veto_exit() {
+                            Some(command) => run_command(
+                                &application,
+                                &mut cache,
+                                None,
+                                &mut renderer,
+                                command,
+                                &mut runtime,
+                                &mut ev_proxy,
+                                &mut debug,
+                                &frame_timer,
+                                || backend.fetch_information(),
+                            ),
+                            None => break 'main,
+                        }
                     }
                 } else {
+                    // a surface was created after the last one was closed
+                    last_surface_closed = false;
                     let mut needs_redraw = false;
                     for (object_id, surface_id) in &surface_ids {
                         // returns (remove, copy)
                         let filter_events = |e: &SctkEvent| match e {
+                            SctkEvent::SeatEvent {
+                                variant:
+                                    SeatEventVariant::Idle
+                                    | SeatEventVariant::Resumed
+                                    | SeatEventVariant::Selection { .. }
+                                    | SeatEventVariant::KeyboardLayout(_),
+                                ..
+                            } => (false, true),
                             SctkEvent::SeatEvent { id, .. } => (&id.id() == object_id, false),
                             SctkEvent::PointerEvent { variant, .. } => {
                                 (&variant.surface.id() == object_id, false)
                             }
-                            SctkEvent::KeyboardEvent { variant, .. } => match variant {
-                                KeyboardEventVariant::Leave(id) => (&id.id() == object_id, false),
-                                _ => (kbd_surface_id.as_ref() == Some(&object_id), false),
-                            },
+                            // key events belong to the surface which was focused when they were
+                            // received, focus may have moved on since
+                            SctkEvent::KeyboardEvent {
+                                variant:
+                                    KeyboardEventVariant::Leave(id) | KeyboardEventVariant::Enter(id),
+                                ..
+                            } => (&id.id() == object_id, false),
+                            SctkEvent::KeyboardEvent { surface, .. } => {
+                                (surface.as_ref().map(|s| s.id()).as_ref() == Some(object_id), false)
+                            }
+                            SctkEvent::TouchEvent { surface, .. } => (&surface.id() == object_id, false),
                             SctkEvent::WindowEvent { id, .. } => (&id.id() == object_id, false),
                             SctkEvent::LayerSurfaceEvent { id, .. } => (&id.id() == object_id, false),
                             SctkEvent::PopupEvent { id, .. } => (&id.id() == object_id, false),
+                            SctkEvent::CustomSurfaceEvent { id, .. } => {
+                                (&id.id() == object_id, false)
+                            }
                             SctkEvent::NewOutput { .. }
                             | SctkEvent::UpdateOutput { .. }
                             | SctkEvent::RemovedOutput(_) => (false, true),
                             SctkEvent::Draw(_) => unimplemented!(),
+                            SctkEvent::BufferReleased { id, .. } => (&id.id() == object_id, false),
                             SctkEvent::ScaleFactorChanged { id, .. } => (&id.id() == object_id, false),
+                            SctkEvent::BufferTransformChanged { id, .. } => (&id.id() == object_id, false),
+                            SctkEvent::SurfaceRecreateRequested { id } => (&id.id() == object_id, false),
+                            SctkEvent::SurfaceOutputsChanged { id, .. } => (&id.id() == object_id, false),
+                            SctkEvent::DndOffer { surface, .. } => (&surface.id() == object_id, false),
+                            SctkEvent::DndSource(_) => (false, true),
+                            SctkEvent::Reconnected(_) => (false, false),
                         };
                         let mut filtered = Vec::with_capacity(events.len());
                         let mut i = 0;
@@ -656,24 +1310,54 @@ where
                                 i += 1;
                             }
                         }
-                        let cursor_position = match states.get(&surface_id.inner()) {
-                            Some(s) => s.cursor_position(),
+                        let state = match states.get_mut(&surface_id.inner()) {
+                            Some(s) => s,
                             None => continue,
                         };
                         if filtered.is_empty() && messages.is_empty() {
                             continue;
-                        } else {
+                        } else if pending_redraws.insert(object_id.clone()) {
                             ev_proxy.send_event(Event::SctkEvent(IcedSctkEvent::RedrawRequested(
                                 object_id.clone(),
                             )));
                         }
+                        let cursor_position = state.hit_test_position();
                         debug.event_processing_started();
-                        let native_events: Vec<_> = filtered
-                            .into_iter()
-                            .flat_map(|e| {
-                                e.to_native(&mut mods, &surface_ids, &destroyed_surface_ids)
-                            })
-                            .collect();
+                        let mut native_events = Vec::with_capacity(filtered.len());
+                        // the modifiers may have changed while another surface had keyboard focus,
+                        // so make sure they are up to date before any pointer events are handled
+                        native_events.extend(sync_modifiers(&mut state.modifiers, mods));
+                        native_events.extend(
+                            filtered
+                                .into_iter()
+                                .flat_map(|e| {
+                                    e.to_native(&mut mods, &surface_ids, &destroyed_surface_ids)
+                                })
+                                .map(|e| match e {
+                                    iced_native::Event::Mouse(mouse::Event::CursorMoved {
+                                        position,
+                                    }) => iced_native::Event::Mouse(mouse::Event::CursorMoved {
+                                        position: state.surface_to_logical(position),
+                                    }),
+                                    iced_native::Event::Touch(e) => {
+                                        iced_native::Event::Touch(touch_to_logical(state, e))
+                                    }
+                                    iced_native::Event::PlatformSpecific(
+                                        PlatformSpecific::Wayland(wayland::Event::DndOffer(
+                                            e,
+                                            surface,
+                                            id,
+                                        )),
+                                    ) => iced_native::Event::PlatformSpecific(
+                                        PlatformSpecific::Wayland(wayland::Event::DndOffer(
+                                            dnd_offer_to_logical(state, e),
+                                            surface,
+                                            id,
+                                        )),
+                                    ),
+                                    e => e,
+                                }),
+                        );
                         let (interface_state, statuses) = {
                             let user_interface = interfaces.get_mut(&surface_id.inner()).unwrap();
                             user_interface.update(
@@ -685,6 +1369,7 @@ where
                             )
                         };
                         debug.event_processing_finished();
+                        frame_timer.stats.event_processing = debug.event_processing_duration();
                         for event in native_events.into_iter().zip(statuses.into_iter()) {
                             runtime.broadcast(event);
                         }
@@ -700,7 +1385,7 @@ where
                             .map(|(id, interface)| (id, interface.into_cache()))
                             .collect();
 
-                        for (_object_id, surface_id) in &surface_ids {
+                        for (object_id, surface_id) in &surface_ids {
                             let state = match states.get_mut(&surface_id.inner()) {
                                 Some(s) => s,
                                 None => continue,
@@ -711,7 +1396,7 @@ where
                             };
 
                             // Update application
-                            update::<A, E, C>(
+                            update::<A, E, B>(
                                 &mut application,
                                 cache,
                                 Some(state),
@@ -719,15 +1404,41 @@ where
                                 &mut runtime,
                                 &mut ev_proxy,
                                 &mut debug,
+                                &mut frame_timer,
                                 &mut messages,
-                                || compositor.fetch_information(),
+                                || backend.fetch_information(),
                             );
 
                             // Update state
-                            state.synchronize(&application);
+                            if state.synchronize(&application) {
+                                // the background switched between opaque and transparent,
+                                // so the compositor's view of the surface is stale
+                                ev_proxy.send_event(Event::SetOpaque {
+                                    id: surface_id.inner(),
+                                    opaque: state.is_opaque(),
+                                });
+                                ev_proxy.send_event(Event::SctkEvent(
+                                    IcedSctkEvent::RedrawRequested(object_id.clone()),
+                                ));
+                                pending_redraws.insert(object_id.clone());
+                            }
 
                             if application.should_exit() {
-                                break 'main;
+                                match application.veto_exit() {
+                                    Some(command) => run_command(
+                                        &application,
+                                        cache,
+                                        Some(state),
+                                        &mut renderer,
+                                        command,
+                                        &mut runtime,
+                                        &mut ev_proxy,
+                                        &mut debug,
+                                        &frame_timer,
+                                        || backend.fetch_information(),
+                                    ),
+                                    None => break 'main,
+                                }
                             }
                         }
                         interfaces = ManuallyDrop::new(build_user_interfaces(
@@ -742,11 +1453,18 @@ where
                 events.clear();
                 // clear the destroyed surfaces after they have been handled
                 destroyed_surface_ids.clear();
+                pending_redraws.retain(|id| surface_ids.contains_key(id));
+                pending_buffer_scales.retain(|id, _| surface_ids.contains_key(id));
+                pending_buffer_transforms.retain(|id, _| surface_ids.contains_key(id));
+                let _ = control_sender.start_send(control_flow_for(&pending_redraws));
             }
             IcedSctkEvent::RedrawRequested(id) => {
-                if let Some((native_id, Some(egl_surface), Some(mut user_interface), Some(state))) =
+                if pending_redraws.remove(&id) {
+                    let _ = control_sender.start_send(control_flow_for(&pending_redraws));
+                }
+                if let Some((native_id, Some(surface), Some(mut user_interface), Some(state))) =
                     surface_ids.get(&id).map(|id| {
-                        let surface = egl_surfaces.get_mut(&id.inner());
+                        let surface = backend_surfaces.get_mut(&id.inner());
                         let interface = interfaces.remove(&id.inner());
                         let state = states.get_mut(&id.inner());
                         (*id, surface, interface, state)
@@ -754,15 +1472,6 @@ where
                 {
                     debug.render_started();
 
-                    if current_context_window != native_id.inner() {
-                        if egl_context.make_current(egl_surface).is_ok() {
-                            current_context_window = native_id.inner();
-                        } else {
-                            interfaces.insert(native_id.inner(), user_interface);
-                            continue;
-                        }
-                    }
-
                     if state.viewport_changed() {
                         let physical_size = state.physical_size();
                         let logical_size = state.logical_size();
@@ -778,33 +1487,54 @@ where
                             &renderer::Style {
                                 text_color: state.text_color(),
                             },
-                            state.cursor_position(),
+                            state.hit_test_position(),
                         );
                         debug.draw_finished();
+                        frame_timer.stats.draw = debug.draw_duration();
                         ev_proxy.send_event(Event::SetCursor(new_mouse_interaction));
 
-                        egl_surface.resize(
-                            &egl_context,
-                            NonZeroU32::new(physical_size.width).unwrap(),
-                            NonZeroU32::new(physical_size.height).unwrap(),
-                        );
-
-                        compositor.resize_viewport(physical_size);
+                        // the swapchain of the backend only reuses a buffer once the
+                        // compositor released it, the release of the shm buffers attached by
+                        // the crate itself is tracked by `SctkState::acquire_shm_buffer`
+                        backend.configure_surface(surface, physical_size);
 
                         let _ = interfaces.insert(native_id.inner(), user_interface);
                     } else {
                         interfaces.insert(native_id.inner(), user_interface);
                     }
 
-                    compositor.present(
+                    if let Some((wl_surface, scale)) = pending_buffer_scales.remove(&id) {
+                        wl_surface.set_buffer_scale(scale);
+                    }
+                    if let Some((wl_surface, transform)) = pending_buffer_transforms.remove(&id) {
+                        wl_surface.set_buffer_transform(transform);
+                    }
+
+                    match backend.present(
                         &mut renderer,
+                        surface,
                         state.viewport(),
                         state.background_color(),
                         &debug.overlay(),
-                    );
-                    let _ = egl_surface.swap_buffers(&egl_context);
+                    ) {
+                        Ok(()) | Err(window::SurfaceError::Timeout) => {}
+                        Err(window::SurfaceError::OutOfMemory) => {
+                            // the frame is dropped, the next redraw may succeed again
+                            log::error!("failed to present surface {:?}: out of memory", native_id);
+                        }
+                        Err(error) => {
+                            // the render target is stale, so draw it again from scratch
+                            log::warn!("failed to present surface {:?}: {:?}", native_id, error);
+                            state.viewport_changed = true;
+                            ev_proxy.send_event(Event::SctkEvent(
+                                IcedSctkEvent::RedrawRequested(id.clone()),
+                            ));
+                            pending_redraws.insert(id.clone());
+                        }
+                    }
 
                     debug.render_finished();
+                    frame_timer.presented(native_id.inner(), &debug);
                 }
             }
             IcedSctkEvent::RedrawEventsCleared => {
@@ -822,6 +1552,10 @@ pub enum SurfaceIdWrapper {
     LayerSurface(SurfaceId),
     Window(SurfaceId),
     Popup(SurfaceId),
+    /// a surface with a role provided by a [`SurfaceRoleHandler`]
+    ///
+    /// [`SurfaceRoleHandler`]: crate::event_loop::custom_surface::SurfaceRoleHandler
+    Custom(SurfaceId),
 }
 
 impl SurfaceIdWrapper {
@@ -830,6 +1564,7 @@ impl SurfaceIdWrapper {
             SurfaceIdWrapper::LayerSurface(id) => *id,
             SurfaceIdWrapper::Window(id) => *id,
             SurfaceIdWrapper::Popup(id) => *id,
+            SurfaceIdWrapper::Custom(id) => *id,
         }
     }
 }
@@ -866,10 +1601,18 @@ where
 {
     pub(crate) id: SurfaceIdWrapper,
     title: String,
-    scale_factor: f64,
+    application_scale_factor: f64,
+    compositor_scale_factor: f64,
+    /// the transform of the buffers, which swaps their width and height if they are rotated
+    /// by 90 or 270 degrees
+    transform: Transform,
+    /// the size of the surface in the logical coordinates of the compositor
+    surface_size: Size<f64>,
     pub(crate) viewport: Viewport,
     viewport_changed: bool,
-    cursor_position: Point,
+    /// the position of the pointer on the surface in logical coordinates, or `None` if it
+    /// isn't over it, in double precision until it reaches the widgets
+    cursor_position: Option<(f64, f64)>,
     modifiers: Modifiers,
     theme: <A::Renderer as crate::Renderer>::Theme,
     appearance: application::Appearance,
@@ -883,20 +1626,25 @@ where
     /// Creates a new [`State`] for the provided [`Application`]
     pub fn new(application: &A, id: SurfaceIdWrapper) -> Self {
         let title = application.title();
-        let scale_factor = application.scale_factor();
-        let theme = application.theme();
+        let application_scale_factor = application.surface_scale_factor(id);
+        let theme = application.surface_theme(id);
         let appearance = theme.appearance(&application.style());
 
-        let viewport = Viewport::with_physical_size(Size::new(1, 1), 1.0);
+        let viewport = Viewport::with_physical_size(Size::new(1, 1), application_scale_factor);
 
         Self {
             id,
             title,
-            scale_factor,
+            application_scale_factor,
+            compositor_scale_factor: 1.0,
+            transform: Transform::Normal,
+            surface_size: Size {
+                width: 1.0,
+                height: 1.0,
+            },
             viewport,
             viewport_changed: false,
-            // TODO: Encode cursor availability in the type-system
-            cursor_position: Point::new(-1.0, -1.0),
+            cursor_position: None,
             modifiers: Modifiers::default(),
             theme,
             appearance,
@@ -924,18 +1672,14 @@ where
         self.viewport.logical_size()
     }
 
-    /// Sets the logical [`Size`] of the [`Viewport`] of the [`State`].
+    /// Sets the logical [`Size`] of the surface of the [`State`].
     pub fn set_logical_size(&mut self, w: f64, h: f64) {
-        let old_size = self.viewport.logical_size();
-        if w != old_size.width.into() || h != old_size.height.into() {
-            self.viewport_changed = true;
-            self.viewport = Viewport::with_physical_size(
-                Size {
-                    width: (w * self.scale_factor) as u32,
-                    height: (h * self.scale_factor) as u32,
-                },
-                self.scale_factor,
-            );
+        if w != self.surface_size.width || h != self.surface_size.height {
+            self.surface_size = Size {
+                width: w,
+                height: h,
+            };
+            self.update_viewport();
         }
     }
 
@@ -944,23 +1688,62 @@ where
         self.viewport.scale_factor()
     }
 
+    /// Sets the scale factor of the compositor for the surface of the [`State`].
     pub fn set_scale_factor(&mut self, scale_factor: f64) {
-        if scale_factor != self.scale_factor {
-            self.viewport_changed = true;
-            let logical_size = self.viewport.logical_size();
-            self.viewport = Viewport::with_physical_size(
-                Size {
-                    width: (logical_size.width as f64 * scale_factor) as u32,
-                    height: (logical_size.height as f64 * scale_factor) as u32,
-                },
-                self.scale_factor,
-            );
+        if scale_factor != self.compositor_scale_factor {
+            self.compositor_scale_factor = scale_factor;
+            self.update_viewport();
         }
     }
 
-    /// Returns the current cursor position of the [`State`].
-    pub fn cursor_position(&self) -> Point {
-        self.cursor_position
+    /// Sets the transform of the buffers of the surface of the [`State`].
+    pub fn set_buffer_transform(&mut self, transform: Transform) {
+        let was_transposed = self.is_transposed();
+        self.transform = transform;
+        if self.is_transposed() != was_transposed {
+            self.update_viewport();
+        }
+    }
+
+    /// Whether the buffers are rotated by 90 or 270 degrees.
+    fn is_transposed(&self) -> bool {
+        matches!(
+            self.transform,
+            Transform::_90 | Transform::_270 | Transform::Flipped90 | Transform::Flipped270
+        )
+    }
+
+    /// Rebuilds the [`Viewport`] from the size of the surface, its buffer transform,
+    /// and the scale factors of the compositor and the [`Application`].
+    fn update_viewport(&mut self) {
+        self.viewport_changed = true;
+        let (width, height) = if self.is_transposed() {
+            (self.surface_size.height, self.surface_size.width)
+        } else {
+            (self.surface_size.width, self.surface_size.height)
+        };
+        self.viewport = Viewport::with_physical_size(
+            Size {
+                width: (width * self.compositor_scale_factor) as u32,
+                height: (height * self.compositor_scale_factor) as u32,
+            },
+            self.compositor_scale_factor * self.application_scale_factor,
+        );
+    }
+
+    /// Returns the current cursor position of the [`State`], or `None` if the pointer
+    /// is not over its surface.
+    pub fn cursor_position(&self) -> Option<Point> {
+        self.cursor_position.map(|(x, y)| Point::new(x as f32, y as f32))
+    }
+
+    /// Returns the cursor position to hit-test the widgets of the [`State`] with.
+    ///
+    /// The [`UserInterface`] has no notion of an unavailable cursor, so it gets a
+    /// position which no widget contains while the pointer is not over the surface.
+    fn hit_test_position(&self) -> Point {
+        self.cursor_position()
+            .unwrap_or_else(|| Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY))
     }
 
     /// Returns the current keyboard modifiers of the [`State`].
@@ -978,13 +1761,50 @@ where
         self.appearance.background_color
     }
 
+    /// Returns whether the background of the [`State`] is fully opaque.
+    pub fn is_opaque(&self) -> bool {
+        self.appearance.background_color.a >= 1.0
+    }
+
     /// Returns the current text [`Color`] of the [`State`].
     pub fn text_color(&self) -> Color {
         self.appearance.text_color
     }
 
-    pub fn set_cursor_position(&mut self, p: Point) {
-        self.cursor_position = p;
+    /// Sets the cursor position of the [`State`], or `None` if the pointer left its surface.
+    pub fn set_cursor_position(&mut self, p: Option<Point>) {
+        self.cursor_position = p.map(|p| (p.x as f64, p.y as f64));
+    }
+
+    /// Sets the cursor position of the [`State`] from a position in surface local
+    /// coordinates, as sent by the compositor, or `None` if the pointer left its surface.
+    ///
+    /// Unlike [`State::surface_to_logical`], the fractional part of the position is
+    /// kept in double precision.
+    pub(crate) fn set_surface_cursor_position(&mut self, position: Option<(f64, f64)>) {
+        self.cursor_position = position.map(|(x, y)| self.surface_to_interface(x, y));
+    }
+
+    /// Converts a position in surface local coordinates, as sent by the compositor,
+    /// to the logical coordinates of the [`UserInterface`].
+    ///
+    /// Surface local coordinates are already scaled by the compositor, so only the
+    /// buffer transform and the scale factor of the [`Application`] are left to apply.
+    pub fn surface_to_logical(&self, position: Point) -> Point {
+        let (x, y) = self.surface_to_interface(position.x as f64, position.y as f64);
+        Point::new(x as f32, y as f32)
+    }
+
+    /// Converts a position in surface local coordinates to the logical coordinates of the
+    /// [`UserInterface`], in double precision.
+    fn surface_to_interface(&self, x: f64, y: f64) -> (f64, f64) {
+        surface_to_interface(
+            self.transform,
+            self.surface_size,
+            self.application_scale_factor,
+            x,
+            y,
+        )
     }
 
     /// Synchronizes the [`State`] with its [`Application`] and its respective
@@ -999,21 +1819,88 @@ where
         application: &A,
         window: &SctkWindow<A::Message>,
         proxy: &proxy::Proxy<Event<A::Message>>,
-    ) {
-        self.synchronize(application);
+    ) -> bool {
+        self.synchronize(application)
     }
 
-    fn synchronize(&mut self, application: &A) {
+    /// Updates the theme and appearance of the [`State`].
+    ///
+    /// Returns `true` if the opacity of the background changed, in which case
+    /// the opaque region of the surface must be recomputed and the whole
+    /// surface redrawn.
+    fn synchronize(&mut self, application: &A) -> bool {
+        let was_opaque = self.is_opaque();
+
+        // Update scale factor
+        let application_scale_factor = application.surface_scale_factor(self.id);
+        if application_scale_factor != self.application_scale_factor {
+            self.application_scale_factor = application_scale_factor;
+            self.update_viewport();
+        }
+
         // Update theme and appearance
-        self.theme = application.theme();
+        self.theme = application.surface_theme(self.id);
         self.appearance = self.theme.appearance(&application.style());
+
+        was_opaque != self.is_opaque()
     }
 }
 
-// XXX Ashley careful, A, E, C must be exact same as in run_instance, or the subscription map type will have a different hash
+/// Collects the phases of the frames recorded by [`Debug`], which the application can fetch
+/// with [`fetch_frame_stats`].
+///
+/// [`fetch_frame_stats`]: crate::commands::frame_stats::fetch_frame_stats
+#[derive(Debug, Default)]
+pub(crate) struct FrameTimer {
+    /// the durations of the phases of the latest frame
+    stats: FrameStats,
+    /// the surface of the latest frame
+    latest: Option<SurfaceId>,
+    /// when the frames of the last second were presented, per surface
+    presents: HashMap<SurfaceId, VecDeque<Instant>>,
+}
+
+impl FrameTimer {
+    /// Records a frame of the surface `id` which was just presented.
+    fn presented(&mut self, id: SurfaceId, debug: &Debug) {
+        self.stats.render = debug.render_duration();
+        self.latest = Some(id);
+        self.presents.entry(id).or_default().push_back(Instant::now());
+        // also forgets the surfaces which did not present for a second, e.g. destroyed ones
+        self.presents.retain(|_, presents| {
+            while presents
+                .front()
+                .map_or(false, |p| p.elapsed() > Duration::from_secs(1))
+            {
+                presents.pop_front();
+            }
+            !presents.is_empty()
+        });
+    }
+
+    /// Returns the durations of the phases of the latest frame, and the number of frames
+    /// its surface presented within the last second.
+    pub(crate) fn stats(&self) -> FrameStats {
+        let fps = self
+            .latest
+            .and_then(|id| self.presents.get(&id))
+            .map_or(0, |presents| {
+                presents
+                    .iter()
+                    .filter(|p| p.elapsed() <= Duration::from_secs(1))
+                    .count()
+            });
+        FrameStats {
+            fps: fps as f32,
+            ..self.stats
+        }
+    }
+}
+
+// XXX Ashley careful, A, E, B must be exact same as in run_instance, or the subscription map type will have a different hash
 /// Updates an [`Application`] by feeding it the provided messages, spawning any
 /// resulting [`Command`], and tracking its [`Subscription`]
-pub(crate) fn update<A, E, C>(
+pub(crate) fn update<A, E, B>(
     application: &mut A,
     cache: &mut user_interface::Cache,
     state: Option<&State<A>>,
@@ -1021,20 +1908,26 @@ pub(crate) fn update<A, E, C>(
     runtime: &mut Runtime<E, proxy::Proxy<Event<A::Message>>, Event<A::Message>>,
     proxy: &mut proxy::Proxy<Event<A::Message>>,
     debug: &mut Debug,
+    frame_timer: &mut FrameTimer,
     messages: &mut Vec<A::Message>,
     graphics_info: impl FnOnce() -> compositor::Information + Copy,
 ) where
     A: Application + 'static,
     E: Executor + 'static,
-    C: window::GLCompositor<Renderer = A::Renderer> + 'static,
+    B: Backend<Renderer = A::Renderer> + 'static,
     <A::Renderer as iced_native::Renderer>::Theme: StyleSheet,
 {
+    if !messages.is_empty() {
+        frame_timer.stats.update = Duration::ZERO;
+    }
     for message in messages.drain(..) {
         debug.log_message(&message);
 
         debug.update_started();
         let command = runtime.enter(|| application.update(message));
         debug.update_finished();
+        // the messages of a batch are all handled before the next frame
+        frame_timer.stats.update += debug.update_duration();
 
         run_command(
             application,
@@ -1045,11 +1938,12 @@ pub(crate) fn update<A, E, C>(
             runtime,
             proxy,
             debug,
+            frame_timer,
             graphics_info,
         );
     }
 
-    runtime.track(application.subscription().map(subscription_map::<A, E, C>));
+    runtime.track(application.subscription().map(subscription_map::<A, E, B>));
 }
 
 /// Runs the actions of a [`Command`].
@@ -1062,6 +1956,7 @@ fn run_command<A, E>(
     runtime: &mut Runtime<E, proxy::Proxy<Event<A::Message>>, Event<A::Message>>,
     proxy: &mut proxy::Proxy<Event<A::Message>>,
     debug: &mut Debug,
+    frame_timer: &FrameTimer,
     _graphics_info: impl FnOnce() -> compositor::Information + Copy,
 ) where
     A: Application,
@@ -1080,12 +1975,16 @@ fn run_command<A, E>(
             }
             command::Action::Clipboard(action) => match action {
                 clipboard::Action::Read(tag) => {
-                    todo!();
+                    // the contents are read by the event loop, without blocking it
+                    proxy.send_event(Event::ReadClipboard(tag));
                 }
                 clipboard::Action::Write(contents) => {
                     todo!();
                 }
             },
+            command::Action::Window(id, iced_native::window::Action::SetMode(mode)) => {
+                proxy.send_event(Event::SetMode { id, mode });
+            }
             command::Action::Window(id, action) => {
                 todo!()
             }
@@ -1159,11 +2058,208 @@ fn run_command<A, E>(
             )) => {
                 proxy.send_event(Event::Popup(popup_action));
             }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Output(output_action),
+            )) => {
+                proxy.send_event(Event::Output(output_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Surface(surface_action),
+            )) => {
+                proxy.send_event(Event::Surface(surface_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::IdleNotify(idle_notify_action),
+            )) => {
+                proxy.send_event(Event::IdleNotify(idle_notify_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Dmabuf(dmabuf_action),
+            )) => {
+                proxy.send_event(Event::Dmabuf(dmabuf_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::DataDevice(data_device_action),
+            )) => {
+                proxy.send_event(Event::DataDevice(data_device_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Pointer(pointer_action),
+            )) => {
+                proxy.send_event(Event::Pointer(pointer_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::FetchFrameStats(f),
+            )) => {
+                proxy.send_event(Event::Application(f(frame_timer.stats())));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Keyboard(keyboard_action),
+            )) => {
+                proxy.send_event(Event::Keyboard(keyboard_action));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Flush,
+            )) => {
+                proxy.send_event(Event::Flush);
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::SetMaxFrameRate(fps),
+            )) => {
+                proxy.send_event(Event::SetMaxFrameRate(fps));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Supports(interface, f),
+            )) => {
+                proxy.send_event(Event::Supports(interface, f));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::FetchCompositorInfo(f),
+            )) => {
+                proxy.send_event(Event::FetchCompositorInfo(f));
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Exit,
+            )) => {
+                proxy.send_event(Event::Exit);
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::BeginBatch,
+            )) => {
+                proxy.send_event(Event::BeginBatch);
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::EndBatch,
+            )) => {
+                proxy.send_event(Event::EndBatch);
+            }
+            command::Action::PlatformSpecific(platform_specific::Action::Wayland(
+                platform_specific::wayland::Action::Seat(seat_action),
+            )) => {
+                proxy.send_event(Event::Seat(seat_action));
+            }
             _ => {}
         }
     }
 }
 
+/// Converts the position of a touch event from surface local coordinates to the logical
+/// coordinates of the [`UserInterface`].
+fn touch_to_logical<A: Application>(state: &State<A>, event: touch::Event) -> touch::Event
+where
+    <A::Renderer as crate::Renderer>::Theme: application::StyleSheet,
+{
+    match event {
+        touch::Event::FingerPressed { id, position } => touch::Event::FingerPressed {
+            id,
+            position: state.surface_to_logical(position),
+        },
+        touch::Event::FingerMoved { id, position } => touch::Event::FingerMoved {
+            id,
+            position: state.surface_to_logical(position),
+        },
+        touch::Event::FingerLifted { id, position } => touch::Event::FingerLifted {
+            id,
+            position: state.surface_to_logical(position),
+        },
+        touch::Event::FingerLost { id, position } => touch::Event::FingerLost {
+            id,
+            position: state.surface_to_logical(position),
+        },
+    }
+}
+
+/// Converts the position of a drag and drop event from surface local coordinates to the
+/// logical coordinates of the [`UserInterface`].
+fn dnd_offer_to_logical<A: Application>(
+    state: &State<A>,
+    event: wayland::DndOfferEvent,
+) -> wayland::DndOfferEvent
+where
+    <A::Renderer as crate::Renderer>::Theme: application::StyleSheet,
+{
+    match event {
+        wayland::DndOfferEvent::Enter {
+            position,
+            mime_types,
+            source_actions,
+        } => wayland::DndOfferEvent::Enter {
+            position: state.surface_to_logical(position),
+            mime_types,
+            source_actions,
+        },
+        wayland::DndOfferEvent::Motion { position } => wayland::DndOfferEvent::Motion {
+            position: state.surface_to_logical(position),
+        },
+        event => event,
+    }
+}
+
+/// Lets the [`Application`] filter a key event before it is turned into an event of
+/// the widgets. Returns `None` if the event was consumed.
+fn filter_key_event<A: Application>(
+    application: &mut A,
+    event: SctkEvent,
+    modifiers: Modifiers,
+) -> Option<SctkEvent>
+where
+    <A::Renderer as crate::Renderer>::Theme: StyleSheet,
+{
+    let (variant, kbd_id, seat_id, surface) = match event {
+        SctkEvent::KeyboardEvent {
+            variant,
+            kbd_id,
+            seat_id,
+            surface,
+        } => (variant, kbd_id, seat_id, surface),
+        event => return Some(event),
+    };
+    let (state, key_event) = match variant {
+        KeyboardEventVariant::Press(e) => (KeyState::Pressed, e),
+        KeyboardEventVariant::Repeat(e) => (KeyState::Repeated, e),
+        KeyboardEventVariant::Release(e) => (KeyState::Released, e),
+        variant => {
+            return Some(SctkEvent::KeyboardEvent {
+                variant,
+                kbd_id,
+                seat_id,
+                surface,
+            })
+        }
+    };
+    let raw = RawKeyEvent {
+        state,
+        keysym: key_event.keysym,
+        scancode: key_event.raw_code,
+        modifiers,
+        utf8: key_event.utf8.clone(),
+    };
+    let (state, key_event) = match application.on_raw_key(&raw) {
+        KeyFilter::Pass => (state, key_event),
+        KeyFilter::Consume => return None,
+        KeyFilter::Replace(raw) => (
+            raw.state,
+            KeyEvent {
+                keysym: raw.keysym,
+                raw_code: raw.scancode,
+                utf8: raw.utf8,
+                ..key_event
+            },
+        ),
+    };
+    let variant = match state {
+        KeyState::Pressed => KeyboardEventVariant::Press(key_event),
+        KeyState::Repeated => KeyboardEventVariant::Repeat(key_event),
+        KeyState::Released => KeyboardEventVariant::Release(key_event),
+    };
+    Some(SctkEvent::KeyboardEvent {
+        variant,
+        kbd_id,
+        seat_id,
+        surface,
+    })
+}
+
 pub fn build_user_interfaces<'a, A>(
     application: &'a A,
     renderer: &mut A::Renderer,
@@ -1207,3 +2303,58 @@ where
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_are_synced_once() {
+        let mut surface = Modifiers::default();
+        let current = Modifiers {
+            shift: true,
+            ..Modifiers::default()
+        };
+        assert!(matches!(
+            sync_modifiers(&mut surface, current),
+            Some(iced_native::Event::Keyboard(keyboard::Event::ModifiersChanged(m))) if m.shift()
+        ));
+        assert_eq!(surface, current);
+        assert!(sync_modifiers(&mut surface, current).is_none());
+    }
+
+    #[test]
+    fn surface_positions_are_scaled_by_the_application() {
+        let size = Size::new(200.0, 100.0);
+        let position = surface_to_interface(Transform::Normal, size, 2.0, 10.0, 30.0);
+        assert_eq!(position, (5.0, 15.0));
+        let position = surface_to_interface(Transform::_180, size, 2.0, 10.0, 30.0);
+        assert_eq!(position, (95.0, 35.0));
+    }
+
+    #[test]
+    fn surface_positions_keep_their_fractional_part() {
+        let size = Size::new(200.0, 100.0);
+        let (x, y) = surface_to_interface(Transform::Normal, size, 1.0, 150.0001, 0.5);
+        // single precision, in which the widgets are positioned, would round it
+        assert_ne!(150.0001f64 as f32 as f64, 150.0001);
+        assert_eq!((x, y), (150.0001, 0.5));
+    }
+
+    #[test]
+    fn surface_to_buffer_rotates_the_corners() {
+        let size = Size::new(200.0, 100.0);
+        // the top left corner of the surface, and its top right corner
+        let corners = |transform| {
+            (
+                surface_to_buffer(transform, size, 0.0, 0.0),
+                surface_to_buffer(transform, size, 200.0, 0.0),
+            )
+        };
+        assert_eq!(corners(Transform::Normal), ((0.0, 0.0), (200.0, 0.0)));
+        assert_eq!(corners(Transform::_90), ((0.0, 200.0), (0.0, 0.0)));
+        assert_eq!(corners(Transform::_180), ((200.0, 100.0), (0.0, 100.0)));
+        assert_eq!(corners(Transform::_270), ((100.0, 0.0), (100.0, 200.0)));
+        assert_eq!(corners(Transform::Flipped90), ((0.0, 0.0), (0.0, 200.0)));
+    }
+}
@@ -0,0 +1,160 @@
+use std::{ffi::CString, num::NonZeroU32};
+
+use glutin::{api::egl, prelude::*, surface::WindowSurface};
+use iced_graphics::{compositor, window, window::SurfaceError, Color, Viewport};
+use sctk::reexports::client::{protocol::wl_surface::WlSurface, Proxy};
+use wayland_backend::client::ObjectId;
+
+use super::Backend;
+use crate::{
+    egl::{get_surface, init_egl, EglError},
+    error::Error,
+    settings::EglSettings,
+    Size,
+};
+
+/// The settings of a [`GlBackend`].
+#[derive(Debug, Clone)]
+pub struct GlSettings<S> {
+    /// the settings of the [`window::GLCompositor`]
+    pub compositor: S,
+    /// the buffers requested from EGL
+    pub egl: EglSettings,
+}
+
+/// A [`Backend`] drawing with a [`window::GLCompositor`] to EGL surfaces.
+#[allow(missing_debug_implementations)]
+pub struct GlBackend<C> {
+    compositor: C,
+    display: egl::display::Display,
+    context: egl::context::PossiblyCurrentContext,
+    config: egl::config::Config,
+    /// whether the surfaces are drawn in the sRGB color space
+    srgb: bool,
+    /// the surface the context is currently bound to
+    current: ObjectId,
+}
+
+/// The EGL surface of a [`WlSurface`].
+#[allow(missing_debug_implementations)]
+pub struct GlSurface {
+    id: ObjectId,
+    surface: egl::surface::Surface<WindowSurface>,
+}
+
+impl<C: window::GLCompositor> GlBackend<C> {
+    /// Binds the context to the surface, if it is not already.
+    fn make_current(&mut self, surface: &GlSurface) -> bool {
+        if self.current == surface.id {
+            return true;
+        }
+        if self.context.make_current(&surface.surface).is_ok() {
+            self.current = surface.id.clone();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<C> Backend for GlBackend<C>
+where
+    C: window::GLCompositor,
+{
+    type Renderer = C::Renderer;
+    type Settings = GlSettings<C::Settings>;
+    type Surface = GlSurface;
+
+    fn new(
+        settings: Self::Settings,
+        wl_surface: &WlSurface,
+        width: u32,
+        height: u32,
+    ) -> Result<(Self, Self::Renderer, Self::Surface), Error> {
+        let (display, context, config, surface) =
+            init_egl(wl_surface, width, height, &settings.egl)?;
+        let context = context
+            .make_current(&surface)
+            .map_err(EglError::MakeCurrent)?;
+
+        #[allow(unsafe_code)]
+        let (compositor, renderer) = unsafe {
+            C::new(settings.compositor, |name| {
+                let name = CString::new(name).unwrap();
+                display.get_proc_address(name.as_c_str())
+            })?
+        };
+
+        Ok((
+            Self {
+                compositor,
+                display,
+                context,
+                config,
+                srgb: settings.egl.srgb,
+                current: wl_surface.id(),
+            },
+            renderer,
+            GlSurface {
+                id: wl_surface.id(),
+                surface,
+            },
+        ))
+    }
+
+    fn reconnect_settings(settings: &Self::Settings) -> Self::Settings {
+        GlSettings {
+            compositor: C::Settings::default(),
+            egl: settings.egl,
+        }
+    }
+
+    fn create_surface(&mut self, wl_surface: &WlSurface, width: u32, height: u32) -> GlSurface {
+        GlSurface {
+            id: wl_surface.id(),
+            surface: get_surface(
+                &self.display,
+                &self.config,
+                wl_surface,
+                width,
+                height,
+                self.srgb,
+            ),
+        }
+    }
+
+    fn configure_surface(&mut self, surface: &mut GlSurface, size: Size<u32>) {
+        if !self.make_current(surface) {
+            return;
+        }
+        surface.surface.resize(
+            &self.context,
+            NonZeroU32::new(size.width.max(1)).unwrap(),
+            NonZeroU32::new(size.height.max(1)).unwrap(),
+        );
+    }
+
+    fn present(
+        &mut self,
+        renderer: &mut Self::Renderer,
+        surface: &mut GlSurface,
+        viewport: &Viewport,
+        background_color: Color,
+        overlay: &[String],
+    ) -> Result<(), SurfaceError> {
+        if !self.make_current(surface) {
+            // skip the frame if the context cannot be bound to the surface
+            return Err(SurfaceError::Timeout);
+        }
+        // the viewport of the compositor is shared by all surfaces
+        self.compositor.resize_viewport(viewport.physical_size());
+        self.compositor
+            .present(renderer, viewport, background_color, overlay);
+        let _ = surface.surface.swap_buffers(&self.context);
+        Ok(())
+    }
+
+    fn fetch_information(&self) -> compositor::Information {
+        self.compositor.fetch_information()
+    }
+}
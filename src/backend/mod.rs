@@ -0,0 +1,99 @@
+//! The graphics backends used to draw the surfaces of an application.
+
+mod gl;
+#[cfg(feature = "wgpu")]
+mod wgpu;
+
+pub use gl::{GlBackend, GlSettings, GlSurface};
+#[cfg(feature = "wgpu")]
+pub use self::wgpu::WgpuBackend;
+
+use crate::{error::Error, Size};
+use iced_graphics::{compositor, window::SurfaceError, Color, Viewport};
+use raw_window_handle::{
+    HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle, RawWindowHandle,
+    WaylandDisplayHandle, WaylandWindowHandle,
+};
+use sctk::reexports::client::{protocol::wl_surface::WlSurface, Proxy};
+
+/// A graphics backend, which creates the render targets of the surfaces of an
+/// application and presents frames to them.
+pub trait Backend: Sized {
+    /// The renderer used to draw the frames.
+    type Renderer: iced_native::Renderer;
+
+    /// The settings of the backend.
+    type Settings;
+
+    /// The render target of a single surface.
+    type Surface;
+
+    /// Creates the backend, its renderer and the render target of the first surface.
+    fn new(
+        settings: Self::Settings,
+        wl_surface: &WlSurface,
+        width: u32,
+        height: u32,
+    ) -> Result<(Self, Self::Renderer, Self::Surface), Error>;
+
+    /// Returns the settings to create the backend with again after reconnecting, with the
+    /// default settings of the compositor.
+    fn reconnect_settings(settings: &Self::Settings) -> Self::Settings;
+
+    /// Creates the render target of another surface.
+    fn create_surface(&mut self, wl_surface: &WlSurface, width: u32, height: u32)
+        -> Self::Surface;
+
+    /// Resizes the render target of a surface to the given physical size.
+    fn configure_surface(&mut self, surface: &mut Self::Surface, size: Size<u32>);
+
+    /// Draws the renderer's primitives to the surface.
+    fn present(
+        &mut self,
+        renderer: &mut Self::Renderer,
+        surface: &mut Self::Surface,
+        viewport: &Viewport,
+        background_color: Color,
+        overlay: &[String],
+    ) -> Result<(), SurfaceError>;
+
+    /// Returns information about the graphics adapter in use.
+    fn fetch_information(&self) -> compositor::Information;
+}
+
+/// The raw handles of a surface and the display it belongs to, for renderers
+/// which draw to the surface on their own.
+///
+/// Fetched with [`fetch_surface_handle`](crate::commands::surface::fetch_surface_handle).
+/// The handles are only valid while the surface is alive.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceHandle {
+    display: RawDisplayHandle,
+    window: RawWindowHandle,
+}
+
+impl SurfaceHandle {
+    /// Returns `None` if the connection of the surface was closed.
+    pub(crate) fn new(surface: &WlSurface) -> Option<Self> {
+        let mut display = WaylandDisplayHandle::empty();
+        display.display = surface.backend().upgrade()?.display_ptr() as *mut _;
+        let mut window = WaylandWindowHandle::empty();
+        window.surface = surface.id().as_ptr() as *mut _;
+        Some(Self {
+            display: RawDisplayHandle::Wayland(display),
+            window: RawWindowHandle::Wayland(window),
+        })
+    }
+}
+
+unsafe impl HasRawDisplayHandle for SurfaceHandle {
+    fn raw_display_handle(&self) -> RawDisplayHandle {
+        self.display
+    }
+}
+
+unsafe impl HasRawWindowHandle for SurfaceHandle {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        self.window
+    }
+}
@@ -0,0 +1,71 @@
+use iced_graphics::{compositor, window, window::SurfaceError, Color, Viewport};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+
+use super::{Backend, SurfaceHandle};
+use crate::{error::Error, Size};
+
+/// A [`Backend`] drawing with a [`window::Compositor`], such as the one of
+/// `iced_wgpu`, to surfaces created from the raw handles of the [`WlSurface`].
+#[allow(missing_debug_implementations)]
+pub struct WgpuBackend<C> {
+    compositor: C,
+}
+
+impl<C> Backend for WgpuBackend<C>
+where
+    C: window::Compositor,
+{
+    type Renderer = C::Renderer;
+    type Settings = C::Settings;
+    type Surface = C::Surface;
+
+    fn new(
+        settings: Self::Settings,
+        wl_surface: &WlSurface,
+        width: u32,
+        height: u32,
+    ) -> Result<(Self, Self::Renderer, Self::Surface), Error> {
+        let handle = SurfaceHandle::new(wl_surface).ok_or_else(|| {
+            Error::WindowCreationFailed("the connection to the compositor was closed".into())
+        })?;
+        let (compositor, renderer) = C::new(settings, Some(&handle))?;
+        let mut backend = Self { compositor };
+        let surface = backend.create_surface(wl_surface, width, height);
+
+        Ok((backend, renderer, surface))
+    }
+
+    fn reconnect_settings(_settings: &Self::Settings) -> Self::Settings {
+        C::Settings::default()
+    }
+
+    fn create_surface(&mut self, wl_surface: &WlSurface, width: u32, height: u32) -> C::Surface {
+        // surfaces are only created while their connection is alive
+        let handle = SurfaceHandle::new(wl_surface).expect("Connection has been closed");
+        let mut surface = self.compositor.create_surface(&handle);
+        self.compositor
+            .configure_surface(&mut surface, width.max(1), height.max(1));
+        surface
+    }
+
+    fn configure_surface(&mut self, surface: &mut C::Surface, size: Size<u32>) {
+        self.compositor
+            .configure_surface(surface, size.width.max(1), size.height.max(1));
+    }
+
+    fn present(
+        &mut self,
+        renderer: &mut Self::Renderer,
+        surface: &mut C::Surface,
+        viewport: &Viewport,
+        background_color: Color,
+        overlay: &[String],
+    ) -> Result<(), SurfaceError> {
+        self.compositor
+            .present(renderer, surface, viewport, background_color, overlay)
+    }
+
+    fn fetch_information(&self) -> compositor::Information {
+        self.compositor.fetch_information()
+    }
+}
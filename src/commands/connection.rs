@@ -0,0 +1,57 @@
+//! Interact with the connection of your application to the compositor.
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+pub use iced_native::command::platform_specific::wayland::CompositorInfo;
+
+/// Sends the pending requests to the compositor right away, e.g. after releasing a held
+/// configure, instead of at the start of the next iteration of the event loop. Requests
+/// of commands which are handled later are not included.
+pub fn flush<Message>() -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Flush),
+    ))
+}
+
+/// Checks whether the compositor supports the protocol of the global with the interface name
+/// `interface`, e.g. `zwlr_layer_shell_v1`, and the application bound it. Only the optional
+/// protocols used by the application are covered, the others are always `false`.
+///
+/// Can be used to adapt the interface of the application to the compositor, e.g. to hide
+/// actions which are not supported.
+pub fn supports<Message>(
+    interface: impl Into<String>,
+    f: impl FnOnce(bool) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Supports(interface.into(), Box::new(f))),
+    ))
+}
+
+/// Exits the application, regardless of [`Application::should_exit`], e.g. after the user
+/// confirmed a quit which was vetoed with [`Application::veto_exit`].
+///
+/// [`Application::should_exit`]: crate::application::Application::should_exit
+/// [`Application::veto_exit`]: crate::application::Application::veto_exit
+pub fn exit<Message>() -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Exit),
+    ))
+}
+
+/// Fetches the name of the compositor and the interface names and versions of every global
+/// it advertises, e.g. to log it or to work around the quirks of a compositor.
+///
+/// The name is a best-effort guess from the private protocols of the known compositors,
+/// `None` if none of them is advertised, e.g. for most wlroots based compositors.
+///
+/// <https://wayland.app/protocols/wayland#wl_registry:event:global>
+pub fn fetch_compositor_info<Message>(
+    f: impl FnOnce(CompositorInfo) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::FetchCompositorInfo(Box::new(f))),
+    ))
+}
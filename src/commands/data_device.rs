@@ -1 +1,83 @@
 //! Interact with the data device objects of your application.
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+use iced_native::window::Id as SurfaceId;
+
+pub use iced_native::command::platform_specific::wayland::data_device::DragIcon;
+pub use sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
+
+/// Fetches the mime types offered by the clipboard of the active seat.
+///
+/// <https://wayland.app/protocols/wayland#wl_data_offer:event:offer>
+pub fn query_clipboard_mimes<Message>(
+    f: impl FnOnce(Vec<String>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::DataDevice(
+            wayland::data_device::Action::QueryMimeTypes(Box::new(f)),
+        )),
+    ))
+}
+
+/// Accepts the drag over the application as `mime_type`, `None` rejects it.
+///
+/// <https://wayland.app/protocols/wayland#wl_data_offer:request:accept>
+pub fn accept_dnd_mime_type<Message>(mime_type: Option<String>) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::DataDevice(
+            wayland::data_device::Action::AcceptMimeType(mime_type),
+        )),
+    ))
+}
+
+/// Sets the actions supported for the drag over the application, and the preferred one.
+///
+/// <https://wayland.app/protocols/wayland#wl_data_offer:request:set_actions>
+pub fn set_dnd_actions<Message>(actions: DndAction, preferred: DndAction) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::DataDevice(
+            wayland::data_device::Action::SetActions { actions, preferred },
+        )),
+    ))
+}
+
+/// Reads the data of the dropped drag as `mime_type`.
+///
+/// <https://wayland.app/protocols/wayland#wl_data_offer:request:receive>
+pub fn read_dnd_offer<Message>(
+    mime_type: impl Into<String>,
+    f: impl FnOnce(Option<Vec<u8>>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::DataDevice(
+            wayland::data_device::Action::ReadOffer {
+                mime_type: mime_type.into(),
+                f: Box::new(f),
+            },
+        )),
+    ))
+}
+
+/// Starts a drag of `contents` from `origin` with the pointer button which is held on it.
+///
+/// <https://wayland.app/protocols/wayland#wl_data_device:request:start_drag>
+pub fn start_drag<Message>(
+    origin: SurfaceId,
+    contents: Vec<(String, Vec<u8>)>,
+    actions: DndAction,
+    icon: Option<DragIcon>,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::DataDevice(
+            wayland::data_device::Action::StartDrag {
+                origin,
+                contents,
+                actions,
+                icon,
+            },
+        )),
+    ))
+}
@@ -0,0 +1,46 @@
+//! Show buffers imported from dmabufs, like decoded video frames, without copying them.
+use iced_native::{
+    command::{
+        self,
+        platform_specific::{self, wayland},
+        Command,
+    },
+    window,
+};
+
+pub use wayland::dmabuf::{DmabufBuffer, DmabufPlane};
+
+/// Fetches the formats and modifiers which the compositor can import dmabufs with.
+///
+/// A format which can only be used without an explicit modifier is listed with
+/// `DRM_FORMAT_MOD_INVALID`.
+///
+/// <https://wayland.app/protocols/linux-dmabuf-unstable-v1#zwp_linux_dmabuf_v1:event:modifier>
+pub fn fetch_dmabuf_formats<Message>(
+    f: impl FnOnce(Vec<(u32, u64)>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Dmabuf(
+            wayland::dmabuf::Action::FetchFormats(Box::new(f)),
+        )),
+    ))
+}
+
+/// Imports `buffer` and shows it on the surface with `id`, which should be a subsurface,
+/// as the contents of the other surfaces are drawn by the renderer.
+///
+/// The file descriptors of the planes stay owned by the application. When the compositor
+/// no longer reads from the buffer, the application receives a
+/// `wayland::BufferEvent::Released` event with the id of the buffer, and can reuse it.
+///
+/// <https://wayland.app/protocols/linux-dmabuf-unstable-v1#zwp_linux_buffer_params_v1:request:create_immed>
+pub fn attach_dmabuf<Message>(
+    id: window::Id,
+    buffer: DmabufBuffer,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Dmabuf(
+            wayland::dmabuf::Action::Attach { id, buffer },
+        )),
+    ))
+}
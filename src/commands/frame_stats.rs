@@ -0,0 +1,37 @@
+//! Measure and pace the frames of your application.
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+pub use wayland::FrameStats;
+
+/// Fetches the timings of the latest frame, and the frames per second of its surface.
+pub fn fetch_frame_stats<Message>(
+    f: impl FnOnce(FrameStats) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::FetchFrameStats(Box::new(f))),
+    ))
+}
+
+/// Caps the redraws of every surface to `fps`, `None` removes the cap.
+pub fn set_max_frame_rate<Message>(fps: Option<u32>) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::SetMaxFrameRate(fps)),
+    ))
+}
+
+/// Suppresses the redraws of every surface until [`end_batch`].
+pub fn begin_batch<Message>() -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::BeginBatch),
+    ))
+}
+
+/// Ends the batch, and redraws the surfaces which requested a redraw during it.
+pub fn end_batch<Message>() -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::EndBatch),
+    ))
+}
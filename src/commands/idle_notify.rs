@@ -0,0 +1,23 @@
+//! Get notified when the user is inactive.
+use std::time::Duration;
+
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+
+/// Sets the inactivity after which every seat is reported as idle, replacing the
+/// timeout from the settings. `None` stops the idle notifications.
+///
+/// The application receives `wayland::Event::Idle` events when a seat becomes idle,
+/// and when it is active again.
+///
+/// <https://wayland.app/protocols/ext-idle-notify-v1#ext_idle_notifier_v1:request:get_idle_notification>
+pub fn set_idle_timeout<Message>(timeout: Option<Duration>) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::IdleNotify(
+            wayland::idle_notify::Action::SetTimeout(timeout),
+        )),
+    ))
+}
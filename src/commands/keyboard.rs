@@ -0,0 +1,35 @@
+//! Interact with the keyboards of the seats of your application.
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+pub use sctk::seat::keyboard::RepeatInfo;
+
+/// Fetches the repeat rate and delay of the keyboard of the active seat, as provided
+/// by the compositor. Produces `None` if the active seat has no keyboard, or if the
+/// compositor sent no repeat info.
+///
+/// <https://wayland.app/protocols/wayland#wl_keyboard:event:repeat_info>
+pub fn fetch_repeat_info<Message>(
+    f: impl FnOnce(Option<RepeatInfo>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Keyboard(
+            wayland::keyboard::Action::FetchRepeatInfo(Box::new(f)),
+        )),
+    ))
+}
+
+/// Disables or restores the key repeat of the surface focused by the keyboard of the active
+/// seat, e.g. while a password field is focused. The repeat of the other surfaces is not
+/// affected, and it is restored when the surface loses the keyboard focus.
+///
+/// A disabled repeat is reported as [`RepeatInfo::Disable`] by [`fetch_repeat_info`].
+pub fn set_repeat_enabled<Message>(enabled: bool) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Keyboard(
+            wayland::keyboard::Action::SetRepeatEnabled(enabled),
+        )),
+    ))
+}
@@ -1,8 +1,10 @@
 //! Interact with the window of your application.
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use iced_native::command::platform_specific::wayland::layer_surface::IcedMargin;
 use iced_native::window::Id as SurfaceId;
+use iced_native::Rectangle;
 use iced_native::{
     command::{
         self,
@@ -40,6 +42,11 @@ pub fn destroy_layer_surface<Message>(id: SurfaceId) -> Command<Message> {
     ))
 }
 
+/// Sets the size of the layer surface. A dimension which is `None` is chosen by the
+/// compositor, which requires the surface to be anchored to both opposite edges, e.g.
+/// the left and right edges for the width of a bar. The surface is then resized along
+/// with the output, without any further commands.
+///
 /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:request:set_size>
 pub fn set_size<Message>(
     id: SurfaceId,
@@ -118,3 +125,71 @@ pub fn set_layer<Message>(id: SurfaceId, layer: Layer) -> Command<Message> {
         )),
     ))
 }
+
+/// Grabs the keyboard with the layer surface, e.g. for a launcher: the surface becomes
+/// exclusively interactive, and receives every key event of the application, whichever of
+/// its surfaces the compositor focuses, until it is released with [`release_keyboard`].
+/// Popups keep their key events.
+///
+/// The keys are still filtered by `Application::on_raw_key`, so e.g. escape can dismiss
+/// the surface, which should release the grab.
+///
+/// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:enum:keyboard_interactivity>
+pub fn grab_keyboard<Message>(id: SurfaceId) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::LayerSurface(
+            wayland::layer_surface::Action::GrabKeyboard(id),
+        )),
+    ))
+}
+
+/// Releases the keyboard grabbed with [`grab_keyboard`], and restores the keyboard
+/// interactivity of the layer surface, unless a focus request or a grabbed popup still
+/// needs it.
+pub fn release_keyboard<Message>(id: SurfaceId) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::LayerSurface(
+            wayland::layer_surface::Action::ReleaseKeyboard(id),
+        )),
+    ))
+}
+
+/// Moves the layer surface to the layer above its own, e.g. to show an OSD above a bar
+/// on the same layer, as the surfaces within a layer can't be restacked. The surface is
+/// moved back with [`restore_layer`], or once `timeout` elapsed if it is not `None`.
+///
+/// A surface on the overlay layer can't be raised.
+///
+/// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:request:set_layer>
+pub fn raise_layer<Message>(id: SurfaceId, timeout: Option<Duration>) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::LayerSurface(
+            wayland::layer_surface::Action::Raise { id, timeout },
+        )),
+    ))
+}
+
+/// Moves a layer surface raised with [`raise_layer`] back to its own layer.
+pub fn restore_layer<Message>(id: SurfaceId) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::LayerSurface(
+            wayland::layer_surface::Action::Restore(id),
+        )),
+    ))
+}
+
+/// Restricts pointer and touch input of the surface to the given rectangles, in
+/// surface local coordinates. Input outside of them passes through to the
+/// surfaces below. `None` or an empty list make the whole surface click-through.
+///
+/// <https://wayland.app/protocols/wayland#wl_surface:request:set_input_region>
+pub fn set_input_region<Message>(
+    id: SurfaceId,
+    region: Option<Vec<Rectangle<i32>>>,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::LayerSurface(
+            wayland::layer_surface::Action::InputRegion { id, region },
+        )),
+    ))
+}
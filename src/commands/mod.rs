@@ -1,6 +1,15 @@
 //! Interact with the wayland objects of your application.
 
+pub mod connection;
 pub mod data_device;
+pub mod dmabuf;
+pub mod frame_stats;
+pub mod idle_notify;
+pub mod keyboard;
 pub mod layer_surface;
+pub mod output;
+pub mod pointer;
 pub mod popup;
+pub mod seat;
+pub mod surface;
 pub mod window;
@@ -0,0 +1,32 @@
+//! Interact with the outputs of your application.
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+use sctk::{output::OutputInfo, reexports::client::protocol::wl_output::WlOutput};
+
+/// An output known to the application.
+#[derive(Debug, Clone)]
+pub struct OutputDescriptor {
+    /// the handle of the output, which can be used to place surfaces on it
+    pub output: WlOutput,
+    /// the latest information sent by the compositor, if any was received yet
+    pub info: Option<OutputInfo>,
+}
+
+/// Fetches every output that is currently advertised by the compositor.
+pub fn fetch_outputs<Message>(
+    f: impl FnOnce(Vec<OutputDescriptor>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Output(
+            wayland::output::Action::FetchOutputs(Box::new(move |outputs| {
+                f(outputs
+                    .into_iter()
+                    .map(|(output, info)| OutputDescriptor { output, info })
+                    .collect())
+            })),
+        )),
+    ))
+}
@@ -0,0 +1,102 @@
+//! Interact with the pointers of the seats of your application.
+use iced_native::{
+    command::{
+        self,
+        platform_specific::{self, wayland},
+        Command,
+    },
+    window, Point,
+};
+
+/// Sets the cursor of every pointer over the application's surfaces to an image.
+///
+/// `pixels` are RGBA, row by row, and must hold `width * height` pixels. The image
+/// is drawn at `scale`, so an image for a scale of 2 should be twice as large as the
+/// cursor appears in logical pixels. The `hotspot` is in logical pixels.
+///
+/// The image takes precedence over the cursor picked by the widgets, until it is
+/// removed with [`unset_cursor_image`].
+///
+/// <https://wayland.app/protocols/wayland#wl_pointer:request:set_cursor>
+pub fn set_cursor_image<Message>(
+    pixels: Vec<u8>,
+    width: u32,
+    height: u32,
+    hotspot: (i32, i32),
+    scale: i32,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Pointer(
+            wayland::pointer::Action::SetCursorImage {
+                pixels,
+                width,
+                height,
+                hotspot,
+                scale,
+            },
+        )),
+    ))
+}
+
+/// Removes the cursor image set with [`set_cursor_image`]. The pointers over the
+/// application's surfaces show the default cursor of the cursor theme again, i.e. the one
+/// of [`Settings::ptr_theme`] if it is set.
+///
+/// [`Settings::ptr_theme`]: crate::settings::Settings::ptr_theme
+pub fn unset_cursor_image<Message>() -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Pointer(
+            wayland::pointer::Action::UnsetCursorImage,
+        )),
+    ))
+}
+
+/// Inverts the scroll direction of the pointers, i.e. natural scrolling, or restores it.
+/// This applies to wheels and fingers alike, on top of an inversion by the compositor.
+///
+/// Overrides [`Settings::invert_scroll`].
+///
+/// [`Settings::invert_scroll`]: crate::settings::Settings::invert_scroll
+pub fn set_scroll_inverted<Message>(inverted: bool) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Pointer(
+            wayland::pointer::Action::SetScrollInverted(inverted),
+        )),
+    ))
+}
+
+/// Multiplies the horizontal and vertical scroll deltas of the pointers, for wheels and
+/// fingers alike, e.g. `(1.0, 2.0)` scrolls twice as far vertically. `(1.0, 1.0)` restores
+/// the deltas provided by the compositor.
+///
+/// Overrides [`Settings::scroll_sensitivity`].
+///
+/// [`Settings::scroll_sensitivity`]: crate::settings::Settings::scroll_sensitivity
+pub fn set_scroll_sensitivity<Message>(
+    horizontal: f32,
+    vertical: f32,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Pointer(
+            wayland::pointer::Action::SetScrollSensitivity {
+                horizontal,
+                vertical,
+            },
+        )),
+    ))
+}
+
+/// Fetches the surface under the pointer of the active seat, and the position of the
+/// pointer on it in surface-local coordinates, e.g. to open a popup at the cursor.
+/// The position is not divided by the scale factor of the application, so it must be
+/// divided by it to compare it with the layout of a scaled application.
+/// Produces `None` if the pointer is not over any of the application's surfaces.
+pub fn fetch_pointer_position<Message>(
+    f: impl FnOnce(Option<(window::Id, Point)>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Pointer(
+            wayland::pointer::Action::FetchPosition(Box::new(f)),
+        )),
+    ))
+}
@@ -11,8 +11,21 @@ use iced_native::command::{
 };
 use iced_native::window::Id as SurfaceId;
 use iced_native::{command::Command, window};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
 pub use window::{Event, Mode};
 
+/// Creates a popup, placed relative to the `anchor_rect` of its positioner, which may be
+/// any non-empty rectangle within the parent in its logical coordinates, e.g. a 1x1
+/// rectangle at the cursor for a context menu. The popup is not created if the rectangle
+/// is empty or not within the parent. The size it requests is kept within the `size_limits`
+/// of the positioner, the compositor may still adjust it to fit the output.
+///
+/// Popups of layer surfaces are created with the layer shell, and popups of windows and
+/// other popups with xdg shell. With `grab` set, the popup is grabbed like with
+/// [`grab_popup`]; the layer surface a grabbed popup belongs to becomes interactive on
+/// demand until the popup is destroyed if it did not accept keyboard input, so the popup
+/// can take it.
+///
 /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:request:get_popup>
 /// <https://wayland.app/protocols/xdg-shell#xdg_surface:request:get_popup>
 pub fn get_popup<Message>(popup: SctkPopupSettings) -> Command<Message> {
@@ -24,6 +37,13 @@ pub fn get_popup<Message>(popup: SctkPopupSettings) -> Command<Message> {
     ))
 }
 
+/// Moves or resizes a popup with a new positioner, e.g. for a menu which grows with its
+/// items. The size of the positioner is clamped to its `size_limits`, the compositor may
+/// still adjust it to fit the output.
+///
+/// Once applied, the popup receives a `wayland::PopupEvent::Repositioned` event, whose token
+/// counts the repositions of the popup, starting at `1`.
+///
 /// <https://wayland.app/protocols/xdg-shell#xdg_popup:request:reposition>
 pub fn reposition_popup<Message>(id: SurfaceId, positioner: SctkPositioner) -> Command<Message> {
     Command::single(command::Action::PlatformSpecific(
@@ -33,7 +53,11 @@ pub fn reposition_popup<Message>(id: SurfaceId, positioner: SctkPositioner) -> C
     ))
 }
 
-// https://wayland.app/protocols/xdg-shell#xdg_popup:request:grab
+/// Grabs the popup with the seat which pressed a pointer button last, so it is dismissed
+/// when that seat interacts with another surface. Must be requested right after the popup
+/// is created, while the button is held.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_popup:request:grab>
 pub fn grab_popup<Message>(id: SurfaceId) -> Command<Message> {
     Command::single(command::Action::PlatformSpecific(
         platform_specific::Action::Wayland(wayland::Action::Popup(wayland::popup::Action::Grab {
@@ -42,6 +66,21 @@ pub fn grab_popup<Message>(id: SurfaceId) -> Command<Message> {
     ))
 }
 
+/// Grabs the popup with `seat`, using the button press of that seat which is still held.
+/// Like [`grab_popup`], it must be requested right after the popup is created. A mapped
+/// popup can't be grabbed again, so to change its seat, e.g. when another seat becomes
+/// active, it has to be destroyed with [`destroy_popup`] and created again with
+/// [`get_popup`].
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_popup:request:grab>
+pub fn grab_popup_with_seat<Message>(id: SurfaceId, seat: WlSeat) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Popup(
+            wayland::popup::Action::GrabWithSeat { id, seat },
+        )),
+    ))
+}
+
 /// <https://wayland.app/protocols/xdg-shell#xdg_popup:request:destroy>
 pub fn destroy_popup<Message>(id: SurfaceId) -> Command<Message> {
     Command::single(command::Action::PlatformSpecific(
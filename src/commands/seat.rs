@@ -0,0 +1,32 @@
+//! Interact with the seats of your application.
+use iced_native::command::{
+    self,
+    platform_specific::{self, wayland},
+    Command,
+};
+pub use iced_native::command::platform_specific::wayland::seat::SeatInfo;
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+
+/// Fetches the seats of the application with their name and capabilities.
+/// The active seat, which receives the input of the application, comes first.
+///
+/// <https://wayland.app/protocols/wayland#wl_seat>
+pub fn fetch_seats<Message>(
+    f: impl FnOnce(Vec<SeatInfo>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Seat(
+            wayland::seat::Action::FetchSeats(Box::new(f)),
+        )),
+    ))
+}
+
+/// Makes the given seat the active seat, and keeps it active until it is removed.
+/// Input of the other seats no longer switches the active seat in the meantime.
+pub fn set_active_seat<Message>(seat: WlSeat) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Seat(
+            wayland::seat::Action::SetActive(seat),
+        )),
+    ))
+}
@@ -0,0 +1,218 @@
+//! Interact with the surfaces of your application, regardless of their role.
+use crate::backend::SurfaceHandle;
+use iced_native::{
+    command::{
+        self,
+        platform_specific::{self, wayland},
+        Command,
+    },
+    window::Id as SurfaceId,
+};
+pub use sctk::reexports::client::protocol::wl_output::Transform;
+use sctk::reexports::client::{backend::ObjectId, protocol::wl_surface::WlSurface, Proxy};
+
+/// The Wayland surface backing a window, layer surface, popup or custom surface.
+///
+/// Meant for integrations which need to attach their own objects to the surface,
+/// e.g. subsurfaces or external renderers. The surface stays owned by the
+/// application and must not be destroyed through this handle.
+#[derive(Debug, Clone)]
+pub struct RawSurface {
+    wl_surface: WlSurface,
+}
+
+impl RawSurface {
+    /// Returns the underlying [`WlSurface`].
+    pub fn wl_surface(&self) -> &WlSurface {
+        &self.wl_surface
+    }
+
+    /// Returns the protocol id of the surface.
+    pub fn object_id(&self) -> ObjectId {
+        self.wl_surface.id()
+    }
+}
+
+/// Fetches the Wayland surface of the surface, `None` if it does not exist.
+pub fn fetch_raw_surface<Message>(
+    id: SurfaceId,
+    f: impl FnOnce(Option<RawSurface>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::FetchWlSurface(
+                id,
+                Box::new(move |wl_surface: Option<WlSurface>| {
+                    f(wl_surface.map(|wl_surface| RawSurface { wl_surface }))
+                }),
+            ),
+        )),
+    ))
+}
+
+/// Fetches the integer scale the compositor prefers for the buffers of the surface.
+///
+/// <https://wayland.app/protocols/wayland#wl_surface:event:preferred_buffer_scale>
+pub fn fetch_scale_factor<Message>(
+    id: SurfaceId,
+    f: impl FnOnce(Option<i32>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::FetchScaleFactor(id, Box::new(f)),
+        )),
+    ))
+}
+
+/// Lets the pointer and touch input pass through the surface.
+///
+/// <https://wayland.app/protocols/wayland#wl_surface:request:set_input_region>
+pub fn set_click_through<Message>(id: SurfaceId, click_through: bool) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::ClickThrough { id, click_through },
+        )),
+    ))
+}
+
+/// Draws the surface at `scale` instead of the preferred one, `None` reverts it.
+///
+/// <https://wayland.app/protocols/wayland#wl_surface:request:set_buffer_scale>
+pub fn set_scale_override<Message>(id: SurfaceId, scale: Option<i32>) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::ScaleOverride { id, scale },
+        )),
+    ))
+}
+
+/// Sets the transform of the buffers of the surface.
+///
+/// <https://wayland.app/protocols/wayland#wl_surface:request:set_buffer_transform>
+pub fn set_buffer_transform<Message>(id: SurfaceId, transform: Transform) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::BufferTransform { id, transform },
+        )),
+    ))
+}
+
+/// Requests the keyboard focus for the surface, `false` if it can't be requested.
+///
+/// <https://wayland.app/protocols/xdg-activation-v1#xdg_activation_v1:request:activate>
+pub fn request_keyboard_focus<Message>(
+    id: SurfaceId,
+    f: impl FnOnce(bool) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::RequestKeyboardFocus(id, Box::new(f)),
+        )),
+    ))
+}
+
+/// Recreates the renderer surface of the surface, and redraws it.
+pub fn recreate_surface<Message>(id: SurfaceId) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::Recreate(id),
+        )),
+    ))
+}
+
+/// Fetches the raw window and display handles of the surface, `None` if it is gone.
+pub fn fetch_surface_handle<Message>(
+    id: SurfaceId,
+    f: impl FnOnce(Option<SurfaceHandle>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::FetchWlSurface(
+                id,
+                Box::new(move |wl_surface: Option<WlSurface>| {
+                    f(wl_surface.as_ref().and_then(SurfaceHandle::new))
+                }),
+            ),
+        )),
+    ))
+}
+
+/// Holds back the redraws after each configure until [`release_configure`].
+pub fn set_hold_configures<Message>(id: SurfaceId, enabled: bool) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::ManualConfigureAck { id, enabled },
+        )),
+    ))
+}
+
+/// Fetches the serial of the latest configure of the surface.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_surface:event:configure>
+pub fn fetch_configure_serial<Message>(
+    id: SurfaceId,
+    f: impl FnOnce(Option<u32>) -> Message + 'static,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::FetchConfigureSerial(id, Box::new(f)),
+        )),
+    ))
+}
+
+/// Releases the held back configure with `serial`, and redraws the surface.
+pub fn release_configure<Message>(id: SurfaceId, serial: u32) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::AckConfigure { id, serial },
+        )),
+    ))
+}
+
+/// Creates a subsurface of `parent` at `position`.
+///
+/// <https://wayland.app/protocols/wayland#wl_subcompositor:request:get_subsurface>
+pub fn get_subsurface<Message>(
+    id: SurfaceId,
+    parent: SurfaceId,
+    position: (i32, i32),
+    sync: bool,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::Subsurface {
+                id,
+                parent,
+                position,
+                sync,
+            },
+        )),
+    ))
+}
+
+/// <https://wayland.app/protocols/wayland#wl_subsurface:request:set_position>
+pub fn set_subsurface_position<Message>(id: SurfaceId, position: (i32, i32)) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::SubsurfacePosition { id, position },
+        )),
+    ))
+}
+
+/// <https://wayland.app/protocols/wayland#wl_subsurface:request:set_sync>
+pub fn set_subsurface_sync<Message>(id: SurfaceId, sync: bool) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::SubsurfaceSync { id, sync },
+        )),
+    ))
+}
+
+/// <https://wayland.app/protocols/wayland#wl_subsurface:request:destroy>
+pub fn destroy_subsurface<Message>(id: SurfaceId) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Surface(
+            wayland::surface::Action::DestroySubsurface(id),
+        )),
+    ))
+}
@@ -6,7 +6,9 @@ use iced_native::command::platform_specific::{
     self,
     wayland::{self, window::SctkWindowSettings},
 };
-use iced_native::window;
+pub use iced_native::command::platform_specific::wayland::window::PlacementHint;
+use iced_native::{window, Rectangle};
+use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge;
 
 pub use window::Action;
 pub use window::{Event, Mode};
@@ -41,6 +43,125 @@ pub fn resize_window<Message>(id: window::Id, width: u32, height: u32) -> Comman
     ))
 }
 
+/// Fixes the size of the window, or lets it be resized again.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:set_min_size>
+pub fn set_resizable<Message>(id: window::Id, resizable: bool) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::Resizable { id, resizable },
+        )),
+    ))
+}
+
+/// Minimizes the window.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:set_minimized>
+pub fn minimize_window<Message>(id: window::Id) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::Minimize { id },
+        )),
+    ))
+}
+
+/// Asks the compositor to draw the attention of the user to the window.
+///
+/// <https://wayland.app/protocols/xdg-activation-v1#xdg_activation_v1:request:activate>
+pub fn request_attention<Message>(id: window::Id) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::RequestAttention { id },
+        )),
+    ))
+}
+
+/// Hints where the popup should be placed, windows can't be placed.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_positioner>
+pub fn set_placement_hint<Message>(id: window::Id, hint: PlacementHint) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::PlacementHint { id, hint },
+        )),
+    ))
+}
+
+/// Starts moving the window with the pointer button which is held on it.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:move>
+pub fn start_interactive_move<Message>(id: window::Id) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::InteractiveMove { id },
+        )),
+    ))
+}
+
+/// Starts resizing the window from `edge` with the pointer button which is held on it.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:resize>
+pub fn start_interactive_resize<Message>(id: window::Id, edge: ResizeEdge) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::InteractiveResize { id, edge },
+        )),
+    ))
+}
+
+/// Shows the window menu of the compositor at `x`, `y` in the window.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:show_window_menu>
+pub fn show_window_menu<Message>(id: window::Id, x: i32, y: i32) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::ShowWindowMenu { id, x, y },
+        )),
+    ))
+}
+
+/// Sets the visible part of the window, `None` makes all of it visible.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_surface:request:set_window_geometry>
+pub fn set_geometry_window<Message>(
+    id: window::Id,
+    geometry: Option<Rectangle<i32>>,
+) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::Geometry { id, geometry },
+        )),
+    ))
+}
+
+/// Makes the window fullscreen, and inhibits the shortcuts of the compositor while it is.
+///
+/// <https://wayland.app/protocols/keyboard-shortcuts-inhibit-unstable-v1>
+pub fn set_fullscreen_window<Message>(
+    id: window::Id,
+    inhibit_shortcuts: bool,
+) -> Command<Message> {
+    let action = if inhibit_shortcuts {
+        wayland::window::Action::FullscreenInhibitShortcuts { id }
+    } else {
+        wayland::window::Action::Fullscreen { id }
+    };
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(action)),
+    ))
+}
+
+/// Makes the window leave fullscreen.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:unset_fullscreen>
+pub fn unset_fullscreen_window<Message>(id: window::Id) -> Command<Message> {
+    Command::single(command::Action::PlatformSpecific(
+        platform_specific::Action::Wayland(wayland::Action::Window(
+            wayland::window::Action::UnsetFullscreen { id },
+        )),
+    ))
+}
+
 /// Sets the [`Mode`] of the window.
 pub fn set_mode_window<Message>(id: window::Id, mode: Mode) -> Command<Message> {
     Command::single(command::Action::Window(id, Action::SetMode(mode)))
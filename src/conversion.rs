@@ -20,23 +20,56 @@ pub fn pointer_button_to_native(button: u32) -> Option<mouse::Button> {
     }
 }
 
+/// The distance a wheel step scrolls in surface local coordinates, used for wheels which
+/// do not report discrete steps.
+const WHEEL_STEP: f64 = 10.0;
+
+/// Converts a frame of axis events to a scroll delta, or `None` if nothing was scrolled.
+///
+/// Wheel steps are scrolled in lines, while fingers and continuous sources scroll in pixels.
+/// Without a source, the presence of discrete steps decides. The deltas are multiplied by the
+/// horizontal and vertical `factor`, which is negative to invert the scroll direction.
 pub fn pointer_axis_to_native(
     source: Option<AxisSource>,
     horizontal: AxisScroll,
     vertical: AxisScroll,
+    factor: (f32, f32),
 ) -> Option<ScrollDelta> {
-    source.map(|source| match source {
-        AxisSource::Wheel | AxisSource::WheelTilt => ScrollDelta::Lines {
-            x: horizontal.discrete as f32,
-            y: vertical.discrete as f32,
-        },
-        AxisSource::Finger | AxisSource::Continuous | _ => ScrollDelta::Pixels {
-            x: horizontal.absolute as f32,
-            y: vertical.absolute as f32,
-        },
+    let scrolled = |axis: &AxisScroll| axis.absolute != 0.0 || axis.discrete != 0;
+    if !scrolled(&horizontal) && !scrolled(&vertical) {
+        return None;
+    }
+    let is_wheel = match source {
+        Some(AxisSource::Wheel) | Some(AxisSource::WheelTilt) => true,
+        Some(_) => false,
+        None => horizontal.discrete != 0 || vertical.discrete != 0,
+    };
+    let lines = |axis: AxisScroll| {
+        if axis.discrete != 0 {
+            axis.discrete as f32
+        } else {
+            (axis.absolute / WHEEL_STEP) as f32
+        }
+    };
+    Some(if is_wheel {
+        ScrollDelta::Lines {
+            x: lines(horizontal) * factor.0,
+            y: lines(vertical) * factor.1,
+        }
+    } else {
+        ScrollDelta::Pixels {
+            x: horizontal.absolute as f32 * factor.0,
+            y: vertical.absolute as f32 * factor.1,
+        }
     })
 }
 
+/// Whether a frame of axis events ends a scroll, e.g. when fingers are lifted from a
+/// touchpad, after which kinetic scrolling may start.
+pub fn pointer_axis_stopped(horizontal: AxisScroll, vertical: AxisScroll) -> bool {
+    horizontal.stop || vertical.stop
+}
+
 pub fn modifiers_to_native(mods: Modifiers) -> keyboard::Modifiers {
     let mut native_mods = keyboard::Modifiers::empty();
     if mods.alt {
@@ -249,3 +282,42 @@ pub fn keysym_to_vkey(keysym: u32) -> Option<KeyCode> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis(absolute: f64, discrete: i32) -> AxisScroll {
+        AxisScroll {
+            absolute,
+            discrete,
+            stop: false,
+        }
+    }
+
+    #[test]
+    fn wheel_steps_scroll_lines_and_fingers_pixels() {
+        let scroll = |source, horizontal, vertical| {
+            pointer_axis_to_native(source, horizontal, vertical, (1.0, 1.0))
+        };
+        assert_eq!(
+            scroll(Some(AxisSource::Wheel), axis(0.0, 0), axis(15.0, 1)),
+            Some(ScrollDelta::Lines { x: 0.0, y: 1.0 })
+        );
+        assert_eq!(
+            scroll(Some(AxisSource::Finger), axis(3.0, 0), axis(4.5, 0)),
+            Some(ScrollDelta::Pixels { x: 3.0, y: 4.5 })
+        );
+        // without a source, scrolling without discrete steps is continuous
+        assert_eq!(
+            scroll(None, axis(0.0, 0), axis(20.0, 0)),
+            Some(ScrollDelta::Pixels { x: 0.0, y: 20.0 })
+        );
+        // without discrete steps, a wheel scrolls a line every `WHEEL_STEP`
+        assert_eq!(
+            scroll(Some(AxisSource::WheelTilt), axis(20.0, 0), axis(0.0, 0)),
+            Some(ScrollDelta::Lines { x: 2.0, y: 0.0 })
+        );
+        assert_eq!(scroll(Some(AxisSource::Wheel), axis(0.0, 0), axis(0.0, 0)), None);
+    }
+}
@@ -3,22 +3,54 @@ use std::num::NonZeroU32;
 use glutin::{api::egl, config::ConfigSurfaceTypes, prelude::GlDisplay, surface::WindowSurface};
 use sctk::reexports::client::{protocol::wl_surface, Proxy};
 
+use crate::settings::EglSettings;
+
+/// An error that occurred while initializing EGL.
+#[derive(Debug, thiserror::Error)]
+pub enum EglError {
+    /// The connection to the compositor was closed.
+    #[error("the connection to the compositor was closed")]
+    ConnectionClosed,
+    /// The EGL display of the Wayland platform could not be created.
+    #[error("the EGL display could not be created: {0}")]
+    Display(glutin::error::Error),
+    /// The configs of the display could not be queried.
+    #[error("the EGL configs could not be queried: {0}")]
+    Config(glutin::error::Error),
+    /// No config supports drawing to the surfaces.
+    #[error("no EGL config is available")]
+    NoConfig,
+    /// Neither an OpenGL nor an OpenGL ES context could be created.
+    #[error("the EGL context could not be created: {0}")]
+    Context(glutin::error::Error),
+    /// The EGL surface of the first surface could not be created.
+    #[error("the EGL surface could not be created: {0}")]
+    Surface(glutin::error::Error),
+    /// The context could not be made current with the first surface.
+    #[error("the EGL context could not be made current: {0}")]
+    MakeCurrent(glutin::error::Error),
+}
+
 /// helper for initializing egl after creation of the first layer surface / window
 pub fn init_egl(
     surface: &wl_surface::WlSurface,
     width: u32,
     height: u32,
-) -> (
-    egl::display::Display,
-    egl::context::NotCurrentContext,
-    glutin::api::egl::config::Config,
-    egl::surface::Surface<glutin::surface::WindowSurface>,
-) {
+    settings: &EglSettings,
+) -> Result<
+    (
+        egl::display::Display,
+        egl::context::NotCurrentContext,
+        glutin::api::egl::config::Config,
+        egl::surface::Surface<glutin::surface::WindowSurface>,
+    ),
+    EglError,
+> {
     let mut display_handle = raw_window_handle::WaylandDisplayHandle::empty();
     display_handle.display = surface
         .backend()
         .upgrade()
-        .expect("Connection has been closed")
+        .ok_or(EglError::ConnectionClosed)?
         .display_ptr() as *mut _;
     let display_handle = raw_window_handle::RawDisplayHandle::Wayland(display_handle);
     let mut window_handle = raw_window_handle::WaylandWindowHandle::empty();
@@ -28,19 +60,25 @@ pub fn init_egl(
     // Initialize the EGL Wayland platform
     //
     // SAFETY: The connection is valid.
-    let display = unsafe { egl::display::Display::new(display_handle) }
-        .expect("Failed to initialize Wayland EGL platform");
+    let display =
+        unsafe { egl::display::Display::new(display_handle) }.map_err(EglError::Display)?;
 
     // Find a suitable config for the window.
-    let config_template = glutin::config::ConfigTemplateBuilder::default()
+    let mut config_template = glutin::config::ConfigTemplateBuilder::default()
         .compatible_with_native_window(window_handle)
         .with_surface_type(ConfigSurfaceTypes::WINDOW)
         .with_api(glutin::config::Api::GLES2)
-        .build();
+        .with_alpha_size(8)
+        .with_depth_size(settings.depth)
+        .with_stencil_size(settings.stencil);
+    if settings.samples > 0 {
+        config_template = config_template.with_multisampling(settings.samples);
+    }
+    let config_template = config_template.build();
     let config = unsafe { display.find_configs(config_template) }
-        .unwrap()
+        .map_err(EglError::Config)?
         .next()
-        .expect("No available configs");
+        .ok_or(EglError::NoConfig)?;
     let gl_attrs = glutin::context::ContextAttributesBuilder::default()
         .with_context_api(glutin::context::ContextApi::OpenGl(None))
         .build(Some(window_handle));
@@ -50,18 +88,19 @@ pub fn init_egl(
 
     let context = unsafe { display.create_context(&config, &gl_attrs) }
         .or_else(|_| unsafe { display.create_context(&config, &gles_attrs) })
-        .expect("Failed to create context");
+        .map_err(EglError::Context)?;
 
     let surface_attrs = glutin::surface::SurfaceAttributesBuilder::<WindowSurface>::default()
+        .with_srgb(Some(settings.srgb))
         .build(
             window_handle,
-            NonZeroU32::new(width).unwrap(),
-            NonZeroU32::new(height).unwrap(),
+            NonZeroU32::new(width.max(1)).unwrap(),
+            NonZeroU32::new(height.max(1)).unwrap(),
         );
     let surface = unsafe { display.create_window_surface(&config, &surface_attrs) }
-        .expect("Failed to create surface");
+        .map_err(EglError::Surface)?;
 
-    (display, context, config, surface)
+    Ok((display, context, config, surface))
 }
 
 pub fn get_surface(
@@ -70,11 +109,13 @@ pub fn get_surface(
     surface: &wl_surface::WlSurface,
     width: u32,
     height: u32,
+    srgb: bool,
 ) -> egl::surface::Surface<glutin::surface::WindowSurface> {
     let mut window_handle = raw_window_handle::WaylandWindowHandle::empty();
     window_handle.surface = surface.id().as_ptr() as *mut _;
     let window_handle = raw_window_handle::RawWindowHandle::Wayland(window_handle);
     let surface_attrs = glutin::surface::SurfaceAttributesBuilder::<WindowSurface>::default()
+        .with_srgb(Some(srgb))
         .build(
             window_handle,
             NonZeroU32::new(width).unwrap(),
@@ -1,5 +1,7 @@
 use iced_futures::futures;
 
+use crate::{egl::EglError, event_loop::state::LayerSurfaceCreationError};
+
 /// An error that occurred while running an application.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -11,9 +13,17 @@ pub enum Error {
     #[error("the application window could not be created")]
     WindowCreationFailed(Box<dyn std::error::Error + Send + Sync>),
 
+    /// The initial layer surface could not be created.
+    #[error("the initial layer surface could not be created")]
+    LayerSurfaceCreationFailed(#[source] LayerSurfaceCreationError),
+
     /// The application graphics context could not be created.
     #[error("the application graphics context could not be created")]
     GraphicsCreationFailed(iced_graphics::Error),
+
+    /// EGL could not be initialized, e.g. to fall back to another backend.
+    #[error("EGL could not be initialized")]
+    EglInitFailed(#[source] EglError),
 }
 
 impl From<iced_graphics::Error> for Error {
@@ -21,3 +31,15 @@ impl From<iced_graphics::Error> for Error {
         Error::GraphicsCreationFailed(error)
     }
 }
+
+impl From<LayerSurfaceCreationError> for Error {
+    fn from(error: LayerSurfaceCreationError) -> Error {
+        Error::LayerSurfaceCreationFailed(error)
+    }
+}
+
+impl From<EglError> for Error {
+    fn from(error: EglError) -> Error {
+        Error::EglInitFailed(error)
+    }
+}
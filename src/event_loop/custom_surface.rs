@@ -0,0 +1,30 @@
+//! Surfaces with a role which iced-sctk does not know about.
+use std::fmt::Debug;
+
+use sctk::reexports::client::{protocol::wl_surface::WlSurface, Connection};
+
+/// Provides the role of a surface which is not natively supported, such as a
+/// fullscreen-shell surface, while the egl, rendering and event plumbing of
+/// the surface is handled like any other surface.
+///
+/// The handler owns the role objects, and is expected to dispatch them on an
+/// event queue of its own.
+pub trait SurfaceRoleHandler: Debug {
+    /// Assigns the role to the newly created `wl_surface`.
+    fn assign_role(&mut self, conn: &Connection, wl_surface: &WlSurface);
+
+    /// Dispatches the events of the role, returning the new logical size of the
+    /// surface if the role was configured since the last call.
+    fn configure(&mut self) -> Option<(u32, u32)>;
+
+    /// Returns whether the role was closed by the compositor.
+    fn closed(&self) -> bool {
+        false
+    }
+
+    /// Called right before the surface is redrawn.
+    fn before_draw(&mut self, _wl_surface: &WlSurface) {}
+
+    /// Called when the surface is destroyed.
+    fn destroy(&mut self) {}
+}
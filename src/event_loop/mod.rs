@@ -1,9 +1,10 @@
 pub mod control_flow;
+pub mod custom_surface;
 pub mod proxy;
 pub mod state;
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Debug,
     mem,
     time::{Duration, Instant},
@@ -12,8 +13,8 @@ use std::{
 use crate::{
     application::Event,
     sctk_event::{
-        IcedSctkEvent, LayerSurfaceEventVariant, PopupEventVariant, SctkEvent, StartCause,
-        SurfaceUserRequest, WindowEventVariant,
+        CustomSurfaceEventVariant, IcedSctkEvent, LayerSurfaceEventVariant, PopupEventVariant,
+        SctkEvent, StartCause, SurfaceUserRequest, WindowEventVariant,
     },
     settings,
 };
@@ -22,14 +23,21 @@ use iced_native::command::platform_specific::{
     self,
     wayland::{layer_surface::SctkLayerSurfaceSettings, window::SctkWindowSettings},
 };
+use iced_native::window::Mode;
 use sctk::{
     compositor::CompositorState,
     event_loop::WaylandSource,
     output::OutputState,
     reexports::{
-        calloop::{self, EventLoop},
+        calloop::{
+            self,
+            timer::{TimeoutAction, Timer},
+            EventLoop,
+        },
         client::{
-            backend::ObjectId, globals::registry_queue_init, protocol::wl_surface::WlSurface,
+            backend::ObjectId,
+            globals::{registry_queue_init, BindError, GlobalError},
+            protocol::{wl_output::WlOutput, wl_surface::WlSurface},
             ConnectError, Connection, DispatchError, Proxy,
         },
     },
@@ -45,6 +53,7 @@ use wayland_backend::client::WaylandError;
 
 use self::{
     control_flow::ControlFlow,
+    custom_surface::SurfaceRoleHandler,
     state::{LayerSurfaceCreationError, SctkState},
 };
 
@@ -73,6 +82,12 @@ pub struct SctkEventLoop<T> {
     pub(crate) wayland_dispatcher:
         calloop::Dispatcher<'static, WaylandSource<SctkState<T>>, SctkState<T>>,
     pub(crate) features: Features,
+    /// the registration of the wayland source in the calloop event loop
+    pub(crate) wayland_token: calloop::RegistrationToken,
+    /// how often reconnecting to the compositor is attempted after losing the connection
+    pub(crate) reconnect_attempts: u32,
+    /// the failed attempts to reconnect since the connection was lost
+    pub(crate) failed_reconnects: u32,
     /// A proxy to wake up event loop.
     pub event_loop_awakener: calloop::ping::Ping,
     /// A sender for submitting user events in the event loop
@@ -84,7 +99,7 @@ impl<T> SctkEventLoop<T>
 where
     T: 'static + Debug,
 {
-    pub(crate) fn new<F: Sized>(_settings: &settings::Settings<F>) -> Result<Self, ConnectError> {
+    pub(crate) fn new<F: Sized>(settings: &settings::Settings<F>) -> Result<Self, ConnectError> {
         let connection = Connection::connect_to_env()?;
         let _display = connection.display();
         let (globals, event_queue) = registry_queue_init(&connection).unwrap();
@@ -95,14 +110,9 @@ where
         let registry_state = RegistryState::new(&globals);
 
         let (ping, ping_source) = calloop::ping::make_ping().unwrap();
-        // TODO
+        // the ping only wakes up the loop, the user events are drained by their channel
         loop_handle
-            .insert_source(ping_source, |_, _, _state| {
-                // Drain events here as well to account for application doing batch event processing
-                // on RedrawEventsCleared.
-                // shim::handle_window_requests(state);
-                todo!()
-            })
+            .insert_source(ping_source, |_, _, _state| {})
             .unwrap();
         let (user_events_sender, user_events_channel) = calloop::channel::channel();
 
@@ -121,14 +131,19 @@ where
                 queue.dispatch_pending(winit_state)
             });
 
-        let _wayland_source_dispatcher = event_loop
+        let wayland_token = event_loop
             .handle()
             .register_dispatcher(wayland_dispatcher.clone())
             .unwrap();
 
+        let shm_state = ShmState::bind(&globals, &qh).expect("wl_shm is not available");
+
         Ok(Self {
             event_loop,
             wayland_dispatcher,
+            wayland_token,
+            reconnect_attempts: settings.reconnect_attempts,
+            failed_reconnects: 0,
             state: SctkState {
                 connection,
                 registry_state,
@@ -136,23 +151,58 @@ where
                 output_state: OutputState::new(&globals, &qh),
                 compositor_state: CompositorState::bind(&globals, &qh)
                     .expect("wl_compositor is not available"),
-                shm_state: ShmState::bind(&globals, &qh).expect("wl_shm is not available"),
+                shm_state,
                 xdg_shell_state: XdgShellState::bind(&globals, &qh)
                     .expect("xdg shell is not available"),
                 xdg_window_state: XdgWindowState::bind(&globals, &qh),
                 layer_shell: LayerShell::bind(&globals, &qh).ok(),
+                subcompositor: globals.bind(&qh, 1..=1, ()).ok(),
+                idle_notifier: globals.bind(&qh, 1..=1, ()).ok(),
+                idle_timeout: settings.idle_timeout,
+                kbd_keymap: settings.kbd_keymap.clone(),
+                ptr_theme: settings.ptr_theme.clone(),
+                cursor_theme: None,
+                seat_pinned: false,
+                touch_emulates_pointer: settings.touch_emulates_pointer,
+                app_id: settings.app_id.clone(),
+                invert_scroll: settings.invert_scroll,
+                scroll_sensitivity: settings.scroll_sensitivity.unwrap_or((1.0, 1.0)),
+                frame_interval: state::frame_interval(settings.max_frame_rate),
+                last_redraws: HashMap::new(),
+                throttled_redraws: HashMap::new(),
+                batched_redraws: None,
+                unconfigured_redraws: HashSet::new(),
+                data_device_manager: globals.bind(&qh, 1..=3, ()).ok(),
+                primary_selection_manager: globals.bind(&qh, 1..=1, ()).ok(),
+                activation: globals.bind(&qh, 1..=1, ()).ok(),
+                shortcuts_inhibit_manager: globals.bind(&qh, 1..=1, ()).ok(),
+                scale_factors: HashMap::new(),
+                scale_overrides: HashMap::new(),
+                empty_region: None,
+                dnd_offer: None,
+                drag_source: None,
+                repeat_disabled: None,
+                reconnect_due: false,
+                keyboard_grab: None,
+                dmabuf: globals.bind(&qh, 3..=3, ()).ok(),
+                dmabuf_formats: Vec::new(),
+                held_configures: HashMap::new(),
 
                 // data_device_manager_state: DataDeviceManagerState::new(),
                 queue_handle: qh,
                 loop_handle: loop_handle,
 
                 cursor_surface: None,
-                multipool: None,
+                cursor_image: None,
+                cursor_upload_pending: false,
+                shm_buffers: HashMap::new(),
                 outputs: Vec::new(),
                 seats: Vec::new(),
                 windows: Vec::new(),
                 layer_surfaces: Vec::new(),
                 popups: Vec::new(),
+                custom_surfaces: Vec::new(),
+                subsurfaces: Vec::new(),
                 kbd_focus: None,
                 window_user_requests: HashMap::new(),
                 window_compositor_updates: HashMap::new(),
@@ -170,9 +220,14 @@ where
     }
 
     pub fn proxy(&self) -> proxy::Proxy<Event<T>> {
-        proxy::Proxy::new(self.user_events_sender.clone())
+        proxy::Proxy::new(
+            self.user_events_sender.clone(),
+            self.event_loop_awakener.clone(),
+        )
     }
 
+    /// Creates a layer surface with the namespace of its own settings, so that surfaces
+    /// with different roles can be told apart by the rules of the compositor.
     pub fn get_layer_surface(
         &mut self,
         layer_surface: SctkLayerSurfaceSettings,
@@ -180,6 +235,21 @@ where
         self.state.get_layer_surface(layer_surface)
     }
 
+    /// Returns the output matching the selector, once the compositor advertised the outputs
+    /// and their information, e.g. to create the initial surface on it.
+    pub fn resolve_output(&mut self, selector: &settings::OutputSelector) -> Option<WlOutput> {
+        // the outputs are bound with the globals, the information of their wl_output arrives
+        // with the next roundtrip, and the one of their xdg_output with the roundtrip after it
+        for _ in 0..2 {
+            let mut wayland_source = self.wayland_dispatcher.as_source_mut();
+            if let Err(err) = wayland_source.queue().roundtrip(&mut self.state) {
+                log::error!("Failed to wait for the outputs: {}", err);
+                break;
+            }
+        }
+        self.state.select_output(selector)
+    }
+
     pub fn get_window(
         &mut self,
         settings: SctkWindowSettings,
@@ -187,6 +257,130 @@ where
         self.state.get_window(settings)
     }
 
+    pub fn get_custom_surface(
+        &mut self,
+        id: iced_native::window::Id,
+        handler: Box<dyn SurfaceRoleHandler>,
+    ) -> WlSurface {
+        self.state.get_custom_surface(id, handler)
+    }
+
+    /// Connects to the compositor again after the connection was lost, rebinds
+    /// the globals, and recreates the surfaces of the application.
+    fn reconnect(&mut self) -> Result<(), ReconnectError> {
+        let connection = Connection::connect_to_env()?;
+        let (globals, event_queue) = registry_queue_init(&connection)?;
+        let qh = event_queue.handle();
+        let wayland_source = WaylandSource::new(event_queue)?;
+        let wayland_dispatcher =
+            calloop::Dispatcher::new(wayland_source, |_, queue, winit_state| {
+                queue.dispatch_pending(winit_state)
+            });
+        let wayland_token = self
+            .event_loop
+            .handle()
+            .register_dispatcher(wayland_dispatcher.clone())
+            .map_err(|_| ReconnectError::EventSource)?;
+        self.event_loop
+            .handle()
+            .remove(mem::replace(&mut self.wayland_token, wayland_token));
+        self.wayland_dispatcher = wayland_dispatcher;
+
+        let state = &mut self.state;
+        state.registry_state = RegistryState::new(&globals);
+        state.seat_state = SeatState::new(&globals, &qh);
+        state.output_state = OutputState::new(&globals, &qh);
+        state.compositor_state = CompositorState::bind(&globals, &qh)?;
+        state.shm_state = ShmState::bind(&globals, &qh)?;
+        state.xdg_shell_state = XdgShellState::bind(&globals, &qh)?;
+        state.xdg_window_state = XdgWindowState::bind(&globals, &qh);
+        state.layer_shell = LayerShell::bind(&globals, &qh).ok();
+        state.subcompositor = globals.bind(&qh, 1..=1, ()).ok();
+        state.idle_notifier = globals.bind(&qh, 1..=1, ()).ok();
+        // the formats are announced again by the new global
+        state.dmabuf_formats.clear();
+        state.dmabuf = globals.bind(&qh, 3..=3, ()).ok();
+        state.queue_handle = qh;
+        state.connection = connection;
+
+        // everything that belonged to the old connection is gone
+        state.seats.clear();
+        state.outputs.clear();
+        state.kbd_focus = None;
+        state.cursor_surface = None;
+        state.cursor_theme = None;
+        // the image is uploaded again once the cursor surface is recreated
+        let cursor_image = state.cursor_image.take();
+        state.shm_buffers.clear();
+        state.sctk_events.clear();
+        state.window_compositor_updates.clear();
+        state.layer_surface_compositor_updates.clear();
+        state.popup_compositor_updates.clear();
+        state.scale_factors.clear();
+        state.scale_overrides.clear();
+        state.empty_region = None;
+        state.dnd_offer = None;
+        state.drag_source = None;
+        state.repeat_disabled = None;
+        state.activation = globals.bind(&qh, 1..=1, ()).ok();
+        state.data_device_manager = globals.bind(&qh, 1..=3, ()).ok();
+        state.primary_selection_manager = globals.bind(&qh, 1..=1, ()).ok();
+        state.shortcuts_inhibit_manager = globals.bind(&qh, 1..=1, ()).ok();
+
+        let surfaces = state.recreate_surfaces();
+        state.set_cursor_image(cursor_image);
+        state.sctk_events.push(SctkEvent::Reconnected(surfaces));
+        Ok(())
+    }
+
+    /// Returns whether the connection to the compositor is broken.
+    fn connection_lost(&self) -> bool {
+        matches!(self.state.connection.flush(), Err(WaylandError::Io(_)))
+    }
+
+    /// Tries to re-establish a lost connection, up to the configured amount of attempts.
+    /// A failed attempt is retried once a timer of the event loop fires, so the other
+    /// sources of the event loop are dispatched in the meantime.
+    /// Returns `false` if every attempt failed.
+    fn try_reconnect(&mut self) -> bool {
+        let attempt = self.failed_reconnects + 1;
+        if attempt > self.reconnect_attempts {
+            return false;
+        }
+        let err = match self.reconnect() {
+            Ok(()) => {
+                self.failed_reconnects = 0;
+                return true;
+            }
+            Err(err) => err,
+        };
+        log::warn!(
+            "Reconnection attempt {}/{} failed: {}",
+            attempt,
+            self.reconnect_attempts,
+            err
+        );
+        if attempt == self.reconnect_attempts {
+            return false;
+        }
+        self.failed_reconnects = attempt;
+        // the source of the lost connection would wake up the event loop continuously
+        let handle = self.event_loop.handle();
+        let _ = handle.disable(&self.wayland_token);
+        let backoff = Timer::from_duration(Duration::from_millis(100 * attempt as u64));
+        if handle
+            .insert_source(backoff, |_, _, state| {
+                state.reconnect_due = true;
+                TimeoutAction::Drop
+            })
+            .is_err()
+        {
+            log::error!("Failed to schedule the next reconnection attempt");
+            return false;
+        }
+        true
+    }
+
     pub fn run_return<F>(&mut self, mut callback: F) -> i32
     where
         F: FnMut(IcedSctkEvent<T>, &SctkState<T>, &mut ControlFlow),
@@ -210,9 +404,22 @@ where
         // with an API to do that via some event.
         // Still, we set the exit code to the error's OS error code, or to 1 if not possible.
         let exit_code = loop {
+            // Until the backoff of the next reconnection attempt is over, only the other
+            // sources of the event loop are dispatched.
+            if self.failed_reconnects > 0 {
+                if let Err(error) = self.event_loop.dispatch(None, &mut self.state) {
+                    break raw_os_err(error);
+                }
+                if mem::take(&mut self.state.reconnect_due) && !self.try_reconnect() {
+                    break 1;
+                }
+                continue;
+            }
+
             // Send pending events to the server.
             match self.state.connection.flush() {
                 Ok(_) => {}
+                Err(WaylandError::Io(_)) if self.try_reconnect() => continue,
                 Err(error) => {
                     break match error {
                         WaylandError::Io(err) => err.raw_os_error(),
@@ -234,6 +441,15 @@ where
                 let queue = wayland_source.queue();
                 match queue.dispatch_pending(&mut self.state) {
                     Ok(dispatched) => dispatched > 0,
+                    Err(DispatchError::Backend(WaylandError::Io(_)))
+                        if self.reconnect_attempts > 0 =>
+                    {
+                        drop(wayland_source);
+                        if self.try_reconnect() {
+                            continue;
+                        }
+                        break 1;
+                    }
                     // TODO better error handling
                     Err(error) => {
                         break match error {
@@ -254,6 +470,9 @@ where
                     // Non-blocking dispatch.
                     let timeout = Duration::from_millis(0);
                     if let Err(error) = self.event_loop.dispatch(Some(timeout), &mut self.state) {
+                        if self.connection_lost() && self.try_reconnect() {
+                            continue;
+                        }
                         break raw_os_err(error);
                     }
 
@@ -271,6 +490,9 @@ where
                     };
 
                     if let Err(error) = self.event_loop.dispatch(timeout, &mut self.state) {
+                        if self.connection_lost() && self.try_reconnect() {
+                            continue;
+                        }
                         break raw_os_err(error);
                     }
 
@@ -294,6 +516,9 @@ where
                     };
 
                     if let Err(error) = self.event_loop.dispatch(Some(duration), &mut self.state) {
+                        if self.connection_lost() && self.try_reconnect() {
+                            continue;
+                        }
                         break raw_os_err(error);
                     }
 
@@ -321,6 +546,9 @@ where
                 }
             }
 
+            // Custom roles are dispatched by their handlers, so poll them for changes.
+            self.state.poll_custom_surfaces();
+
             // The purpose of the back buffer and that swap is to not hold borrow_mut when
             // we're doing callback to the user, since we can double borrow if the user decides
             // to create a window in one of those callbacks.
@@ -365,6 +593,7 @@ where
                         variant: LayerSurfaceEventVariant::Done,
                         id,
                     } => {
+                        // the handler may already have dropped the layer surface
                         if let Some(i) = self
                             .state
                             .layer_surfaces
@@ -372,9 +601,32 @@ where
                             .position(|l| l.surface.wl_surface().id() == id.id())
                         {
                             let _l = self.state.layer_surfaces.remove(i);
+                        }
+                        sticky_exit_callback(
+                            IcedSctkEvent::SctkEvent(SctkEvent::LayerSurfaceEvent {
+                                variant: LayerSurfaceEventVariant::Done,
+                                id,
+                            }),
+                            &self.state,
+                            &mut control_flow,
+                            &mut callback,
+                        );
+                    }
+                    SctkEvent::CustomSurfaceEvent {
+                        variant: CustomSurfaceEventVariant::Done,
+                        id,
+                    } => {
+                        if let Some(i) = self
+                            .state
+                            .custom_surfaces
+                            .iter()
+                            .position(|c| c.wl_surface.id() == id.id())
+                        {
+                            let mut c = self.state.custom_surfaces.remove(i);
+                            c.handler.destroy();
                             sticky_exit_callback(
-                                IcedSctkEvent::SctkEvent(SctkEvent::LayerSurfaceEvent {
-                                    variant: LayerSurfaceEventVariant::Done,
+                                IcedSctkEvent::SctkEvent(SctkEvent::CustomSurfaceEvent {
+                                    variant: CustomSurfaceEventVariant::Done,
                                     id,
                                 }),
                                 &self.state,
@@ -387,24 +639,25 @@ where
                         variant: WindowEventVariant::Close,
                         id,
                     } => {
+                        // the handler may already have dropped the window
                         if let Some(i) = self
                             .state
-                            .layer_surfaces
+                            .windows
                             .iter()
-                            .position(|l| l.surface.wl_surface().id() == id.id())
+                            .position(|w| w.window.wl_surface().id() == id.id())
                         {
                             let w = self.state.windows.remove(i);
                             w.window.xdg_toplevel().destroy();
-                            sticky_exit_callback(
-                                IcedSctkEvent::SctkEvent(SctkEvent::WindowEvent {
-                                    variant: WindowEventVariant::Close,
-                                    id,
-                                }),
-                                &self.state,
-                                &mut control_flow,
-                                &mut callback,
-                            );
                         }
+                        sticky_exit_callback(
+                            IcedSctkEvent::SctkEvent(SctkEvent::WindowEvent {
+                                variant: WindowEventVariant::Close,
+                                id,
+                            }),
+                            &self.state,
+                            &mut control_flow,
+                            &mut callback,
+                        );
                     }
                     _ => sticky_exit_callback(
                         IcedSctkEvent::SctkEvent(event),
@@ -424,6 +677,18 @@ where
             let mut to_commit = HashMap::new();
             for event in sctk_events.into_iter().chain(user_events.into_iter()) {
                 match event {
+                    // nothing may be drawn before the first configure of a surface
+                    Event::SctkEvent(IcedSctkEvent::RedrawRequested(id))
+                        if self.state.defer_unconfigured_redraw(&id) => {}
+                    // a surface waiting for its configure to be released is drawn once it is
+                    Event::SctkEvent(IcedSctkEvent::RedrawRequested(id))
+                        if self.state.awaiting_configure_release(&id) => {}
+                    // redraws during a batch of updates are requested again once it ends
+                    Event::SctkEvent(IcedSctkEvent::RedrawRequested(id))
+                        if self.state.batch_redraw(&id) => {}
+                    // redraws exceeding the frame rate cap are requested again by a timer
+                    Event::SctkEvent(IcedSctkEvent::RedrawRequested(id))
+                        if self.state.throttle_redraw(&id) => {}
                     Event::SctkEvent(event) => {
                         sticky_exit_callback(event, &self.state, &mut control_flow, &mut callback)
                     }
@@ -432,34 +697,31 @@ where
                             builder,
                             _phantom,
                         } => {
-                            // TODO ASHLEY: error handling
-                            if let Ok((id, wl_surface)) = self.state.get_layer_surface(builder) {
-                                let object_id = wl_surface.id();
-                                sticky_exit_callback(
-                                    IcedSctkEvent::SctkEvent(SctkEvent::LayerSurfaceEvent {
-                                        variant: LayerSurfaceEventVariant::Created(object_id.clone(), id),
-                                        id: wl_surface.clone(),
-                                    }),
-                                    &self.state,
-                                    &mut control_flow,
-                                    &mut callback,
-                                );
+                            let namespace = builder.namespace.clone();
+                            let size = match builder.size {
+                                (Some(w), Some(h)) if w > 0 && h > 0 => Some((w, h)),
+                                _ => None,
+                            };
+                            match self.state.get_layer_surface(builder) {
+                                Ok((id, wl_surface)) => {
+                                    let object_id = wl_surface.id();
+                                    sticky_exit_callback(
+                                        IcedSctkEvent::SctkEvent(SctkEvent::LayerSurfaceEvent {
+                                            variant: LayerSurfaceEventVariant::Created(object_id.clone(), id, size),
+                                            id: wl_surface.clone(),
+                                        }),
+                                        &self.state,
+                                        &mut control_flow,
+                                        &mut callback,
+                                    );
+                                }
+                                Err(err) => log::error!("failed to create layer surface {:?}: {}", namespace, err),
                             }
                         }
-                        platform_specific::wayland::layer_surface::Action::Size {
-                            id,
-                            width,
-                            height,
-                        } => {
-                            if let Some(layer_surface) = self.state.layer_surfaces.iter_mut().find(|l| l.id == id) {
-                                layer_surface.requested_size = (width, height);
-                                layer_surface.surface.set_size(width.unwrap_or_default(), height.unwrap_or_default());
-                                to_commit.insert(id, layer_surface.surface.wl_surface().clone());
-                            }
-                        },
                         platform_specific::wayland::layer_surface::Action::Destroy(id) => {
                             if let Some(i) = self.state.layer_surfaces.iter().position(|l| &l.id == &id) {
                                 let l = self.state.layer_surfaces.remove(i);
+                                self.state.destroy_subsurfaces_of(id);
                                 sticky_exit_callback(
                                     IcedSctkEvent::SctkEvent(SctkEvent::LayerSurfaceEvent {
                                         variant: LayerSurfaceEventVariant::Done,
@@ -471,55 +733,104 @@ where
                                 );
                             }
                         },
-                        platform_specific::wayland::layer_surface::Action::Anchor { id, anchor } => {
-                            if let Some(layer_surface) = self.state.layer_surfaces.iter_mut().find(|l| l.id == id) {
-                                layer_surface.anchor = anchor;
-                                layer_surface.surface.set_anchor(anchor);
-                                to_commit.insert(id, layer_surface.surface.wl_surface().clone());
-
-                            }
-                        }
-                        platform_specific::wayland::layer_surface::Action::ExclusiveZone {
-                            id,
-                            exclusive_zone,
-                        } => {
-                            if let Some(layer_surface) = self.state.layer_surfaces.iter_mut().find(|l| l.id == id) {
-                                layer_surface.exclusive_zone = exclusive_zone;
-                                layer_surface.surface.set_exclusive_zone(exclusive_zone);
-                                to_commit.insert(id, layer_surface.surface.wl_surface().clone());
+                        platform_specific::wayland::layer_surface::Action::InputRegion { id, region } => {
+                            if let Some(wl_surface) = self.state.set_input_region(id, region.as_deref()) {
+                                to_commit.insert(id, wl_surface);
                             }
                         },
-                        platform_specific::wayland::layer_surface::Action::Margin {
-                            id,
-                            margin,
-                        } => {
-                            if let Some(layer_surface) = self.state.layer_surfaces.iter_mut().find(|l| l.id == id) {
-                                layer_surface.margin = margin;
-                                layer_surface.surface.set_margin(margin.top, margin.right, margin.bottom, margin.left);
-                                to_commit.insert(id, layer_surface.surface.wl_surface().clone());
+                        platform_specific::wayland::layer_surface::Action::GrabKeyboard(id) => {
+                            if !self.state.grab_keyboard(id) {
+                                log::warn!("Failed to grab the keyboard, layer surface {:?} does not exist", id);
                             }
                         },
-                        platform_specific::wayland::layer_surface::Action::KeyboardInteractivity { id, keyboard_interactivity } => {
-                            if let Some(layer_surface) = self.state.layer_surfaces.iter_mut().find(|l| l.id == id) {
-                                layer_surface.keyboard_interactivity = keyboard_interactivity;
-                                layer_surface.surface.set_keyboard_interactivity(keyboard_interactivity);
-                                to_commit.insert(id, layer_surface.surface.wl_surface().clone());
-
+                        platform_specific::wayland::layer_surface::Action::ReleaseKeyboard(id) => {
+                            self.state.release_keyboard(id);
+                        },
+                        platform_specific::wayland::layer_surface::Action::Raise { id, timeout } => {
+                            if let Some(wl_surface) = self.state.raise_layer_surface(id, timeout) {
+                                to_commit.insert(id, wl_surface);
                             }
                         },
-                        platform_specific::wayland::layer_surface::Action::Layer { id, layer } => {
-                            if let Some(layer_surface) = self.state.layer_surfaces.iter_mut().find(|l| l.id == id) {
-                                layer_surface.layer = layer;
-                                layer_surface.surface.set_layer(layer);
-                                to_commit.insert(id, layer_surface.surface.wl_surface().clone());
-
+                        platform_specific::wayland::layer_surface::Action::Restore(id) => {
+                            if let Some(wl_surface) = self.state.restore_layer_surface(id) {
+                                to_commit.insert(id, wl_surface);
                             }
                         },
+                        // property changes are applied together below, so they share one commit
+                        request => self.state.queue_layer_surface_request(request),
                     },
                     Event::SetCursor(_) => {
                         // TODO set cursor after cursor theming PR is merged
                         // https://github.com/Smithay/client-toolkit/pull/306
+                        // unless there is a cursor image, which takes precedence
+                    }
+                    Event::Flush => {
+                        // a lost connection is handled by the flush at the top of the loop
+                        if let Err(err) = self.state.connection.flush() {
+                            log::warn!("Failed to flush the connection: {}", err);
+                        }
                     }
+                    Event::SetMaxFrameRate(fps) => self.state.set_max_frame_rate(fps),
+                    Event::FetchCompositorInfo(f) => {
+                        let info = self.state.compositor_info();
+                        sticky_exit_callback(
+                            IcedSctkEvent::UserEvent(f(info)),
+                            &self.state,
+                            &mut control_flow,
+                            &mut callback,
+                        );
+                    }
+                    Event::Supports(interface, f) => {
+                        let supported = self.state.supports(&interface);
+                        sticky_exit_callback(
+                            IcedSctkEvent::UserEvent(f(supported)),
+                            &self.state,
+                            &mut control_flow,
+                            &mut callback,
+                        );
+                    }
+                    Event::Exit => control_flow = ControlFlow::ExitWithCode(0),
+                    Event::BeginBatch => self.state.begin_batch(),
+                    Event::EndBatch => {
+                        for id in self.state.end_batch() {
+                            self.state
+                                .pending_user_events
+                                .push(Event::SctkEvent(IcedSctkEvent::RedrawRequested(id)));
+                        }
+                    }
+                    Event::SetOpaque { id, opaque } => {
+                        if let Some(wl_surface) = self.state.set_opaque_region(id, opaque) {
+                            to_commit.insert(id, wl_surface);
+                        }
+                    }
+                    Event::SetDecorations { id, decorations } => {
+                        if let Some(wl_surface) = self.state.set_decorations(id, decorations) {
+                            to_commit.insert(id, wl_surface);
+                        }
+                    }
+                    Event::SetMode { id, mode } => match mode {
+                        Mode::Fullscreen => {
+                            // the shortcuts are inhibited as requested by the last
+                            // `set_fullscreen_window` of the window
+                            let inhibit_shortcuts = self
+                                .state
+                                .windows
+                                .iter()
+                                .find(|w| w.id == id)
+                                .map_or(false, |w| w.inhibit_shortcuts);
+                            if let Some(wl_surface) = self.state.set_fullscreen(id, inhibit_shortcuts) {
+                                to_commit.insert(id, wl_surface);
+                            }
+                        }
+                        Mode::Windowed => {
+                            if let Some(window) = self.state.windows.iter().find(|w| w.id == id) {
+                                window.window.unset_fullscreen();
+                                to_commit.insert(id, window.window.wl_surface().clone());
+                            }
+                        }
+                        // xdg-shell has no way to hide a window
+                        Mode::Hidden => log::warn!("Failed to hide window {:?}, not supported", id),
+                    },
                     Event::Window(action) => match action {
                         platform_specific::wayland::window::Action::Window { builder, _phantom } => {
                             let (id, wl_surface) = self.state.get_window(builder);
@@ -534,13 +845,21 @@ where
                         platform_specific::wayland::window::Action::Size { id, width, height } => {
                             if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
                                 window.requested_size = Some((width, height));
-                                window.window.xdg_surface().set_window_geometry(0, 0, width.max(1) as i32, height.max(1) as i32);
+                                // a window which is not resizable keeps the size of the application
+                                if !window.settings.iced_settings.resizable {
+                                    window.window.set_min_size(Some((width, height)));
+                                    window.window.set_max_size(Some((width, height)));
+                                }
+                                // a geometry set by the application stays until it is changed
+                                if window.geometry.is_none() {
+                                    window.window.xdg_surface().set_window_geometry(0, 0, width.max(1) as i32, height.max(1) as i32);
+                                }
                                 to_commit.insert(id, window.window.wl_surface().clone());
                                 // TODO Ashley maybe don't force window size?
                                 if let Some(mut prev_configure) = window.last_configure.clone() {
                                     prev_configure.new_size = Some((width, height));
                                     sticky_exit_callback(
-                                        IcedSctkEvent::SctkEvent(SctkEvent::WindowEvent { variant: WindowEventVariant::Configure(prev_configure, window.window.wl_surface().clone(), false), id: window.window.wl_surface().clone()}),
+                                        IcedSctkEvent::SctkEvent(SctkEvent::WindowEvent { variant: WindowEventVariant::Configure { configure: prev_configure, surface: window.window.wl_surface().clone(), first: false, serial: None, requested: true }, id: window.window.wl_surface().clone()}),
                                         &self.state,
                                         &mut control_flow,
                                         &mut callback,
@@ -548,16 +867,43 @@ where
                                 }
                             }
                         },
-                        platform_specific::wayland::window::Action::MinSize { id, size } => {
+                        platform_specific::wayland::window::Action::Geometry { id, geometry } => {
                             if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
-                                window.window.set_min_size(size);
+                                let (x, y, width, height) = match geometry {
+                                    Some(g) => (g.x, g.y, g.width, g.height),
+                                    None => {
+                                        let size = window.requested_size.unwrap_or((1, 1));
+                                        (0, 0, size.0 as i32, size.1 as i32)
+                                    }
+                                };
+                                window.window.xdg_surface().set_window_geometry(x, y, width.max(1), height.max(1));
+                                window.geometry = geometry;
                                 to_commit.insert(id, window.window.wl_surface().clone());
                             }
                         },
+                        platform_specific::wayland::window::Action::MinSize { id, size } => {
+                            if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
+                                // applied once the window is resizable again
+                                window.settings.iced_settings.min_size = size;
+                                if window.settings.iced_settings.resizable {
+                                    window.window.set_min_size(size);
+                                    to_commit.insert(id, window.window.wl_surface().clone());
+                                }
+                            }
+                        },
                         platform_specific::wayland::window::Action::MaxSize { id, size } => {
                             if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
-                                window.window.set_max_size(size);
-                                to_commit.insert(id, window.window.wl_surface().clone());
+                                // applied once the window is resizable again
+                                window.settings.iced_settings.max_size = size;
+                                if window.settings.iced_settings.resizable {
+                                    window.window.set_max_size(size);
+                                    to_commit.insert(id, window.window.wl_surface().clone());
+                                }
+                            }
+                        },
+                        platform_specific::wayland::window::Action::Resizable { id, resizable } => {
+                            if let Some(wl_surface) = self.state.set_resizable(id, resizable) {
+                                to_commit.insert(id, wl_surface);
                             }
                         },
                         platform_specific::wayland::window::Action::Title { id, title } => {
@@ -569,9 +915,20 @@ where
                         platform_specific::wayland::window::Action::Minimize { id } => {
                             if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
                                 window.window.set_mimimized();
+                                window.minimized = true;
                                 to_commit.insert(id, window.window.wl_surface().clone());
                             }
                         },
+                        platform_specific::wayland::window::Action::PlacementHint { id, hint } => {
+                            if !self.state.set_placement_hint(id, hint) {
+                                log::warn!("Failed to apply the placement hint of surface {:?}, only popups can be placed", id);
+                            }
+                        },
+                        platform_specific::wayland::window::Action::RequestAttention { id } => {
+                            if !self.state.request_attention(id) {
+                                log::warn!("Failed to request attention for window {:?}, xdg_activation_v1 is not supported", id);
+                            }
+                        },
                         platform_specific::wayland::window::Action::Maximize { id } => {
                             if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
                                 window.window.set_maximized();
@@ -585,28 +942,50 @@ where
                             }
                         },
                         platform_specific::wayland::window::Action::Fullscreen { id } => {
-                            if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
-                                // TODO ASHLEY: allow specific output to be requested for fullscreen?
-                                window.window.set_fullscreen(None);
-                                to_commit.insert(id, window.window.wl_surface().clone());
+                            if let Some(wl_surface) = self.state.set_fullscreen(id, false) {
+                                to_commit.insert(id, wl_surface);
+                            }
+                        },
+                        platform_specific::wayland::window::Action::FullscreenInhibitShortcuts { id } => {
+                            if let Some(wl_surface) = self.state.set_fullscreen(id, true) {
+                                to_commit.insert(id, wl_surface);
                             }
                         },
                         platform_specific::wayland::window::Action::UnsetFullscreen { id } => {
+                            // the shortcuts are restored by the configure without fullscreen
                             if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
                                 window.window.unset_fullscreen();
                                 to_commit.insert(id, window.window.wl_surface().clone());
                             }
                         },
                         platform_specific::wayland::window::Action::InteractiveMove { id } => {
-                            if let Some(window) = self.state.windows.iter_mut().find(|w| w.id == id) {
-                                todo!();
+                            if let Some(window) = self.state.windows.iter().find(|w| w.id == id) {
+                                match self.state.last_press_on(window.window.wl_surface()) {
+                                    Some((seat, serial)) => window.window.xdg_toplevel()._move(seat, serial),
+                                    None => log::warn!("no pointer press to start moving window {:?}", id),
+                                }
+                            }
+                        },
+                        platform_specific::wayland::window::Action::InteractiveResize { id, edge } => {
+                            if let Some(window) = self.state.windows.iter().find(|w| w.id == id) {
+                                match self.state.last_press_on(window.window.wl_surface()) {
+                                    Some((seat, serial)) => window.window.xdg_toplevel().resize(seat, serial, edge),
+                                    None => log::warn!("no pointer press to start resizing window {:?}", id),
+                                }
+                            }
+                        },
+                        platform_specific::wayland::window::Action::ShowWindowMenu { id, x, y } => {
+                            if let Some(window) = self.state.windows.iter().find(|w| w.id == id) {
+                                match self.state.last_press_on(window.window.wl_surface()) {
+                                    Some((seat, serial)) => window.window.xdg_toplevel().show_window_menu(seat, serial, x, y),
+                                    None => log::warn!("no pointer press to show the window menu of window {:?}", id),
+                                }
                             }
                         },
-                        platform_specific::wayland::window::Action::InteractiveResize { id, edge } => todo!(),
-                        platform_specific::wayland::window::Action::ShowWindowMenu { id, x, y } => todo!(),
                         platform_specific::wayland::window::Action::Destroy(id) => {
                             if let Some(i) = self.state.windows.iter().position(|l| &l.id == &id) {
                                 let window = self.state.windows.remove(i);
+                                self.state.destroy_subsurfaces_of(id);
                                 window.window.xdg_toplevel().destroy();
                                 sticky_exit_callback(
                                     IcedSctkEvent::SctkEvent(SctkEvent::WindowEvent {
@@ -622,14 +1001,25 @@ where
                     },
                     Event::Popup(action) => match action {
                         platform_specific::wayland::popup::Action::Popup { popup, .. } => {
-                            if let Ok((id, parent_id, toplevel_id, wl_surface)) = self.state.get_popup(popup) {
-                                let object_id = wl_surface.id();
-                                sticky_exit_callback(
-                                    IcedSctkEvent::SctkEvent(SctkEvent::PopupEvent { variant: crate::sctk_event::PopupEventVariant::Created(object_id.clone(), id), toplevel_id, parent_id, id: wl_surface.clone() }),
-                                    &self.state,
-                                    &mut control_flow,
-                                    &mut callback,
-                                );
+                            let grab = popup.grab;
+                            match self.state.get_popup(popup) {
+                                Ok((id, parent_id, toplevel_id, wl_surface)) => {
+                                    // the grab must be requested before the popup is mapped
+                                    if grab {
+                                        self.state.allow_popup_keyboard_grab(id);
+                                        if let Err(err) = self.state.grab_popup(id, None) {
+                                            log::warn!("failed to grab popup {:?}: {}", id, err);
+                                        }
+                                    }
+                                    let object_id = wl_surface.id();
+                                    sticky_exit_callback(
+                                        IcedSctkEvent::SctkEvent(SctkEvent::PopupEvent { variant: crate::sctk_event::PopupEventVariant::Created(object_id.clone(), id), toplevel_id, parent_id, id: wl_surface.clone() }),
+                                        &self.state,
+                                        &mut control_flow,
+                                        &mut callback,
+                                    );
+                                }
+                                Err(err) => log::error!("Failed to create popup: {}", err),
                             }
                         },
                         // XXX popup destruction must be done carefully
@@ -662,6 +1052,8 @@ where
                                 }
                             }
                             for popup in to_destroy.into_iter().rev() {
+                                self.state.destroy_subsurfaces_of(popup.id);
+                                self.state.popup_destroyed(popup.id);
                                 sticky_exit_callback(IcedSctkEvent::SctkEvent(SctkEvent::PopupEvent {
                                     variant: PopupEventVariant::Done,
                                     toplevel_id: popup.toplevel.clone(),
@@ -674,12 +1066,325 @@ where
                                 );
                             }
                         },
-                        platform_specific::wayland::popup::Action::Reposition { id, positioner } => todo!(),
-                        platform_specific::wayland::popup::Action::Grab { id } => todo!(),
+                        platform_specific::wayland::popup::Action::Reposition { id, positioner } => {
+                            self.state.reposition_popup(id, positioner);
+                        }
+                        platform_specific::wayland::popup::Action::Grab { id } => {
+                            if let Err(err) = self.state.grab_popup(id, None) {
+                                log::warn!("failed to grab popup {:?}: {}", id, err);
+                            }
+                        },
+                        platform_specific::wayland::popup::Action::GrabWithSeat { id, seat } => {
+                            if let Err(err) = self.state.grab_popup(id, Some(&seat)) {
+                                log::warn!("failed to grab popup {:?}: {}", id, err);
+                            }
+                        },
+                    },
+                    Event::Output(action) => match action {
+                        platform_specific::wayland::output::Action::FetchOutputs(f) => {
+                            let outputs = self
+                                .state
+                                .outputs
+                                .iter()
+                                .map(|o| (o.clone(), self.state.output_state.info(o)))
+                                .collect();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(outputs)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                    },
+                    Event::Pointer(action) => match action {
+                        platform_specific::wayland::pointer::Action::SetCursorImage {
+                            pixels,
+                            width,
+                            height,
+                            hotspot,
+                            scale,
+                        } => {
+                            self.state.set_cursor_image(Some(state::CursorImage {
+                                pixels,
+                                width,
+                                height,
+                                hotspot,
+                                scale,
+                            }));
+                        }
+                        platform_specific::wayland::pointer::Action::UnsetCursorImage => {
+                            self.state.set_cursor_image(None);
+                        }
+                        platform_specific::wayland::pointer::Action::SetScrollInverted(inverted) => {
+                            self.state.invert_scroll = inverted;
+                        }
+                        platform_specific::wayland::pointer::Action::SetScrollSensitivity {
+                            horizontal,
+                            vertical,
+                        } => {
+                            self.state.scroll_sensitivity = (horizontal, vertical);
+                        }
+                        platform_specific::wayland::pointer::Action::FetchPosition(f) => {
+                            let position = self.state.pointer_position();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(position)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                    },
+                    Event::Keyboard(action) => match action {
+                        platform_specific::wayland::keyboard::Action::FetchRepeatInfo(f) => {
+                            let info = self.state.repeat_info();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(info)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::keyboard::Action::SetRepeatEnabled(enabled) => {
+                            self.state.set_repeat_enabled(enabled);
+                        }
+                    },
+                    Event::Seat(action) => match action {
+                        platform_specific::wayland::seat::Action::FetchSeats(f) => {
+                            let seats = self.state.seat_infos();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(seats)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::seat::Action::SetActive(seat) => {
+                            if !self.state.set_active_seat(&seat) {
+                                log::warn!("Tried to activate an unknown seat: {:?}", seat);
+                            }
+                        }
+                    },
+                    Event::IdleNotify(action) => match action {
+                        platform_specific::wayland::idle_notify::Action::SetTimeout(timeout) => {
+                            self.state.set_idle_timeout(timeout);
+                        }
+                    },
+                    Event::ReadClipboard(f) => match self.state.receive_selection() {
+                        Ok(reader) => {
+                            if let Err(err) = self.state.read_selection(reader, f) {
+                                log::error!("Failed to read the clipboard: {}", err);
+                            }
+                        }
+                        // the application gets an answer, even if there is nothing to read
+                        Err(err) => {
+                            log::debug!("Failed to read the clipboard: {}", err);
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(None)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                    },
+                    Event::DataDevice(action) => match action {
+                        platform_specific::wayland::data_device::Action::QueryMimeTypes(f) => {
+                            let mime_types = self.state.selection_mime_types();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(mime_types)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::data_device::Action::StartDrag {
+                            origin,
+                            contents,
+                            actions,
+                            icon,
+                        } => {
+                            let icon = icon.map(|icon| state::CursorImage {
+                                pixels: icon.pixels,
+                                width: icon.width,
+                                height: icon.height,
+                                hotspot: icon.hotspot,
+                                scale: icon.scale,
+                            });
+                            if let Err(err) = self.state.start_drag(origin, contents, actions, icon) {
+                                log::warn!("Failed to start the drag: {}", err);
+                            }
+                        }
+                        platform_specific::wayland::data_device::Action::AcceptMimeType(mime_type) => {
+                            self.state.accept_dnd_mime_type(mime_type);
+                        }
+                        platform_specific::wayland::data_device::Action::SetActions {
+                            actions,
+                            preferred,
+                        } => {
+                            self.state.set_dnd_actions(actions, preferred);
+                        }
+                        platform_specific::wayland::data_device::Action::ReadOffer { mime_type, f } => {
+                            match self.state.receive_dnd_offer(mime_type) {
+                                Ok((reader, offer)) => {
+                                    if let Err(err) = self.state.read_dnd_offer(reader, offer, f) {
+                                        log::error!("Failed to read the drag and drop offer: {}", err);
+                                    }
+                                }
+                                // the application gets an answer, even if there is nothing to read
+                                Err(err) => {
+                                    log::debug!("Failed to read the drag and drop offer: {}", err);
+                                    sticky_exit_callback(
+                                        IcedSctkEvent::UserEvent(f(None)),
+                                        &self.state,
+                                        &mut control_flow,
+                                        &mut callback,
+                                    );
+                                }
+                            }
+                        }
+                    },
+                    Event::Dmabuf(action) => match action {
+                        platform_specific::wayland::dmabuf::Action::FetchFormats(f) => {
+                            let formats = self.state.dmabuf_formats.clone();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(formats)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::dmabuf::Action::Attach { id, buffer } => {
+                            match self.state.attach_dmabuf(id, buffer) {
+                                Ok(wl_surface) => {
+                                    to_commit.insert(id, wl_surface);
+                                }
+                                Err(err) => log::error!("Failed to attach dmabuf: {}", err),
+                            }
+                        }
+                    },
+                    Event::Surface(action) => match action {
+                        platform_specific::wayland::surface::Action::FetchWlSurface(id, f) => {
+                            let wl_surface = self.state.wl_surface(id).cloned();
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(wl_surface)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::surface::Action::FetchScaleFactor(id, f) => {
+                            let scale_factor = self
+                                .state
+                                .wl_surface(id)
+                                .map(|s| self.state.scale_factors.get(&s.id()).copied().unwrap_or(1));
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(scale_factor)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::surface::Action::Subsurface { id, parent, position, sync } => {
+                            match self.state.get_subsurface(id, parent, position, sync) {
+                                Ok((_, parent_surface)) => {
+                                    to_commit.insert(parent, parent_surface);
+                                }
+                                Err(err) => log::error!("Failed to create subsurface: {}", err),
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::SubsurfacePosition { id, position } => {
+                            if let Some(subsurface) = self.state.subsurfaces.iter_mut().find(|s| s.id == id) {
+                                subsurface.position = position;
+                                subsurface.subsurface.set_position(position.0, position.1);
+                                let parent = subsurface.parent;
+                                // the position is applied with the next commit of the parent
+                                if let Some(parent_surface) = self.state.wl_surface(parent) {
+                                    to_commit.insert(parent, parent_surface.clone());
+                                }
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::SubsurfaceSync { id, sync } => {
+                            if let Some(subsurface) = self.state.subsurfaces.iter().find(|s| s.id == id) {
+                                if sync {
+                                    subsurface.subsurface.set_sync();
+                                } else {
+                                    subsurface.subsurface.set_desync();
+                                }
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::ManualConfigureAck { id, enabled } => {
+                            if let Some(wl_surface) = self.state.set_hold_configures(id, enabled) {
+                                must_redraw.push(wl_surface);
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::ClickThrough {
+                            id,
+                            click_through,
+                        } => {
+                            if let Some(wl_surface) = self.state.set_click_through(id, click_through) {
+                                to_commit.insert(id, wl_surface);
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::ScaleOverride { id, scale } => {
+                            if !self.state.set_scale_override(id, scale) {
+                                log::warn!("Failed to set the scale {:?} of surface {:?}", scale, id);
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::FetchConfigureSerial(id, f) => {
+                            let serial = self.state.configure_serial(id);
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(serial)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::surface::Action::AckConfigure { id, serial } => {
+                            if let Some(wl_surface) = self.state.release_configure(id, serial) {
+                                must_redraw.push(wl_surface);
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::BufferTransform { id, transform } => {
+                            if let Some(wl_surface) = self.state.wl_surface(id).cloned() {
+                                if self.state.drawn_surfaces().any(|s| *s == wl_surface) {
+                                    // set by the renderer with the next buffer, of the new size
+                                    self.state.sctk_events.push(SctkEvent::BufferTransformChanged {
+                                        id: wl_surface,
+                                        transform,
+                                    });
+                                } else {
+                                    wl_surface.set_buffer_transform(transform);
+                                }
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::RequestKeyboardFocus(id, f) => {
+                            let requested = self.state.request_keyboard_focus(id);
+                            sticky_exit_callback(
+                                IcedSctkEvent::UserEvent(f(requested)),
+                                &self.state,
+                                &mut control_flow,
+                                &mut callback,
+                            );
+                        }
+                        platform_specific::wayland::surface::Action::Recreate(id) => {
+                            if let Some(wl_surface) = self.state.wl_surface(id) {
+                                self.state.sctk_events.push(SctkEvent::SurfaceRecreateRequested {
+                                    id: wl_surface.clone(),
+                                });
+                            }
+                        }
+                        platform_specific::wayland::surface::Action::DestroySubsurface(id) => {
+                            if let Some(parent) = self.state.destroy_subsurface(id) {
+                                if let Some(parent_surface) = self.state.wl_surface(parent) {
+                                    to_commit.insert(parent, parent_surface.clone());
+                                }
+                            }
+                        }
                     },
                 }
             }
 
+            to_commit.extend(self.state.apply_layer_surface_requests());
+
             // commit changes made via actions
             for s in to_commit {
                 s.1.commit();
@@ -731,8 +1436,14 @@ where
                     surface_request.redraw_requested = true;
                 }
 
+                if self.state.awaiting_configure_release(surface_id)
+                    || self.state.defer_unconfigured_redraw(surface_id)
+                {
+                    continue;
+                }
+
                 // Handle redraw request.
-                if surface_request.redraw_requested {
+                if surface_request.redraw_requested && !self.state.batch_redraw(surface_id) {
                     sticky_exit_callback(
                         IcedSctkEvent::RedrawRequested(surface_id.clone()),
                         &self.state,
@@ -744,6 +1455,20 @@ where
             }
 
             for id in must_redraw {
+                if self.state.awaiting_configure_release(&id.id())
+                    || self.state.defer_unconfigured_redraw(&id.id())
+                    || self.state.batch_redraw(&id.id())
+                {
+                    continue;
+                }
+                if let Some(c) = self
+                    .state
+                    .custom_surfaces
+                    .iter_mut()
+                    .find(|c| c.wl_surface == id)
+                {
+                    c.handler.before_draw(&c.wl_surface);
+                }
                 sticky_exit_callback(
                     IcedSctkEvent::RedrawRequested(id.id()),
                     &self.state,
@@ -761,11 +1486,39 @@ where
             );
         };
 
+        // destroy the idle notifications
+        self.state.set_idle_timeout(None);
+        let _ = self.state.connection.flush();
+
         callback(IcedSctkEvent::LoopDestroyed, &self.state, &mut control_flow);
         exit_code
     }
 }
 
+/// An error that occurred while reconnecting to the compositor.
+#[derive(Debug, thiserror::Error)]
+pub enum ReconnectError {
+    /// Connecting to the compositor failed
+    #[error("Connecting to the compositor failed")]
+    Connect(#[from] ConnectError),
+
+    /// The registry could not be initialized
+    #[error("The registry could not be initialized")]
+    Registry(#[from] GlobalError),
+
+    /// A required global is missing
+    #[error("A required global is missing")]
+    Bind(#[from] BindError),
+
+    /// The Wayland event source could not be created
+    #[error("The Wayland event source could not be created")]
+    Wayland(#[from] WaylandError),
+
+    /// The Wayland event source could not be registered
+    #[error("The Wayland event source could not be registered")]
+    EventSource,
+}
+
 fn sticky_exit_callback<T, F>(
     evt: IcedSctkEvent<T>,
     target: &SctkState<T>,
@@ -10,24 +10,30 @@ use std::pin::Pin;
 #[derive(Debug)]
 pub struct Proxy<Message: 'static> {
     raw: calloop::channel::Sender<Message>,
+    awakener: calloop::ping::Ping,
 }
 
 impl<Message: 'static> Clone for Proxy<Message> {
     fn clone(&self) -> Self {
         Self {
             raw: self.raw.clone(),
+            awakener: self.awakener.clone(),
         }
     }
 }
 
 impl<Message: 'static> Proxy<Message> {
-    /// Creates a new [`Proxy`] from an `EventLoopProxy`.
-    pub fn new(raw: calloop::channel::Sender<Message>) -> Self {
-        Self { raw }
+    /// Creates a new [`Proxy`] from an `EventLoopProxy`, which wakes up the event loop
+    /// with `awakener` whenever an event is sent.
+    pub fn new(raw: calloop::channel::Sender<Message>, awakener: calloop::ping::Ping) -> Self {
+        Self { raw, awakener }
     }
     /// send an event
     pub fn send_event(&self, message: Message) {
-        let _ = self.raw.send(message);
+        if self.raw.send(message).is_ok() {
+            // don't leave the event pending until an unrelated event wakes up the loop
+            self.awakener.ping();
+        }
     }
 }
 
@@ -39,7 +45,7 @@ impl<Message: 'static> Sink<Message> for Proxy<Message> {
     }
 
     fn start_send(self: Pin<&mut Self>, message: Message) -> Result<(), Self::Error> {
-        let _ = self.raw.send(message);
+        self.send_event(message);
 
         Ok(())
     }
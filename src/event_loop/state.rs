@@ -1,37 +1,69 @@
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    io::Read,
+    os::unix::{io::AsRawFd, net::UnixStream},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    application::Event,
+    application::{Decorations, Event, SurfaceIdWrapper},
     dpi::LogicalSize,
-    sctk_event::{SctkEvent, SurfaceCompositorUpdate, SurfaceUserRequest},
+    event_loop::{control_flow::ControlFlow, custom_surface::SurfaceRoleHandler},
+    handlers::data_device::{
+        data_device::DndOffer,
+        data_offer::DataOfferData,
+        data_source::{DataSourceData, DragSource},
+    },
+    sctk_event::{
+        CustomSurfaceEventVariant, IcedSctkEvent, SctkEvent, SeatEventVariant,
+        SurfaceCompositorUpdate, SurfaceUserRequest,
+    },
+    settings::OutputSelector,
 };
 
 use iced_native::{
     command::platform_specific::{
         self,
         wayland::{
-            layer_surface::{IcedMargin, SctkLayerSurfaceSettings},
-            popup::SctkPopupSettings,
-            window::SctkWindowSettings,
+            dmabuf::DmabufBuffer,
+            layer_surface::{IcedMargin, IcedOutput, SctkLayerSurfaceSettings},
+            popup::{SctkPopupSettings, SctkPositioner},
+            seat::SeatInfo,
+            CompositorInfo,
+            window::{PlacementHint, SctkWindowSettings},
         },
     },
     keyboard::Modifiers,
-    window,
+    layout::Limits,
+    window, Point, Rectangle,
 };
 use sctk::{
-    compositor::CompositorState,
+    compositor::{CompositorState, Region, SurfaceData},
     error::GlobalError,
     output::OutputState,
     reexports::{
-        calloop::LoopHandle,
+        calloop::{
+            generic::Generic,
+            timer::{TimeoutAction, Timer},
+            Interest, LoopHandle, Mode, PostAction, RegistrationToken,
+        },
         client::{
             backend::ObjectId,
             protocol::{
+                wl_buffer::WlBuffer,
                 wl_data_device::WlDataDevice,
+                wl_data_device_manager::{DndAction, WlDataDeviceManager},
+                wl_data_offer::WlDataOffer,
+                wl_data_source::WlDataSource,
                 wl_keyboard::WlKeyboard,
                 wl_output::WlOutput,
                 wl_pointer::WlPointer,
                 wl_seat::WlSeat,
+                wl_shm,
+                wl_subcompositor::WlSubcompositor,
+                wl_subsurface::WlSubsurface,
                 wl_surface::{self, WlSurface},
                 wl_touch::WlTouch,
             },
@@ -39,32 +71,175 @@ use sctk::{
         },
     },
     registry::RegistryState,
-    seat::{keyboard::KeyEvent, SeatState},
+    seat::{
+        keyboard::{KeyEvent, RepeatInfo, RMLVO},
+        SeatState,
+    },
     shell::{
         layer::{
             Anchor, KeyboardInteractivity, Layer, LayerShell, LayerSurface, LayerSurfaceConfigure,
         },
         xdg::{
             popup::{Popup, PopupConfigure},
-            window::{Window, WindowConfigure, WindowDecorations, XdgWindowState},
+            window::{
+                DecorationMode, Window, WindowConfigure, WindowDecorations, XdgWindowState,
+            },
             XdgPositioner, XdgShellState, XdgShellSurface,
         },
     },
-    shm::{multi::MultiPool, ShmState},
+    shm::{raw::RawPool, CreatePoolError, ShmState},
+};
+use wayland_cursor::CursorTheme;
+use wayland_protocols::wp::linux_dmabuf::zv1::client::{
+    zwp_linux_buffer_params_v1, zwp_linux_dmabuf_v1::ZwpLinuxDmabufV1,
+};
+use wayland_protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
+use wayland_protocols::xdg::shell::client::xdg_positioner;
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::{
+    zwp_keyboard_shortcuts_inhibit_manager_v1::ZwpKeyboardShortcutsInhibitManagerV1,
+    zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1,
 };
+use wayland_protocols::wp::primary_selection::zv1::client::{
+    zwp_primary_selection_device_manager_v1::ZwpPrimarySelectionDeviceManagerV1,
+    zwp_primary_selection_device_v1::ZwpPrimarySelectionDeviceV1,
+    zwp_primary_selection_offer_v1::ZwpPrimarySelectionOfferV1,
+};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::ExtIdleNotificationV1, ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+
+/// The longest time between two presses of a button, in milliseconds, for them to count
+/// as consecutive clicks.
+const CLICK_INTERVAL: u32 = 400;
+/// The farthest distance between two presses of a button, in surface local coordinates,
+/// for them to count as consecutive clicks.
+const CLICK_DISTANCE: f64 = 4.0;
+/// The distance the pointer must move from where a button was pressed, in surface local
+/// coordinates, for the press to start a drag instead of a click.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// The last press of a pointer button, to count consecutive clicks.
+#[derive(Debug, Clone)]
+pub(crate) struct PointerClick {
+    surface: ObjectId,
+    time: u32,
+    button: u32,
+    position: (f64, f64),
+    count: u32,
+}
+
+impl PointerClick {
+    /// Returns the press following `previous`, counting it as another click if it
+    /// repeats the same button quickly enough and close enough.
+    pub(crate) fn next(
+        previous: Option<&PointerClick>,
+        surface: ObjectId,
+        time: u32,
+        button: u32,
+        position: (f64, f64),
+    ) -> Self {
+        let count = match previous {
+            Some(p)
+                if p.surface == surface
+                    && p.button == button
+                    && time.wrapping_sub(p.time) <= CLICK_INTERVAL
+                    && (p.position.0 - position.0).abs() <= CLICK_DISTANCE
+                    && (p.position.1 - position.1).abs() <= CLICK_DISTANCE =>
+            {
+                p.count + 1
+            }
+            _ => 1,
+        };
+        Self {
+            surface,
+            time,
+            button,
+            position,
+            count,
+        }
+    }
+
+    pub(crate) fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// A pressed pointer button, to tell a click from the start of a drag.
+#[derive(Debug, Clone)]
+pub(crate) struct PointerDrag {
+    button: u32,
+    origin: (f64, f64),
+    started: bool,
+}
+
+impl PointerDrag {
+    pub(crate) fn new(button: u32, origin: (f64, f64)) -> Self {
+        Self {
+            button,
+            origin,
+            started: false,
+        }
+    }
+
+    /// Tracks a motion of the pointer while the button is pressed, and returns the
+    /// button if the motion moved it far enough from the origin to start a drag.
+    pub(crate) fn motion(&mut self, position: (f64, f64)) -> Option<u32> {
+        if self.started {
+            return None;
+        }
+        let (dx, dy) = (position.0 - self.origin.0, position.1 - self.origin.1);
+        self.started = dx.hypot(dy) > DRAG_THRESHOLD;
+        self.started.then_some(self.button)
+    }
+
+    pub(crate) fn button(&self) -> u32 {
+        self.button
+    }
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct SctkSeat {
     pub(crate) seat: WlSeat,
+    /// the name of the seat, sent by the compositor after the seat was announced
+    pub(crate) name: Option<String>,
     pub(crate) kbd: Option<WlKeyboard>,
     pub(crate) kbd_focus: Option<WlSurface>,
     pub(crate) last_kbd_press: Option<KeyEvent>,
+    /// the repeat rate and delay of the keyboard, sent by the compositor
+    pub(crate) repeat_info: Option<RepeatInfo>,
+    /// the name of the active layout of the keyboard, e.g. "English (US)"
+    pub(crate) kbd_layout: Option<String>,
+    /// the names of the layouts of the keymap of the keyboard, by group
+    pub(crate) kbd_layouts: Vec<String>,
+    /// the active group of the keyboard, i.e. the index of its active layout
+    pub(crate) kbd_group: u32,
     pub(crate) ptr: Option<WlPointer>,
     pub(crate) ptr_focus: Option<WlSurface>,
+    /// the last position of the pointer on the focused surface
+    pub(crate) ptr_position: (f64, f64),
+    /// the last button press on the focused surface, until the button is released or the
+    /// pointer leaves the surface
     pub(crate) last_ptr_press: Option<(u32, u32, u32)>, // (time, button, serial)
+    pub(crate) last_ptr_enter: Option<u32>,             // serial
+    /// the serial of the latest key press, button press or touch of the seat, which
+    /// activation requests must carry for the compositor to move the focus
+    pub(crate) last_input_serial: Option<u32>,
+    pub(crate) last_click: Option<PointerClick>,
+    /// the button which is held down, until it is released
+    pub(crate) ptr_drag: Option<PointerDrag>,
     pub(crate) touch: Option<WlTouch>,
+    /// the surface and last position of the touch points which are down
+    pub(crate) touch_points: HashMap<i32, (WlSurface, (f64, f64))>,
+    /// the touch point which drives the pointer, if any
+    pub(crate) touch_primary: Option<i32>,
     pub(crate) data_device: Option<WlDataDevice>,
+    /// the current selection of the seat, i.e. the clipboard
+    pub(crate) selection: Option<WlDataOffer>,
+    pub(crate) primary_selection_device: Option<ZwpPrimarySelectionDeviceV1>,
+    /// the current primary selection of the seat, i.e. the selected text
+    pub(crate) primary_selection: Option<ZwpPrimarySelectionOfferV1>,
     pub(crate) modifiers: Modifiers,
+    pub(crate) idle_notification: Option<ExtIdleNotificationV1>,
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +249,19 @@ pub struct SctkWindow<T> {
     pub(crate) requested_size: Option<(u32, u32)>,
     pub(crate) current_size: Option<(u32, u32)>,
     pub(crate) last_configure: Option<WindowConfigure>,
+    /// the serial of the latest configure, which the configure itself does not carry
+    pub(crate) configure_serial: Option<u32>,
+    /// whether the window was minimized, and has not been configured since
+    pub(crate) minimized: bool,
+    /// whether the shortcuts of the compositor are inhibited while the window is fullscreen
+    pub(crate) inhibit_shortcuts: bool,
+    /// inhibits the shortcuts of the compositor while the window is fullscreen
+    pub(crate) shortcuts_inhibitor: Option<ZwpKeyboardShortcutsInhibitorV1>,
+    /// the visible part of the window set by the application, which replaces the
+    /// whole window as its geometry
+    pub(crate) geometry: Option<Rectangle<i32>>,
+    /// the settings the window was created with, used to recreate it
+    pub(crate) settings: SctkWindowSettings,
     /// Requests that SCTK window should perform.
     pub(crate) pending_requests: Vec<platform_specific::wayland::window::Action<T>>,
 }
@@ -82,17 +270,57 @@ pub struct SctkWindow<T> {
 pub struct SctkLayerSurface<T> {
     pub(crate) id: iced_native::window::Id,
     pub(crate) surface: LayerSurface,
+    /// the size set by the application, `None` leaves the dimension to the compositor
     pub(crate) requested_size: (Option<u32>, Option<u32>),
     pub(crate) current_size: Option<LogicalSize<u32>>,
     pub(crate) layer: Layer,
     pub(crate) anchor: Anchor,
+    /// the keyboard interactivity applied to the surface, the one of the application raised
+    /// by the holds of the surface
     pub(crate) keyboard_interactivity: KeyboardInteractivity,
+    /// the keyboard interactivity set by the application
+    pub(crate) requested_interactivity: KeyboardInteractivity,
+    /// what raises the keyboard interactivity temporarily
+    pub(crate) keyboard_holds: Vec<KeyboardHold>,
     pub(crate) margin: IcedMargin,
     pub(crate) exclusive_zone: i32,
+    /// the output the surface was created on, `None` if the compositor picked it
+    pub(crate) output: Option<WlOutput>,
     pub(crate) last_configure: Option<LayerSurfaceConfigure>,
+    /// the serial of the latest configure, which the configure itself does not carry
+    pub(crate) configure_serial: Option<u32>,
+    /// whether the surface was moved to the layer above [`SctkLayerSurface::layer`]
+    pub(crate) raised: bool,
+    /// the timer restoring the layer of the raised surface
+    pub(crate) raise_timer: Option<RegistrationToken>,
+    /// the settings the layer surface was created with, used to recreate it
+    pub(crate) settings: SctkLayerSurfaceSettings,
     pub(crate) pending_requests: Vec<platform_specific::wayland::layer_surface::Action<T>>,
 }
 
+/// What raises the keyboard interactivity of a layer surface above the one set by the
+/// application, until it is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyboardHold {
+    /// the layer surface grabbed the keyboard
+    Grab,
+    /// the keyboard focus was requested for the layer surface, which it did not get yet
+    FocusRequest,
+    /// the layer surface got the keyboard focus it requested, and keeps it until it leaves
+    Focused,
+    /// a grabbed popup of the layer surface takes the keyboard focus
+    Popup(window::Id),
+}
+
+impl KeyboardHold {
+    fn interactivity(self) -> KeyboardInteractivity {
+        match self {
+            KeyboardHold::Grab | KeyboardHold::FocusRequest => KeyboardInteractivity::Exclusive,
+            KeyboardHold::Focused | KeyboardHold::Popup(_) => KeyboardInteractivity::OnDemand,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SctkSurface {
     LayerSurface(WlSurface),
@@ -115,11 +343,45 @@ pub struct SctkPopup<T> {
     pub(crate) parent: SctkSurface,
     pub(crate) toplevel: WlSurface,
     pub(crate) requested_size: (u32, u32),
+    /// the token of the latest reposition of the popup
+    pub(crate) reposition_token: u32,
+    /// the positioner the popup was last placed with
+    pub(crate) positioner: SctkPositioner,
     pub(crate) last_configure: Option<PopupConfigure>,
-    // pub(crate) positioner: XdgPositioner,
     pub(crate) pending_requests: Vec<platform_specific::wayland::popup::Action<T>>,
 }
 
+#[derive(Debug)]
+pub struct SctkCustomSurface {
+    pub(crate) id: iced_native::window::Id,
+    pub(crate) wl_surface: WlSurface,
+    pub(crate) handler: Box<dyn SurfaceRoleHandler>,
+    pub(crate) current_size: Option<(u32, u32)>,
+}
+
+/// A cursor image, or the icon of a drag, provided by the application.
+#[derive(Debug, Clone)]
+pub(crate) struct CursorImage {
+    /// RGBA pixels, row by row
+    pub(crate) pixels: Vec<u8>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// the hotspot in surface local coordinates
+    pub(crate) hotspot: (i32, i32),
+    /// the scale the image was drawn at
+    pub(crate) scale: i32,
+}
+
+#[derive(Debug)]
+pub struct SctkSubsurface {
+    pub(crate) id: iced_native::window::Id,
+    pub(crate) parent: iced_native::window::Id,
+    pub(crate) wl_surface: WlSurface,
+    pub(crate) subsurface: WlSubsurface,
+    /// offset from the top left corner of the parent
+    pub(crate) position: (i32, i32),
+}
+
 /// Wrapper to carry sctk state.
 #[derive(Debug)]
 pub struct SctkState<T> {
@@ -130,14 +392,20 @@ pub struct SctkState<T> {
     // pub(crate) config: Option<glutin::api::egl::config::Config>,
     /// the cursor wl_surface
     pub(crate) cursor_surface: Option<wl_surface::WlSurface>,
-    /// a memory pool
-    pub(crate) multipool: Option<MultiPool<WlSurface>>,
+    /// the cursor image set by the application, takes precedence over named cursors
+    pub(crate) cursor_image: Option<CursorImage>,
+    /// whether the cursor image waits for the compositor to release one of its buffers
+    pub(crate) cursor_upload_pending: bool,
+    /// the two shm buffers of each surface drawn by the application
+    pub(crate) shm_buffers: HashMap<ObjectId, ShmSlots<ShmSlot>>,
 
     // all present outputs
     pub(crate) outputs: Vec<WlOutput>,
     // though (for now) only one seat will be active in an iced application at a time, all ought to be tracked
     // Active seat is the first seat in the list
     pub(crate) seats: Vec<SctkSeat>,
+    /// whether the active seat was set by the application, and must not be swapped on input
+    pub(crate) seat_pinned: bool,
     // Windows / Surfaces
     /// Window list containing all SCTK windows. Since those windows aren't allowed
     /// to be sent to other threads, they live on the event loop's thread
@@ -146,6 +414,10 @@ pub struct SctkState<T> {
     pub(crate) windows: Vec<SctkWindow<T>>,
     pub(crate) layer_surfaces: Vec<SctkLayerSurface<T>>,
     pub(crate) popups: Vec<SctkPopup<T>>,
+    /// Surfaces with a role provided by a [`SurfaceRoleHandler`].
+    pub(crate) custom_surfaces: Vec<SctkCustomSurface>,
+    /// Subsurfaces whose content is provided by the application.
+    pub(crate) subsurfaces: Vec<SctkSubsurface>,
     pub(crate) kbd_focus: Option<WlSurface>,
 
     /// Window updates, which are coming from SCTK or the compositor, which require
@@ -190,6 +462,63 @@ pub struct SctkState<T> {
     pub(crate) xdg_shell_state: XdgShellState,
     pub(crate) xdg_window_state: XdgWindowState,
     pub(crate) layer_shell: Option<LayerShell>,
+    pub(crate) subcompositor: Option<WlSubcompositor>,
+    pub(crate) idle_notifier: Option<ExtIdleNotifierV1>,
+    /// the inactivity after which the seats are reported as idle
+    pub(crate) idle_timeout: Option<Duration>,
+    /// the keymap of the keyboards, instead of the one provided by the compositor
+    pub(crate) kbd_keymap: Option<RMLVO>,
+    /// the name and size of the cursor theme, instead of the default one
+    pub(crate) ptr_theme: Option<(String, u32)>,
+    /// the cursor theme, loaded once the default cursor is shown by the application
+    pub(crate) cursor_theme: Option<CursorTheme>,
+    /// whether the backoff after a failed attempt to reconnect to the compositor is over
+    pub(crate) reconnect_due: bool,
+    /// the layer surface which grabbed the keyboard
+    pub(crate) keyboard_grab: Option<window::Id>,
+    /// the surface focused by the keyboard of the active seat whose key repeat was disabled,
+    /// until it loses the focus
+    pub(crate) repeat_disabled: Option<ObjectId>,
+    /// whether the first touch point also drives the pointer
+    pub(crate) touch_emulates_pointer: bool,
+    /// the app id of the application, the default of the app id of its windows
+    pub(crate) app_id: Option<String>,
+    /// whether the scroll direction of the pointers is inverted, i.e. natural scrolling
+    pub(crate) invert_scroll: bool,
+    /// the multipliers of the horizontal and vertical scroll deltas of the pointers
+    pub(crate) scroll_sensitivity: (f32, f32),
+    /// the minimum time between two redraws of a surface, if the frame rate is capped
+    pub(crate) frame_interval: Option<Duration>,
+    /// when the surfaces were last redrawn, for the frame rate cap
+    pub(crate) last_redraws: HashMap<ObjectId, Instant>,
+    /// the surfaces whose redraw is delayed by the frame rate cap, and when they are redrawn
+    pub(crate) throttled_redraws: HashMap<ObjectId, Instant>,
+    /// the surfaces whose redraw was suppressed by the open batch of updates, `None` if no
+    /// batch is open
+    pub(crate) batched_redraws: Option<HashSet<ObjectId>>,
+    /// the surfaces whose redraw was requested before their first configure
+    pub(crate) unconfigured_redraws: HashSet<ObjectId>,
+    pub(crate) data_device_manager: Option<WlDataDeviceManager>,
+    pub(crate) primary_selection_manager: Option<ZwpPrimarySelectionDeviceManagerV1>,
+    pub(crate) activation: Option<XdgActivationV1>,
+    pub(crate) shortcuts_inhibit_manager: Option<ZwpKeyboardShortcutsInhibitManagerV1>,
+    /// the integer scale preferred by the compositor for the buffers of each surface
+    pub(crate) scale_factors: HashMap<ObjectId, i32>,
+    /// the scale set by the application for the buffers of a surface, which replaces the
+    /// one preferred by the compositor
+    pub(crate) scale_overrides: HashMap<ObjectId, i32>,
+    /// an empty region, shared by the surfaces which let every input pass through
+    pub(crate) empty_region: Option<Region>,
+    /// the offer of the drag over a surface of the application, or dropped on it
+    pub(crate) dnd_offer: Option<DndOffer>,
+    /// the drag started by the application, until it ends
+    pub(crate) drag_source: Option<DragSource>,
+    pub(crate) dmabuf: Option<ZwpLinuxDmabufV1>,
+    /// the formats and modifiers which dmabufs can be imported with
+    pub(crate) dmabuf_formats: Vec<(u32, u64)>,
+    /// Surfaces whose redraws are held back after a configure until the application
+    /// releases it, with the serial of the configure it has not released yet.
+    pub(crate) held_configures: HashMap<window::Id, Option<u32>>,
 
     pub(crate) connection: Connection,
 }
@@ -208,6 +537,190 @@ pub enum PopupCreationError {
     /// Popup creation failed
     #[error("Popup creation failed")]
     PopupCreationFailed(GlobalError),
+
+    /// The anchor rectangle is not within the parent
+    #[error("The anchor rectangle {0:?} is not within the parent of size {1:?}")]
+    InvalidAnchorRect(Rectangle<i32>, (u32, u32)),
+
+    /// The anchor rectangle has no area, which the compositor treats as a protocol error
+    #[error("The anchor rectangle {0:?} is empty")]
+    EmptyAnchorRect(Rectangle<i32>),
+}
+
+/// The modifier of formats which can only be used without an explicit modifier.
+pub(crate) const DRM_FORMAT_MOD_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
+/// A shm buffer drawn by the application, in its own pool so it can be resized while the
+/// other buffer of the surface is still read by the compositor.
+#[derive(Debug)]
+pub(crate) struct ShmSlot {
+    pool: RawPool,
+    /// the buffer with its width and height, recreated when the size changes
+    buffer: Option<(WlBuffer, i32, i32)>,
+}
+
+/// The two buffers of a surface, created once they are first needed, and whether the
+/// compositor may still read them.
+#[derive(Debug)]
+pub(crate) struct ShmSlots<T> {
+    slots: [Option<T>; 2],
+    busy: [bool; 2],
+}
+
+impl<T> Default for ShmSlots<T> {
+    fn default() -> Self {
+        Self {
+            slots: [None, None],
+            busy: [false; 2],
+        }
+    }
+}
+
+impl<T> ShmSlots<T> {
+    /// Returns the first slot the compositor does not read.
+    pub(crate) fn free(&self) -> Option<usize> {
+        self.busy.iter().position(|busy| !busy)
+    }
+
+    /// Returns the buffer of the slot, created with `create` if the slot is still empty.
+    pub(crate) fn get_or_try_insert_with<E>(
+        &mut self,
+        slot: usize,
+        create: impl FnOnce() -> Result<T, E>,
+    ) -> Result<&mut T, E> {
+        match &mut self.slots[slot] {
+            Some(buffer) => Ok(buffer),
+            empty => Ok(empty.insert(create()?)),
+        }
+    }
+
+    /// Marks the slot as read by the compositor, once its buffer is attached.
+    pub(crate) fn acquire(&mut self, slot: usize) {
+        self.busy[slot] = true;
+    }
+
+    /// Marks the slot as free, once the compositor released its buffer. Unknown slots are
+    /// ignored.
+    pub(crate) fn release(&mut self, slot: usize) {
+        if let Some(busy) = self.busy.get_mut(slot) {
+            *busy = false;
+        }
+    }
+
+    /// Returns the buffers which were created.
+    pub(crate) fn into_buffers(self) -> impl Iterator<Item = T> {
+        self.slots.into_iter().flatten()
+    }
+}
+
+/// The data of a shm buffer, which identifies its slot once the compositor released it.
+#[derive(Debug, Clone)]
+pub(crate) struct ShmBufferData {
+    pub(crate) surface: WlSurface,
+    pub(crate) slot: usize,
+}
+
+/// The data of a buffer imported from a dmabuf.
+#[derive(Debug, Clone)]
+pub(crate) struct DmabufBufferData {
+    /// the id given by the application
+    pub(crate) id: u64,
+    /// the surface the buffer is attached to
+    pub(crate) surface: WlSurface,
+    pub(crate) surface_id: window::Id,
+    /// the surface of the application which receives the release of the buffer
+    pub(crate) root: WlSurface,
+}
+
+/// An error that occurred while attaching a dmabuf to a surface.
+#[derive(Debug, thiserror::Error)]
+pub enum DmabufError {
+    /// zwp_linux_dmabuf_v1 is not supported by the compositor
+    #[error("zwp_linux_dmabuf_v1 is not supported by the compositor")]
+    NotSupported,
+
+    /// The surface does not exist
+    #[error("The surface does not exist")]
+    SurfaceMissing,
+
+    /// The buffer has no planes
+    #[error("The buffer has no planes")]
+    NoPlanes,
+}
+
+/// The mime types of text in the clipboard, in the order of preference.
+const TEXT_MIME_TYPES: [&str; 3] = ["text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
+
+/// An error that occurred while acquiring a shm buffer.
+#[derive(Debug, thiserror::Error)]
+pub enum ShmBufferError {
+    /// The shm pool could not be created
+    #[error("The shm pool could not be created: {0}")]
+    Pool(CreatePoolError),
+
+    /// The shm pool could not be resized for the buffer
+    #[error("The shm pool could not be resized for the buffer: {0}")]
+    Resize(std::io::Error),
+
+    /// Both buffers of the surface are still read by the compositor
+    #[error("Both buffers of the surface are still read by the compositor")]
+    InUse,
+}
+
+/// An error that occurred while reading the clipboard.
+#[derive(Debug, thiserror::Error)]
+pub enum ClipboardError {
+    /// The active seat has no selection
+    #[error("The active seat has no selection")]
+    NoSelection,
+
+    /// The selection is not offered as text
+    #[error("The selection is not offered as text")]
+    NoText,
+
+    /// No drag entered a surface of the application, or was dropped on it
+    #[error("There is no drag and drop offer")]
+    NoDndOffer,
+
+    /// The contents of the selection could not be received
+    #[error("The contents of the selection could not be received: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// An error that occurred while grabbing a popup.
+#[derive(Debug, thiserror::Error)]
+pub enum PopupGrabError {
+    /// The popup does not exist
+    #[error("The popup does not exist")]
+    PopupMissing,
+
+    /// The seat does not exist
+    #[error("The seat does not exist")]
+    SeatMissing,
+
+    /// The seat does not hold a pointer button, whose press the grab must respond to
+    #[error("The seat has no input to grab the popup with")]
+    NoInput,
+
+    /// Popups can only be grabbed before they are mapped, a mapped popup has to be recreated
+    #[error("The popup is already mapped")]
+    AlreadyMapped,
+}
+
+/// An error that occurred while starting a drag.
+#[derive(Debug, thiserror::Error)]
+pub enum DragError {
+    /// The compositor does not support data devices, or the seat has none
+    #[error("There is no data device to start the drag with")]
+    DataDeviceMissing,
+
+    /// The surface the drag starts from does not exist
+    #[error("The surface does not exist")]
+    SurfaceMissing,
+
+    /// No pointer button is held on the origin, which the drag must start from
+    #[error("The seat has no input to start the drag with")]
+    NoInput,
 }
 
 /// An error that occurred while running an application.
@@ -224,134 +737,1568 @@ pub enum LayerSurfaceCreationError {
     /// LayerSurface creation failed
     #[error("Layer Surface creation failed")]
     LayerSurfaceCreationFailed(GlobalError),
+
+    /// A dimension of the size is left to the compositor, but the surface is not anchored to
+    /// both edges along it, so the compositor can't determine it
+    #[error("The layer surface has no {0} and is not anchored to both edges to stretch it")]
+    Unsized(&'static str),
+}
+
+/// An error that occurred while creating a subsurface.
+#[derive(Debug, thiserror::Error)]
+pub enum SubsurfaceCreationError {
+    /// Subsurfaces are not supported by the compositor
+    #[error("Subsurfaces are not supported by the compositor")]
+    SubcompositorNotSupported,
+
+    /// The specified parent is missing
+    #[error("The specified parent is missing")]
+    ParentMissing,
+}
+
+// subsurface bookkeeping is also needed by the shell handlers
+impl<T: Debug> SctkState<T> {
+    /// Destroys the subsurface with the given id, and every subsurface stacked on it.
+    /// Returns the id of the parent, which must be committed for the change to apply.
+    pub(crate) fn destroy_subsurface(&mut self, id: window::Id) -> Option<window::Id> {
+        let i = self.subsurfaces.iter().position(|s| s.id == id)?;
+        let subsurface = self.subsurfaces.remove(i);
+        self.destroy_subsurfaces_of(id);
+        subsurface.subsurface.destroy();
+        subsurface.wl_surface.destroy();
+        Some(subsurface.parent)
+    }
+
+    /// Destroys the subsurfaces of a surface which is being destroyed.
+    pub(crate) fn destroy_subsurfaces_of(&mut self, parent: window::Id) {
+        let children: Vec<_> = self
+            .subsurfaces
+            .iter()
+            .filter(|s| s.parent == parent)
+            .map(|s| s.id)
+            .collect();
+        for child in children {
+            let _ = self.destroy_subsurface(child);
+        }
+    }
+
+    /// Shows the cursor image of the application, if there is one, for a pointer which
+    /// entered a surface with `serial`.
+    pub(crate) fn update_cursor(&self, ptr: &WlPointer, serial: u32) {
+        if let (Some(image), Some(cursor_surface)) = (&self.cursor_image, &self.cursor_surface) {
+            ptr.set_cursor(serial, Some(cursor_surface), image.hotspot.0, image.hotspot.1);
+        }
+    }
+
+    /// Re-applies the position of the subsurfaces of `parent`, so that they are
+    /// moved together with the next commit of the resized parent.
+    pub(crate) fn reposition_subsurfaces(&self, parent: window::Id) {
+        for s in self.subsurfaces.iter().filter(|s| s.parent == parent) {
+            s.subsurface.set_position(s.position.0, s.position.1);
+        }
+    }
+
+    /// Records the serial of a configure of the surface, which holds back its redraws
+    /// until the application releases it, if it holds the configures of the surface.
+    pub(crate) fn configure_received(&mut self, id: window::Id, serial: u32) {
+        let object_id = if let Some(w) = self.windows.iter_mut().find(|w| w.id == id) {
+            w.configure_serial.replace(serial);
+            Some(w.window.wl_surface().id())
+        } else if let Some(l) = self.layer_surfaces.iter_mut().find(|l| l.id == id) {
+            l.configure_serial.replace(serial);
+            Some(l.surface.wl_surface().id())
+        } else {
+            self.popups.iter().find(|p| p.id == id).map(|p| p.popup.wl_surface().id())
+        };
+        if let Some(pending) = self.held_configures.get_mut(&id) {
+            pending.replace(serial);
+        }
+        // the redraws requested before the first configure can be drawn now
+        if let Some(object_id) = object_id.filter(|o| self.unconfigured_redraws.remove(o)) {
+            self.pending_user_events
+                .push(Event::SctkEvent(IcedSctkEvent::RedrawRequested(object_id)));
+        }
+    }
+
+    /// Returns the surfaces which are drawn by the renderer, and follow the scale of the
+    /// outputs they are on.
+    pub(crate) fn drawn_surfaces(&self) -> impl Iterator<Item = &WlSurface> {
+        self.windows
+            .iter()
+            .map(|w| w.window.wl_surface())
+            .chain(self.layer_surfaces.iter().map(|l| l.surface.wl_surface()))
+            .chain(self.popups.iter().map(|p| p.popup.wl_surface()))
+    }
+
+    /// Returns the outputs the surface entered.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:event:enter>
+    pub(crate) fn surface_outputs(&self, surface: &WlSurface) -> Vec<WlOutput> {
+        surface
+            .data::<SurfaceData>()
+            .map(|data| data.outputs().collect())
+            .unwrap_or_default()
+    }
+
+    /// Reports the outputs the surface is displayed on, once it entered or left one.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:event:leave>
+    pub(crate) fn surface_outputs_changed(&mut self, surface: &WlSurface) {
+        if self.drawn_surfaces().any(|s| s == surface) {
+            let outputs = self.surface_outputs(surface);
+            self.sctk_events.push(SctkEvent::SurfaceOutputsChanged {
+                id: surface.clone(),
+                outputs,
+            });
+        }
+    }
+
+    /// Returns the largest scale of the outputs the surface entered, which the compositor
+    /// prefers for its buffers.
+    pub(crate) fn output_scale(&self, surface: &WlSurface) -> i32 {
+        self.surface_outputs(surface)
+            .iter()
+            .filter_map(|o| self.output_state.info(o))
+            .map(|info| info.scale_factor)
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Sets the scale of the buffers of a surface drawn by the renderer, and notifies the
+    /// application if it changed, which sets it with the next buffer it draws.
+    pub(crate) fn set_surface_scale(&mut self, surface: &WlSurface, factor: i32) {
+        let factor = self.scale_overrides.get(&surface.id()).copied().unwrap_or(factor);
+        if !self.drawn_surfaces().any(|s| s == surface)
+            || self.scale_factors.get(&surface.id()) == Some(&factor)
+        {
+            return;
+        }
+        self.scale_factors.insert(surface.id(), factor);
+        // set by the application with the next buffer it draws at the new scale
+        self.sctk_events.push(SctkEvent::ScaleFactorChanged {
+            factor: factor as f64,
+            id: surface.clone(),
+        });
+    }
 }
 
 impl<T> SctkState<T>
 where
     T: 'static + Debug,
 {
-    pub fn get_popup(
-        &mut self,
-        settings: SctkPopupSettings,
-    ) -> Result<(window::Id, WlSurface, WlSurface, WlSurface), PopupCreationError> {
-        let positioner = XdgPositioner::new(&self.xdg_shell_state)
-            .map_err(|e| PopupCreationError::PositionerCreationFailed(e))?;
-        positioner.set_anchor(settings.positioner.anchor);
-        positioner.set_anchor_rect(
-            settings.positioner.anchor_rect.x,
-            settings.positioner.anchor_rect.y,
-            settings.positioner.anchor_rect.width,
-            settings.positioner.anchor_rect.height,
-        );
-        positioner.set_constraint_adjustment(settings.positioner.constraint_adjustment);
-        positioner.set_gravity(settings.positioner.gravity);
-        positioner.set_offset(settings.positioner.offset.0, settings.positioner.offset.1);
-        if settings.positioner.reactive {
-            positioner.set_reactive();
-        }
-        positioner.set_size(
-            settings.positioner.size.0 as i32,
-            settings.positioner.size.1 as i32,
-        );
+    /// Returns the seat and the serial of the latest pointer press on `surface` whose
+    /// button is still held. Interactive moves and resizes must be started with such a press.
+    pub(crate) fn last_press_on(&self, surface: &WlSurface) -> Option<(&WlSeat, u32)> {
+        self.latest_press(Some(surface))
+    }
 
-        if let Some(parent) = self.layer_surfaces.iter().find(|l| l.id == settings.parent) {
-            let wl_surface = self.compositor_state.create_surface(&self.queue_handle);
-            let popup = Popup::from_surface(
-                None,
-                &positioner,
-                &self.queue_handle,
-                wl_surface.clone(),
-                &self.xdg_shell_state,
-            )
-            .map_err(|e| PopupCreationError::PopupCreationFailed(e))?;
+    /// Returns the seat and the serial of the latest pointer press whose button is still held,
+    /// among the seats whose pointer is over `surface`, or all seats.
+    fn latest_press(&self, surface: Option<&WlSurface>) -> Option<(&WlSeat, u32)> {
+        self.seats
+            .iter()
+            .filter(|s| surface.is_none() || s.ptr_focus.as_ref() == surface)
+            .filter_map(|s| s.last_ptr_press.map(|(time, _, serial)| (time, &s.seat, serial)))
+            .max_by_key(|(time, ..)| *time)
+            .map(|(_, seat, serial)| (seat, serial))
+    }
 
-            parent.surface.get_popup(popup.xdg_popup());
-            wl_surface.commit();
-            self.popups.push(SctkPopup {
-                id: settings.id,
-                popup: popup.clone(),
-                parent: SctkSurface::LayerSurface(parent.surface.wl_surface().clone()),
-                toplevel: parent.surface.wl_surface().clone(),
-                requested_size: settings.positioner.size,
-                last_configure: None,
-                pending_requests: Default::default(),
-            });
-            Ok((
-                settings.id,
-                parent.surface.wl_surface().clone(),
-                parent.surface.wl_surface().clone(),
-                popup.wl_surface().clone(),
-            ))
-        } else if let Some(parent) = self.windows.iter().find(|w| w.id == settings.parent) {
-            let popup = Popup::new(
-                parent.window.xdg_surface(),
-                &positioner,
-                &self.queue_handle,
-                &self.compositor_state,
-                &self.xdg_shell_state,
-            )
-            .map_err(|e| PopupCreationError::PopupCreationFailed(e))?;
-            self.popups.push(SctkPopup {
-                id: settings.id,
-                popup: popup.clone(),
-                parent: SctkSurface::Window(parent.window.wl_surface().clone()),
-                toplevel: parent.window.wl_surface().clone(),
-                requested_size: settings.positioner.size,
-                last_configure: None,
-                pending_requests: Default::default(),
-            });
-            Ok((
-                settings.id,
-                parent.window.wl_surface().clone(),
-                parent.window.wl_surface().clone(),
-                popup.wl_surface().clone(),
-            ))
-        } else if let Some(i) = self.popups.iter().position(|p| p.id == settings.parent) {
-            let (popup, parent, toplevel) = {
-                let parent = &self.popups[i];
-                (
-                    Popup::new(
-                        parent.popup.xdg_surface(),
-                        &positioner,
-                        &self.queue_handle,
-                        &self.compositor_state,
-                        &self.xdg_shell_state,
-                    )
-                    .map_err(|e| PopupCreationError::PopupCreationFailed(e))?,
-                    parent.popup.wl_surface().clone(),
-                    parent.toplevel.clone(),
-                )
-            };
-            self.popups.push(SctkPopup {
-                id: settings.id,
-                popup: popup.clone(),
-                parent: SctkSurface::Popup(parent.clone()),
-                toplevel: toplevel.clone(),
-                requested_size: settings.positioner.size,
-                last_configure: None,
-                pending_requests: Default::default(),
-            });
-            Ok((
-                settings.id,
-                parent,
-                toplevel,
-                popup.wl_surface().clone(),
-            ))
+    /// Holds back the redraws of a surface after each configure until the application
+    /// releases it.
+    ///
+    /// The configure is still acknowledged to the compositor as soon as it arrives, but it
+    /// only takes effect with the next commit of the surface, so the surface is not drawn
+    /// until the application releases the serial of the latest configure.
+    /// Returns the surface to redraw, if disabling released a held configure.
+    pub(crate) fn set_hold_configures(
+        &mut self,
+        id: window::Id,
+        enabled: bool,
+    ) -> Option<WlSurface> {
+        if enabled {
+            self.held_configures.entry(id).or_default();
+            None
         } else {
-            Err(PopupCreationError::ParentMissing)
+            self.held_configures.remove(&id)??;
+            self.wl_surface(id).cloned()
         }
     }
 
-    pub fn get_window(&mut self, settings: SctkWindowSettings) -> (window::Id, WlSurface) {
-        let SctkWindowSettings {
-            iced_settings:
-                window::Settings {
-                    size,
-                    min_size,
-                    max_size,
-                    decorations,
-                    transparent,
-                    icon,
+    /// Releases the configure with `serial` held back for the application.
+    /// Returns the surface to redraw, if `serial` is the configure the surface waits for.
+    pub(crate) fn release_configure(&mut self, id: window::Id, serial: u32) -> Option<WlSurface> {
+        let pending = self.held_configures.get_mut(&id)?;
+        if *pending != Some(serial) {
+            log::debug!("ignoring release of stale configure {} of surface {:?}", serial, id);
+            return None;
+        }
+        pending.take();
+        self.wl_surface(id).cloned()
+    }
+
+    /// Replaces the scale of the buffers of the surface preferred by the compositor, `None`
+    /// reverts to it. Returns `false` if the surface does not exist or the scale is invalid.
+    pub(crate) fn set_scale_override(&mut self, id: window::Id, scale: Option<i32>) -> bool {
+        let surface = match self.wl_surface(id) {
+            Some(s) if scale.map_or(true, |s| s > 0) => s.clone(),
+            _ => return false,
+        };
+        match scale {
+            Some(scale) => self.scale_overrides.insert(surface.id(), scale),
+            None => self.scale_overrides.remove(&surface.id()),
+        };
+        let scale = self.output_scale(&surface);
+        self.set_surface_scale(&surface, scale);
+        true
+    }
+
+    /// Returns the serial of the latest configure of the window, layer surface or popup.
+    ///
+    /// <https://wayland.app/protocols/xdg-shell#xdg_surface:event:configure>
+    pub(crate) fn configure_serial(&self, id: window::Id) -> Option<u32> {
+        self.windows
+            .iter()
+            .find(|w| w.id == id)
+            .and_then(|w| w.configure_serial)
+            .or_else(|| {
+                self.layer_surfaces
+                    .iter()
+                    .find(|l| l.id == id)
+                    .and_then(|l| l.configure_serial)
+            })
+            .or_else(|| {
+                self.popups
+                    .iter()
+                    .find(|p| p.id == id)
+                    .and_then(|p| p.last_configure.as_ref().map(|c| c.serial))
+            })
+    }
+
+    /// Returns `true` if the surface was not configured yet, which must not have a buffer
+    /// attached before. The redraw is then requested again with its first configure.
+    ///
+    /// <https://wayland.app/protocols/xdg-shell#xdg_surface>
+    pub(crate) fn defer_unconfigured_redraw(&mut self, object_id: &ObjectId) -> bool {
+        let unconfigured = self
+            .windows
+            .iter()
+            .any(|w| w.last_configure.is_none() && &w.window.wl_surface().id() == object_id)
+            || self
+                .layer_surfaces
+                .iter()
+                .any(|l| l.last_configure.is_none() && &l.surface.wl_surface().id() == object_id)
+            || self
+                .popups
+                .iter()
+                .any(|p| p.last_configure.is_none() && &p.popup.wl_surface().id() == object_id);
+        if unconfigured {
+            self.unconfigured_redraws.insert(object_id.clone());
+        }
+        unconfigured
+    }
+
+    /// Whether the surface waits for the application to release a configure.
+    pub(crate) fn awaiting_configure_release(&self, object_id: &ObjectId) -> bool {
+        self.held_configures.iter().any(|(id, pending)| {
+            pending.is_some()
+                && self
+                    .wl_surface(*id)
+                    .map_or(false, |s| &s.id() == object_id)
+        })
+    }
+
+    /// Returns the id of the window, layer surface, popup or subsurface of `wl_surface`.
+    pub(crate) fn surface_id(&self, wl_surface: &WlSurface) -> Option<window::Id> {
+        self.windows
+            .iter()
+            .find(|w| w.window.wl_surface() == wl_surface)
+            .map(|w| w.id)
+            .or_else(|| {
+                self.layer_surfaces
+                    .iter()
+                    .find(|l| l.surface.wl_surface() == wl_surface)
+                    .map(|l| l.id)
+            })
+            .or_else(|| {
+                self.popups
+                    .iter()
+                    .find(|p| p.popup.wl_surface() == wl_surface)
+                    .map(|p| p.id)
+            })
+            .or_else(|| {
+                self.custom_surfaces
+                    .iter()
+                    .find(|c| &c.wl_surface == wl_surface)
+                    .map(|c| c.id)
+            })
+            .or_else(|| {
+                self.subsurfaces
+                    .iter()
+                    .find(|s| &s.wl_surface == wl_surface)
+                    .map(|s| s.id)
+            })
+    }
+
+    /// Returns the surface under the pointer of the active seat, and the position of the
+    /// pointer on it in surface-local coordinates, without the scale factor of the application.
+    pub(crate) fn pointer_position(&self) -> Option<(window::Id, Point)> {
+        let seat = self.seats.first()?;
+        let id = self.surface_id(seat.ptr_focus.as_ref()?)?;
+        let (x, y) = seat.ptr_position;
+        Some((id, Point::new(x as f32, y as f32)))
+    }
+
+    /// Returns the repeat info of the keyboard of the active seat, as provided by the
+    /// compositor, or `None` if the compositor sent none.
+    pub(crate) fn repeat_info(&self) -> Option<RepeatInfo> {
+        let seat = self.seats.first()?;
+        seat.kbd.as_ref()?;
+        if self.repeat_disabled.is_some()
+            && seat.kbd_focus.as_ref().map(|s| s.id()) == self.repeat_disabled
+        {
+            return Some(RepeatInfo::Disable);
+        }
+        seat.repeat_info
+    }
+
+    /// Disables or restores the key repeat of the surface focused by the keyboard of the
+    /// active seat. The repeat is restored when the surface loses the focus.
+    pub(crate) fn set_repeat_enabled(&mut self, enabled: bool) {
+        self.repeat_disabled = if enabled {
+            None
+        } else {
+            self.seats
+                .first()
+                .and_then(|s| s.kbd_focus.as_ref())
+                .map(|s| s.id())
+        };
+    }
+
+    /// Caps the redraws of every surface to the given number of frames per second,
+    /// `None` or `0` removes the cap.
+    pub(crate) fn set_max_frame_rate(&mut self, fps: Option<u32>) {
+        self.frame_interval = frame_interval(fps);
+        if self.frame_interval.is_none() {
+            self.last_redraws.clear();
+        }
+    }
+
+    /// Suppresses the redraws of every surface until [`SctkState::end_batch`], e.g. while the
+    /// application applies many updates at once. An open batch is kept.
+    pub(crate) fn begin_batch(&mut self) {
+        self.batched_redraws.get_or_insert_with(HashSet::new);
+    }
+
+    /// Closes the batch of updates, and returns the surfaces whose redraw was suppressed.
+    pub(crate) fn end_batch(&mut self) -> HashSet<ObjectId> {
+        self.batched_redraws.take().unwrap_or_default()
+    }
+
+    /// Returns `true` if the redraw of the surface is suppressed by an open batch of updates,
+    /// in which case it is redrawn once the batch ends.
+    pub(crate) fn batch_redraw(&mut self, id: &ObjectId) -> bool {
+        match self.batched_redraws.as_mut() {
+            Some(batched) => {
+                batched.insert(id.clone());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if redrawing the surface now would exceed the frame rate cap.
+    /// The redraw is then requested again by a timer once the surface may be redrawn.
+    pub(crate) fn throttle_redraw(&mut self, id: &ObjectId) -> bool {
+        let interval = match self.frame_interval {
+            Some(interval) => interval,
+            None => return false,
+        };
+        if self.throttled_redraws.contains_key(id) {
+            return true;
+        }
+        let now = Instant::now();
+        let due = match self.last_redraws.get(id) {
+            Some(last) if *last + interval > now => *last + interval,
+            _ => {
+                self.last_redraws.insert(id.clone(), now);
+                return false;
+            }
+        };
+        let timer_id = id.clone();
+        let res = self
+            .loop_handle
+            .insert_source(Timer::from_deadline(due), move |_, _, state| {
+                state.throttled_redraws.remove(&timer_id);
+                state
+                    .pending_user_events
+                    .push(Event::SctkEvent(IcedSctkEvent::RedrawRequested(timer_id.clone())));
+                TimeoutAction::Drop
+            });
+        match res {
+            Ok(_) => {
+                self.throttled_redraws.insert(id.clone(), due);
+                true
+            }
+            Err(err) => {
+                log::error!("Failed to delay the redraw of {:?}: {}", id, err);
+                false
+            }
+        }
+    }
+
+    /// Refines the control flow requested by the application. Outstanding redraws only keep
+    /// the loop busy while they are queued: the loop sleeps while they wait for a frame
+    /// callback, a configure or the end of a batch, which all wake it up, and until the
+    /// earliest redraw if they are delayed by the frame rate cap.
+    pub(crate) fn control_flow(&self, requested: ControlFlow) -> ControlFlow {
+        if requested != ControlFlow::Poll || !self.pending_user_events.is_empty() {
+            return requested;
+        }
+        match self.throttled_redraws.values().min() {
+            Some(due) => ControlFlow::WaitUntil(*due),
+            None => ControlFlow::Wait,
+        }
+    }
+
+    /// Returns the interface names of the optional globals which were bound, i.e. the
+    /// protocols supported by both the compositor and the application.
+    pub(crate) fn supported_globals(&self) -> Vec<&'static str> {
+        [
+            self.layer_shell.as_ref().map(|_| "zwlr_layer_shell_v1"),
+            self.subcompositor.as_ref().map(|g| g.interface().name),
+            self.idle_notifier.as_ref().map(|g| g.interface().name),
+            self.data_device_manager.as_ref().map(|g| g.interface().name),
+            self.primary_selection_manager.as_ref().map(|g| g.interface().name),
+            self.activation.as_ref().map(|g| g.interface().name),
+            self.shortcuts_inhibit_manager.as_ref().map(|g| g.interface().name),
+            self.dmabuf.as_ref().map(|g| g.interface().name),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Whether the optional global with the interface name `interface`, e.g.
+    /// `zwlr_layer_shell_v1`, was bound.
+    pub(crate) fn supports(&self, interface: &str) -> bool {
+        self.supported_globals().contains(&interface)
+    }
+
+    /// Returns the name of the compositor, guessed from its globals, and the interface names
+    /// and versions of every global it advertises.
+    pub(crate) fn compositor_info(&self) -> CompositorInfo {
+        let globals: Vec<(String, u32)> = self
+            .registry_state
+            .globals()
+            .map(|g| (g.interface.clone(), g.version))
+            .collect();
+        CompositorInfo {
+            name: compositor_name(globals.iter().map(|(interface, _)| interface.as_str()))
+                .map(String::from),
+            globals,
+        }
+    }
+
+    /// Returns the seats of the application, the active seat first.
+    pub(crate) fn seat_infos(&self) -> Vec<SeatInfo> {
+        self.seats
+            .iter()
+            .map(|s| SeatInfo {
+                seat: s.seat.clone(),
+                name: s.name.clone(),
+                keyboard: s.kbd.is_some(),
+                pointer: s.ptr.is_some(),
+                touch: s.touch.is_some(),
+            })
+            .collect()
+    }
+
+    /// Makes the given seat the active seat, and keeps it active until it is removed.
+    /// Returns `false` if the seat is unknown.
+    pub(crate) fn set_active_seat(&mut self, seat: &WlSeat) -> bool {
+        let i = match self.seats.iter().position(|s| &s.seat == seat) {
+            Some(i) => i,
+            None => return false,
+        };
+        self.seats.swap(0, i);
+        self.seat_pinned = true;
+        true
+    }
+
+    /// Returns the output matching the selector, if any.
+    pub(crate) fn select_output(&self, selector: &OutputSelector) -> Option<WlOutput> {
+        let mut infos = self
+            .outputs
+            .iter()
+            .filter_map(|o| self.output_state.info(o).map(|info| (o, info)));
+        match selector {
+            OutputSelector::Name(name) => infos
+                .find(|(_, info)| info.name.as_ref() == Some(name))
+                .map(|(o, _)| o.clone()),
+            OutputSelector::First => self.outputs.first().cloned(),
+            OutputSelector::Leftmost => infos
+                .min_by_key(|(_, info)| info.logical_position.unwrap_or(info.location))
+                .map(|(o, _)| o.clone()),
+        }
+    }
+
+    /// Returns the multipliers of the horizontal and vertical scroll deltas of the pointers.
+    pub(crate) fn scroll_factor(&self) -> (f32, f32) {
+        let (horizontal, vertical) = self.scroll_sensitivity;
+        if self.invert_scroll {
+            (-horizontal, -vertical)
+        } else {
+            (horizontal, vertical)
+        }
+    }
+
+    /// Returns the [`WlSurface`] of the window, layer surface or popup with the given id.
+    pub(crate) fn wl_surface(&self, id: window::Id) -> Option<&WlSurface> {
+        self.windows
+            .iter()
+            .find(|w| w.id == id)
+            .map(|w| w.window.wl_surface())
+            .or_else(|| {
+                self.layer_surfaces
+                    .iter()
+                    .find(|l| l.id == id)
+                    .map(|l| l.surface.wl_surface())
+            })
+            .or_else(|| {
+                self.popups
+                    .iter()
+                    .find(|p| p.id == id)
+                    .map(|p| p.popup.wl_surface())
+            })
+            .or_else(|| {
+                self.custom_surfaces
+                    .iter()
+                    .find(|c| c.id == id)
+                    .map(|c| &c.wl_surface)
+            })
+            .or_else(|| {
+                self.subsurfaces
+                    .iter()
+                    .find(|s| s.id == id)
+                    .map(|s| &s.wl_surface)
+            })
+    }
+
+    /// Creates a subsurface of the surface `parent`, placed at `position` relative to
+    /// the top left corner of the parent. Returns the surface of the subsurface, and
+    /// the parent, which must be committed for the subsurface to be mapped.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_subcompositor:request:get_subsurface>
+    pub fn get_subsurface(
+        &mut self,
+        id: window::Id,
+        parent: window::Id,
+        position: (i32, i32),
+        sync: bool,
+    ) -> Result<(WlSurface, WlSurface), SubsurfaceCreationError> {
+        let subcompositor = self
+            .subcompositor
+            .as_ref()
+            .ok_or(SubsurfaceCreationError::SubcompositorNotSupported)?;
+        let parent_surface = self
+            .wl_surface(parent)
+            .ok_or(SubsurfaceCreationError::ParentMissing)?
+            .clone();
+        let wl_surface = self.compositor_state.create_surface(&self.queue_handle);
+        let subsurface =
+            subcompositor.get_subsurface(&wl_surface, &parent_surface, &self.queue_handle, ());
+        subsurface.set_position(position.0, position.1);
+        if sync {
+            subsurface.set_sync();
+        } else {
+            subsurface.set_desync();
+        }
+        wl_surface.commit();
+        self.subsurfaces.push(SctkSubsurface {
+            id,
+            parent,
+            wl_surface: wl_surface.clone(),
+            subsurface,
+            position,
+        });
+        Ok((wl_surface, parent_surface))
+    }
+
+    /// Returns the keyboard interactivity of the layer surface backed by `surface`.
+    /// Returns `None` if `surface` is not a layer surface.
+    pub(crate) fn layer_keyboard_interactivity(
+        &self,
+        surface: &WlSurface,
+    ) -> Option<KeyboardInteractivity> {
+        self.layer_surfaces
+            .iter()
+            .find(|l| l.surface.wl_surface() == surface)
+            .map(|l| l.keyboard_interactivity)
+    }
+
+    /// Returns the surface the key events of a keyboard focused on `focus` are delivered to,
+    /// which is the layer surface which grabbed the keyboard, if any.
+    pub(crate) fn keyboard_target(&self, focus: Option<WlSurface>) -> Option<WlSurface> {
+        let grab = self
+            .keyboard_grab
+            .and_then(|id| self.layer_surfaces.iter().find(|l| l.id == id));
+        let is_popup =
+            |surface: &WlSurface| self.popups.iter().any(|p| p.popup.wl_surface() == surface);
+        match grab {
+            // popups keep their key events, as they are grabbed themselves
+            Some(_) if focus.as_ref().map_or(false, is_popup) => focus,
+            Some(grab) => Some(grab.surface.wl_surface().clone()),
+            None => focus,
+        }
+    }
+
+    /// Makes the layer surface exclusively interactive, and delivers every key event to it,
+    /// whichever surface of the application the compositor focuses, until it is released.
+    /// Returns `false` if the layer surface does not exist.
+    ///
+    /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:enum:keyboard_interactivity>
+    pub(crate) fn grab_keyboard(&mut self, id: window::Id) -> bool {
+        if !self.layer_surfaces.iter().any(|l| l.id == id) {
+            return false;
+        }
+        // a grab of another surface is released first
+        if let Some(grabbed) = self.keyboard_grab.filter(|grabbed| *grabbed != id) {
+            self.release_keyboard(grabbed);
+        }
+        self.keyboard_grab = Some(id);
+        self.hold_keyboard(id, KeyboardHold::Grab);
+        true
+    }
+
+    /// Releases the keyboard grabbed by the layer surface, and restores its keyboard
+    /// interactivity.
+    pub(crate) fn release_keyboard(&mut self, id: window::Id) {
+        if self.keyboard_grab == Some(id) {
+            self.keyboard_grab = None;
+            self.release_keyboard_hold(id, KeyboardHold::Grab);
+        }
+    }
+
+    /// Raises the keyboard interactivity of the layer surface with `hold`, which is applied
+    /// with the pending requests of the surface. Returns `false` if the layer surface does
+    /// not exist.
+    pub(crate) fn hold_keyboard(&mut self, id: window::Id, hold: KeyboardHold) -> bool {
+        match self.layer_surfaces.iter_mut().find(|l| l.id == id) {
+            Some(layer_surface) => {
+                if !layer_surface.keyboard_holds.contains(&hold) {
+                    layer_surface.keyboard_holds.push(hold);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Releases `hold` of the layer surface, whose keyboard interactivity is restored once
+    /// nothing holds it anymore.
+    pub(crate) fn release_keyboard_hold(&mut self, id: window::Id, hold: KeyboardHold) {
+        if let Some(layer_surface) = self.layer_surfaces.iter_mut().find(|l| l.id == id) {
+            layer_surface.keyboard_holds.retain(|h| *h != hold);
+        }
+    }
+
+    /// Moves the focus request of the layer surface backed by `surface` to its focus once
+    /// the keyboard entered it, or releases it once the keyboard left.
+    pub(crate) fn keyboard_focus_changed(&mut self, surface: &WlSurface, entered: bool) {
+        let layer_surface = match self
+            .layer_surfaces
+            .iter_mut()
+            .find(|l| l.surface.wl_surface() == surface)
+        {
+            Some(l) => l,
+            None => return,
+        };
+        let holds = &mut layer_surface.keyboard_holds;
+        if entered {
+            if let Some(hold) = holds.iter_mut().find(|h| **h == KeyboardHold::FocusRequest) {
+                // the surface keeps the focus until the user moves it elsewhere
+                *hold = KeyboardHold::Focused;
+            }
+        } else {
+            holds.retain(|h| *h != KeyboardHold::Focused);
+        }
+    }
+
+    /// Marks the whole surface as opaque, or clears the opaque region.
+    /// Returns the surface which must be committed for the change to apply.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:request:set_opaque_region>
+    pub(crate) fn set_opaque_region(&self, id: window::Id, opaque: bool) -> Option<WlSurface> {
+        let wl_surface = self.wl_surface(id)?.clone();
+        if opaque {
+            // the compositor clips the region to the surface, so it does not
+            // need to be recomputed when the surface is resized
+            let region = Region::new(&self.compositor_state).ok()?;
+            region.add(0, 0, i32::MAX, i32::MAX);
+            wl_surface.set_opaque_region(Some(region.wl_region()));
+        } else {
+            wl_surface.set_opaque_region(None);
+        }
+        Some(wl_surface)
+    }
+
+    /// Restricts the input of the surface to the given rectangles in surface local
+    /// coordinates. `None` or an empty list make the surface click-through.
+    /// Returns the surface which must be committed for the change to apply.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:request:set_input_region>
+    pub(crate) fn set_input_region(
+        &self,
+        id: window::Id,
+        region: Option<&[Rectangle<i32>]>,
+    ) -> Option<WlSurface> {
+        let wl_surface = self.wl_surface(id)?.clone();
+        let input_region = Region::new(&self.compositor_state).ok()?;
+        for rect in region.unwrap_or_default() {
+            input_region.add(rect.x, rect.y, rect.width, rect.height);
+        }
+        wl_surface.set_input_region(Some(input_region.wl_region()));
+        Some(wl_surface)
+    }
+
+    /// Moves the layer surface to the layer above its own, e.g. to show it above another
+    /// surface on the same layer, until it is restored or the `timeout` elapsed.
+    /// Returns the surface to commit, or `None` if it is on the overlay layer already.
+    ///
+    /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:request:set_layer>
+    pub(crate) fn raise_layer_surface(
+        &mut self,
+        id: window::Id,
+        timeout: Option<Duration>,
+    ) -> Option<WlSurface> {
+        let i = self.layer_surfaces.iter().position(|l| l.id == id)?;
+        let above = match layer_above(self.layer_surfaces[i].layer) {
+            Some(layer) => layer,
+            None => {
+                log::warn!("Layer surface {:?} is on the overlay layer, it can't be raised", id);
+                return None;
+            }
+        };
+        let timer = timeout.and_then(|timeout| {
+            self.loop_handle
+                .insert_source(Timer::from_duration(timeout), move |_, _, state| {
+                    // the timer is dropped by returning, not by the restore
+                    if let Some(l) = state.layer_surfaces.iter_mut().find(|l| l.id == id) {
+                        l.raise_timer = None;
+                    }
+                    if let Some(wl_surface) = state.restore_layer_surface(id) {
+                        wl_surface.commit();
+                    }
+                    TimeoutAction::Drop
+                })
+                .map_err(|err| log::error!("Failed to restore the layer of {:?} later: {}", id, err))
+                .ok()
+        });
+        let layer_surface = &mut self.layer_surfaces[i];
+        if let Some(token) = std::mem::replace(&mut layer_surface.raise_timer, timer) {
+            self.loop_handle.remove(token);
+        }
+        layer_surface.raised = true;
+        layer_surface.surface.set_layer(above);
+        Some(layer_surface.surface.wl_surface().clone())
+    }
+
+    /// Moves a raised layer surface back to its own layer.
+    /// Returns the surface to commit, or `None` if it was not raised.
+    pub(crate) fn restore_layer_surface(&mut self, id: window::Id) -> Option<WlSurface> {
+        let layer_surface = self.layer_surfaces.iter_mut().find(|l| l.id == id && l.raised)?;
+        if let Some(token) = layer_surface.raise_timer.take() {
+            self.loop_handle.remove(token);
+        }
+        layer_surface.raised = false;
+        layer_surface.surface.set_layer(layer_surface.layer);
+        Some(layer_surface.surface.wl_surface().clone())
+    }
+
+    /// Lets every pointer and touch input pass through the surface with an empty input
+    /// region, or makes the whole surface accept input again.
+    /// Returns the surface to commit, or `None` if the surface is unknown.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:request:set_input_region>
+    pub(crate) fn set_click_through(
+        &mut self,
+        id: window::Id,
+        click_through: bool,
+    ) -> Option<WlSurface> {
+        let wl_surface = self.wl_surface(id)?.clone();
+        if click_through {
+            // the region is copied by the compositor, so it can be reused for every toggle
+            if self.empty_region.is_none() {
+                self.empty_region = Region::new(&self.compositor_state).ok();
+            }
+            wl_surface.set_input_region(Some(self.empty_region.as_ref()?.wl_region()));
+        } else {
+            wl_surface.set_input_region(None);
+        }
+        Some(wl_surface)
+    }
+
+    /// Creates the notification reporting the inactivity of `seat`, if a timeout is set.
+    ///
+    /// <https://wayland.app/protocols/ext-idle-notify-v1#ext_idle_notifier_v1:request:get_idle_notification>
+    pub(crate) fn get_idle_notification(&self, seat: &WlSeat) -> Option<ExtIdleNotificationV1> {
+        let timeout = self.idle_timeout?;
+        let notifier = self.idle_notifier.as_ref()?;
+        Some(notifier.get_idle_notification(
+            timeout.as_millis().min(u32::MAX as u128) as u32,
+            seat,
+            &self.queue_handle,
+            seat.clone(),
+        ))
+    }
+
+    /// Creates the data device of `seat`, which receives its selection.
+    pub(crate) fn get_data_device(&self, seat: &WlSeat) -> Option<WlDataDevice> {
+        let manager = self.data_device_manager.as_ref()?;
+        Some(manager.get_data_device(seat, &self.queue_handle, seat.clone()))
+    }
+
+    /// Creates the primary selection device of `seat`, if the compositor supports it.
+    ///
+    /// <https://wayland.app/protocols/primary-selection-unstable-v1>
+    pub(crate) fn get_primary_selection_device(
+        &self,
+        seat: &WlSeat,
+    ) -> Option<ZwpPrimarySelectionDeviceV1> {
+        let manager = self.primary_selection_manager.as_ref()?;
+        Some(manager.get_device(seat, &self.queue_handle, seat.clone()))
+    }
+
+    /// Reports the mime types of the new selection, or primary selection, of `seat`,
+    /// if it is the active seat.
+    pub(crate) fn selection_changed(&mut self, seat: &WlSeat, primary: bool) {
+        let my_seat = match self.seats.first() {
+            Some(s) if &s.seat == seat => s,
+            _ => return,
+        };
+        let mime_types = if primary {
+            my_seat.primary_selection.as_ref().and_then(|o| o.data::<DataOfferData>())
+        } else {
+            my_seat.selection.as_ref().and_then(|o| o.data::<DataOfferData>())
+        }
+        .map(|data| data.mime_types())
+        .unwrap_or_default();
+        self.sctk_events.push(SctkEvent::SeatEvent {
+            variant: SeatEventVariant::Selection { primary, mime_types },
+            id: seat.clone(),
+        });
+    }
+
+    /// Returns the mime types of the selection of the active seat, without reading it.
+    /// Empty if there is no selection.
+    pub(crate) fn selection_mime_types(&self) -> Vec<String> {
+        self.seats
+            .first()
+            .and_then(|seat| seat.selection.as_ref())
+            .and_then(|offer| offer.data::<DataOfferData>())
+            .map(|data| data.mime_types())
+            .unwrap_or_default()
+    }
+
+    /// Asks the owner of the selection of the active seat to send it as text, and returns
+    /// the non-blocking end of the pipe it is written to.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_offer:request:receive>
+    pub(crate) fn receive_selection(&self) -> Result<UnixStream, ClipboardError> {
+        let offer = self
+            .seats
+            .first()
+            .and_then(|seat| seat.selection.as_ref())
+            .ok_or(ClipboardError::NoSelection)?;
+        let mime_types = offer
+            .data::<DataOfferData>()
+            .map(|data| data.mime_types())
+            .unwrap_or_default();
+        let mime_type = TEXT_MIME_TYPES
+            .iter()
+            .find(|m| mime_types.iter().any(|offered| offered == *m))
+            .ok_or(ClipboardError::NoText)?;
+
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        offer.receive(mime_type.to_string(), writer.as_raw_fd());
+        // the fd must be sent before our end of it is closed, which is when the owner
+        // can see the end of the contents
+        self.connection.flush().map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, e.to_string())
+        })?;
+        drop(writer);
+        Ok(reader)
+    }
+
+    /// Reads the contents of the selection from `reader` as it becomes readable, without
+    /// blocking the event loop, and passes them to the application once they are complete.
+    pub(crate) fn read_selection(
+        &self,
+        reader: UnixStream,
+        f: Box<dyn Fn(Option<String>) -> T>,
+    ) -> Result<(), ClipboardError> {
+        self.read_pipe(reader, move |state, contents| {
+            let text = contents.map(|c| String::from_utf8_lossy(&c).into_owned());
+            state
+                .pending_user_events
+                .push(Event::SctkEvent(IcedSctkEvent::UserEvent(f(text))));
+        })
+    }
+
+    /// Asks the source of the drag and drop offer to send it as `mime_type`, and returns
+    /// the non-blocking end of the pipe it is written to, with the offer.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_offer:request:receive>
+    pub(crate) fn receive_dnd_offer(
+        &self,
+        mime_type: String,
+    ) -> Result<(UnixStream, WlDataOffer), ClipboardError> {
+        let offer = self
+            .dnd_offer
+            .as_ref()
+            .and_then(|o| o.offer.as_ref())
+            .ok_or(ClipboardError::NoDndOffer)?;
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        offer.receive(mime_type, writer.as_raw_fd());
+        // like for the selection, the fd must be sent before our end of it is closed
+        self.connection.flush().map_err(|e| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, e.to_string())
+        })?;
+        drop(writer);
+        Ok((reader, offer.clone()))
+    }
+
+    /// Reads the contents of the drag and drop offer from `reader`, and passes them to the
+    /// application once they are complete. The offer of a drop is finished and destroyed
+    /// afterwards.
+    pub(crate) fn read_dnd_offer(
+        &self,
+        reader: UnixStream,
+        offer: WlDataOffer,
+        f: Box<dyn FnOnce(Option<Vec<u8>>) -> T>,
+    ) -> Result<(), ClipboardError> {
+        self.read_pipe(reader, move |state, contents| {
+            // the offer of another drag is left alone
+            let read = |o: &DndOffer| o.dropped && o.offer.as_ref() == Some(&offer);
+            if state.dnd_offer.as_ref().map_or(false, read) {
+                state.dnd_offer.take().unwrap().destroy();
+            }
+            state
+                .pending_user_events
+                .push(Event::SctkEvent(IcedSctkEvent::UserEvent(f(contents))));
+        })
+    }
+
+    /// Reads `reader` as it becomes readable, without blocking the event loop, and calls
+    /// `done` with the contents once they are complete, or `None` if they can't be read.
+    fn read_pipe(
+        &self,
+        reader: UnixStream,
+        done: impl FnOnce(&mut Self, Option<Vec<u8>>) + 'static,
+    ) -> Result<(), ClipboardError> {
+        let mut contents = Vec::new();
+        let mut buf = vec![0; 64 * 1024];
+        let mut done = Some(done);
+        self.loop_handle
+            .insert_source(
+                Generic::new(reader, Interest::READ, Mode::Level),
+                move |_, reader, state| {
+                    // a single read per wake up, so large contents don't hold back the
+                    // rendering of the surfaces
+                    let contents = match reader.read(&mut buf) {
+                        Ok(0) => Some(std::mem::take(&mut contents)),
+                        Ok(n) => {
+                            contents.extend_from_slice(&buf[..n]);
+                            return Ok(PostAction::Continue);
+                        }
+                        Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                            return Ok(PostAction::Continue)
+                        }
+                        Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {
+                            return Ok(PostAction::Continue)
+                        }
+                        Err(err) => {
+                            log::error!("Failed to read the offered data: {}", err);
+                            None
+                        }
+                    };
+                    if let Some(done) = done.take() {
+                        done(state, contents);
+                    }
+                    Ok(PostAction::Remove)
+                },
+            )
+            .map_err(|e| {
+                ClipboardError::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+            })?;
+        Ok(())
+    }
+
+    /// Accepts the drag and drop offer as `mime_type`, or rejects it with `None`.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_offer:request:accept>
+    pub(crate) fn accept_dnd_mime_type(&self, mime_type: Option<String>) {
+        if let Some(dnd_offer) = self.dnd_offer.as_ref().filter(|o| !o.dropped) {
+            if let Some(offer) = dnd_offer.offer.as_ref() {
+                offer.accept(dnd_offer.serial, mime_type);
+            }
+        }
+    }
+
+    /// Sets the actions the application supports for the drag and drop offer, and the one
+    /// it prefers.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_offer:request:set_actions>
+    pub(crate) fn set_dnd_actions(&self, actions: DndAction, preferred: DndAction) {
+        let offer = self
+            .dnd_offer
+            .as_ref()
+            .filter(|o| !o.dropped)
+            .and_then(|o| o.offer.as_ref());
+        match offer {
+            Some(offer) if offer.version() >= 3 => offer.set_actions(actions, preferred),
+            Some(_) => log::warn!("wl_data_offer.set_actions is not supported, only copy is"),
+            None => {}
+        }
+    }
+
+    /// Changes the timeout of the idle notifications, recreating them for every seat.
+    /// `None` destroys the notifications.
+    pub(crate) fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        if self.idle_timeout == timeout {
+            return;
+        }
+        self.idle_timeout = timeout;
+        for i in 0..self.seats.len() {
+            if let Some(notification) = self.seats[i].idle_notification.take() {
+                notification.destroy();
+            }
+            let notification = self.get_idle_notification(&self.seats[i].seat);
+            self.seats[i].idle_notification = notification;
+        }
+    }
+
+    /// Uploads the cursor image to a shm buffer and shows it for every pointer of the
+    /// application's surfaces. `None` removes the image, and shows the default cursor of the
+    /// cursor theme instead.
+    pub(crate) fn set_cursor_image(&mut self, image: Option<CursorImage>) {
+        self.cursor_image = image;
+        self.cursor_upload_pending = false;
+        let image = match &self.cursor_image {
+            Some(image) => image,
+            None => {
+                if let Some(cursor_surface) = self.cursor_surface.clone() {
+                    self.release_shm_buffers(&cursor_surface);
+                    self.set_default_cursor(&cursor_surface);
+                }
+                return;
+            }
+        };
+        if image.pixels.len() != image.width as usize * image.height as usize * 4 {
+            log::error!("The cursor image does not match its size");
+            self.cursor_image = None;
+            return;
+        }
+        let cursor_surface = self
+            .cursor_surface
+            .get_or_insert_with(|| self.compositor_state.create_surface(&self.queue_handle))
+            .clone();
+        let (width, height) = (image.width as i32, image.height as i32);
+        let pixels = image.pixels.clone();
+        let scale = image.scale;
+        let buffer = match self.acquire_shm_buffer(&cursor_surface, width, height, |canvas| {
+            rgba_to_argb8888(&pixels, canvas)
+        }) {
+            Ok(buffer) => buffer,
+            // the compositor still reads both buffers, so the image is uploaded once it
+            // released one, instead of drawing into a buffer which is shown
+            Err(ShmBufferError::InUse) => {
+                self.cursor_upload_pending = true;
+                return;
+            }
+            Err(err) => {
+                log::error!("Failed to create a buffer for the cursor: {}", err);
+                return;
+            }
+        };
+        cursor_surface.set_buffer_scale(scale.max(1));
+        cursor_surface.attach(Some(&buffer), 0, 0);
+        cursor_surface.damage_buffer(0, 0, width, height);
+        cursor_surface.commit();
+
+        for seat in &self.seats {
+            if let (Some(ptr), Some(_), Some(serial)) =
+                (&seat.ptr, &seat.ptr_focus, seat.last_ptr_enter)
+            {
+                self.update_cursor(ptr, serial);
+            }
+        }
+    }
+
+    /// Shows the default cursor of the cursor theme for every pointer of the application's
+    /// surfaces, which showed the cursor image on `cursor_surface` until now.
+    fn set_default_cursor(&mut self, cursor_surface: &WlSurface) {
+        if self.cursor_theme.is_none() {
+            let wl_shm = self.shm_state.wl_shm().clone();
+            let theme = match self.ptr_theme.as_ref() {
+                Some((name, size)) => {
+                    CursorTheme::load_from_name(&self.connection, wl_shm, name, *size)
+                }
+                None => CursorTheme::load(&self.connection, wl_shm, 24),
+            };
+            self.cursor_theme = theme
+                .map_err(|err| log::warn!("Failed to load the cursor theme: {:?}", err))
+                .ok();
+        }
+        let image = match self.cursor_theme.as_mut().and_then(|t| t.get_cursor("left_ptr")) {
+            Some(cursor) => &cursor[0],
+            None => return,
+        };
+        let (width, height) = image.dimensions();
+        let (x, y) = image.hotspot();
+        cursor_surface.set_buffer_scale(1);
+        cursor_surface.attach(Some(image), 0, 0);
+        cursor_surface.damage_buffer(0, 0, width as i32, height as i32);
+        cursor_surface.commit();
+
+        for seat in &self.seats {
+            if let (Some(ptr), Some(_), Some(serial)) =
+                (&seat.ptr, &seat.ptr_focus, seat.last_ptr_enter)
+            {
+                ptr.set_cursor(serial, Some(cursor_surface), x as i32, y as i32);
+            }
+        }
+    }
+
+    /// Starts a drag from the surface with the id `origin` with the latest pointer button
+    /// press on it which is still held, offering `contents` for each of their mime types with the
+    /// given actions. The `icon` follows the pointer until the drag ends.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_device:request:start_drag>
+    pub(crate) fn start_drag(
+        &mut self,
+        origin: window::Id,
+        contents: Vec<(String, Vec<u8>)>,
+        actions: DndAction,
+        icon: Option<CursorImage>,
+    ) -> Result<(), DragError> {
+        let manager = self
+            .data_device_manager
+            .clone()
+            .ok_or(DragError::DataDeviceMissing)?;
+        let origin = self.wl_surface(origin).ok_or(DragError::SurfaceMissing)?.clone();
+        let (seat, serial) = self.last_press_on(&origin).ok_or(DragError::NoInput)?;
+        let device = self
+            .seats
+            .iter()
+            .find(|s| &s.seat == seat)
+            .and_then(|s| s.data_device.clone())
+            .ok_or(DragError::DataDeviceMissing)?;
+
+        // a previous drag which did not end yet is abandoned
+        if let Some(previous) = self.drag_source.take() {
+            self.destroy_drag(previous);
+        }
+        let mime_types: Vec<_> = contents.iter().map(|(m, _)| m.clone()).collect();
+        let source = manager.create_data_source(
+            &self.queue_handle,
+            DataSourceData {
+                contents,
+                ..Default::default()
+            },
+        );
+        for mime_type in mime_types {
+            source.offer(mime_type);
+        }
+        if manager.version() >= 3 {
+            source.set_actions(actions);
+        }
+        let icon_surface = icon
+            .as_ref()
+            .map(|_| self.compositor_state.create_surface(&self.queue_handle));
+        device.start_drag(Some(&source), &origin, icon_surface.as_ref(), serial);
+        self.drag_source = Some(DragSource {
+            source,
+            icon: icon_surface.clone(),
+        });
+
+        let (surface, image) = match (icon_surface, icon) {
+            (Some(surface), Some(image)) => (surface, image),
+            _ => return Ok(()),
+        };
+        if image.pixels.len() != image.width as usize * image.height as usize * 4 {
+            log::error!("The drag icon does not match its size");
+            return Ok(());
+        }
+        let (width, height) = (image.width as i32, image.height as i32);
+        let buffer = match self.acquire_shm_buffer(&surface, width, height, |canvas| {
+            rgba_to_argb8888(&image.pixels, canvas)
+        }) {
+            Ok(buffer) => buffer,
+            Err(err) => {
+                log::error!("Failed to create a buffer for the drag icon: {}", err);
+                return Ok(());
+            }
+        };
+        surface.set_buffer_scale(image.scale.max(1));
+        // the pointer holds the icon at its hotspot
+        let (x, y) = image.hotspot;
+        if surface.version() >= 5 {
+            surface.offset(-x, -y);
+            surface.attach(Some(&buffer), 0, 0);
+        } else {
+            surface.attach(Some(&buffer), -x, -y);
+        }
+        surface.damage_buffer(0, 0, width, height);
+        surface.commit();
+        Ok(())
+    }
+
+    /// Destroys the drag started by the application with `source` once it ended, with the
+    /// surface of its icon.
+    pub(crate) fn end_drag(&mut self, source: &WlDataSource) {
+        match self.drag_source.take() {
+            Some(drag) if &drag.source == source => self.destroy_drag(drag),
+            drag => {
+                self.drag_source = drag;
+                source.destroy();
+            }
+        }
+    }
+
+    fn destroy_drag(&mut self, drag: DragSource) {
+        if let Some(icon) = drag.icon {
+            self.release_shm_buffers(&icon);
+            icon.destroy();
+        }
+        drag.source.destroy();
+    }
+
+    /// Draws into a buffer of the surface with `draw`, and returns the buffer to attach.
+    /// The buffers are Argb8888, and each surface has two of them, so one can be drawn while
+    /// the compositor still reads the other. A buffer is reused once the compositor released it.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_buffer:event:release>
+    pub(crate) fn acquire_shm_buffer(
+        &mut self,
+        surface: &WlSurface,
+        width: i32,
+        height: i32,
+        draw: impl FnOnce(&mut [u8]),
+    ) -> Result<WlBuffer, ShmBufferError> {
+        let slots = self.shm_buffers.entry(surface.id()).or_default();
+        let slot = slots.free().ok_or(ShmBufferError::InUse)?;
+        let stride = width * 4;
+        let len = (stride * height) as usize;
+        let shm_state = &self.shm_state;
+        let shm_slot = slots.get_or_try_insert_with(slot, || {
+            Ok(ShmSlot {
+                pool: RawPool::new(len, shm_state).map_err(ShmBufferError::Pool)?,
+                buffer: None,
+            })
+        })?;
+        if shm_slot.pool.len() < len {
+            shm_slot.pool.resize(len).map_err(ShmBufferError::Resize)?;
+        }
+        let buffer = match &shm_slot.buffer {
+            Some((buffer, w, h)) if (*w, *h) == (width, height) => buffer.clone(),
+            _ => {
+                if let Some((buffer, _, _)) = shm_slot.buffer.take() {
+                    buffer.destroy();
+                }
+                let buffer = shm_slot.pool.create_buffer(
+                    0,
+                    width,
+                    height,
+                    stride,
+                    wl_shm::Format::Argb8888,
+                    ShmBufferData {
+                        surface: surface.clone(),
+                        slot,
+                    },
+                    &self.queue_handle,
+                );
+                shm_slot.buffer = Some((buffer.clone(), width, height));
+                buffer
+            }
+        };
+        draw(&mut shm_slot.pool.mmap()[..len]);
+        slots.acquire(slot);
+        Ok(buffer)
+    }
+
+    /// Marks the buffer in `slot` of the surface as free, once the compositor released it,
+    /// and uploads the cursor image if it waited for the buffer.
+    pub(crate) fn shm_buffer_released(&mut self, surface: &WlSurface, slot: usize) {
+        if let Some(slots) = self.shm_buffers.get_mut(&surface.id()) {
+            slots.release(slot);
+        }
+        if self.cursor_upload_pending && self.cursor_surface.as_ref() == Some(surface) {
+            let image = self.cursor_image.take();
+            self.set_cursor_image(image);
+        }
+    }
+
+    /// Frees the buffers of the surface, e.g. once it is destroyed or drawn by other means.
+    pub(crate) fn release_shm_buffers(&mut self, surface: &WlSurface) {
+        for shm_slot in self
+            .shm_buffers
+            .remove(&surface.id())
+            .into_iter()
+            .flat_map(ShmSlots::into_buffers)
+        {
+            if let Some((buffer, _, _)) = shm_slot.buffer {
+                buffer.destroy();
+            }
+        }
+    }
+
+    /// Returns the logical size of the window, layer surface or popup with the given id,
+    /// once it was configured.
+    fn configured_size(&self, id: window::Id) -> Option<(u32, u32)> {
+        self.windows
+            .iter()
+            .find(|w| w.id == id)
+            .and_then(|w| w.last_configure.as_ref()?.new_size)
+            .or_else(|| {
+                self.layer_surfaces
+                    .iter()
+                    .find(|l| l.id == id)
+                    .and_then(|l| l.current_size)
+                    .map(|s| (s.width, s.height))
+            })
+            .or_else(|| {
+                self.popups
+                    .iter()
+                    .find(|p| p.id == id)
+                    .and_then(|p| p.last_configure.as_ref())
+                    .map(|c| (c.width as u32, c.height as u32))
+            })
+    }
+
+    /// Creates a popup of the window, layer surface or popup `settings.parent`, placed
+    /// relative to the anchor rectangle of the positioner, in the logical coordinates of
+    /// the parent. The rectangle may be any non-empty part of the parent, e.g. a 1x1
+    /// rectangle at the cursor for a context menu.
+    pub fn get_popup(
+        &mut self,
+        settings: SctkPopupSettings,
+    ) -> Result<(window::Id, WlSurface, WlSurface, WlSurface), PopupCreationError> {
+        let anchor_rect = settings.positioner.anchor_rect;
+        if anchor_rect.width <= 0 || anchor_rect.height <= 0 {
+            return Err(PopupCreationError::EmptyAnchorRect(anchor_rect));
+        }
+        // a parent which was not configured yet can't be checked
+        if let Some(parent_size) = self.configured_size(settings.parent) {
+            if anchor_rect.x < 0
+                || anchor_rect.y < 0
+                || anchor_rect.x + anchor_rect.width > parent_size.0 as i32
+                || anchor_rect.y + anchor_rect.height > parent_size.1 as i32
+            {
+                return Err(PopupCreationError::InvalidAnchorRect(anchor_rect, parent_size));
+            }
+        }
+        let size = clamp_popup_size(&settings.positioner.size_limits, settings.positioner.size);
+        let positioner = self
+            .xdg_positioner(&settings.positioner)
+            .map_err(|e| PopupCreationError::PositionerCreationFailed(e))?;
+
+        if let Some(parent) = self.layer_surfaces.iter().find(|l| l.id == settings.parent) {
+            let wl_surface = self.compositor_state.create_surface(&self.queue_handle);
+            let popup = Popup::from_surface(
+                None,
+                &positioner,
+                &self.queue_handle,
+                wl_surface.clone(),
+                &self.xdg_shell_state,
+            )
+            .map_err(|e| PopupCreationError::PopupCreationFailed(e))?;
+
+            parent.surface.get_popup(popup.xdg_popup());
+            wl_surface.commit();
+            self.popups.push(SctkPopup {
+                id: settings.id,
+                popup: popup.clone(),
+                parent: SctkSurface::LayerSurface(parent.surface.wl_surface().clone()),
+                toplevel: parent.surface.wl_surface().clone(),
+                requested_size: size,
+                reposition_token: 0,
+                positioner: settings.positioner.clone(),
+                last_configure: None,
+                pending_requests: Default::default(),
+            });
+            Ok((
+                settings.id,
+                parent.surface.wl_surface().clone(),
+                parent.surface.wl_surface().clone(),
+                popup.wl_surface().clone(),
+            ))
+        } else if let Some(parent) = self.windows.iter().find(|w| w.id == settings.parent) {
+            let popup = Popup::new(
+                parent.window.xdg_surface(),
+                &positioner,
+                &self.queue_handle,
+                &self.compositor_state,
+                &self.xdg_shell_state,
+            )
+            .map_err(|e| PopupCreationError::PopupCreationFailed(e))?;
+            self.popups.push(SctkPopup {
+                id: settings.id,
+                popup: popup.clone(),
+                parent: SctkSurface::Window(parent.window.wl_surface().clone()),
+                toplevel: parent.window.wl_surface().clone(),
+                requested_size: size,
+                reposition_token: 0,
+                positioner: settings.positioner.clone(),
+                last_configure: None,
+                pending_requests: Default::default(),
+            });
+            Ok((
+                settings.id,
+                parent.window.wl_surface().clone(),
+                parent.window.wl_surface().clone(),
+                popup.wl_surface().clone(),
+            ))
+        } else if let Some(i) = self.popups.iter().position(|p| p.id == settings.parent) {
+            let (popup, parent, toplevel) = {
+                let parent = &self.popups[i];
+                (
+                    Popup::new(
+                        parent.popup.xdg_surface(),
+                        &positioner,
+                        &self.queue_handle,
+                        &self.compositor_state,
+                        &self.xdg_shell_state,
+                    )
+                    .map_err(|e| PopupCreationError::PopupCreationFailed(e))?,
+                    parent.popup.wl_surface().clone(),
+                    parent.toplevel.clone(),
+                )
+            };
+            self.popups.push(SctkPopup {
+                id: settings.id,
+                popup: popup.clone(),
+                parent: SctkSurface::Popup(parent.clone()),
+                toplevel: toplevel.clone(),
+                requested_size: size,
+                reposition_token: 0,
+                positioner: settings.positioner.clone(),
+                last_configure: None,
+                pending_requests: Default::default(),
+            });
+            Ok((
+                settings.id,
+                parent,
+                toplevel,
+                popup.wl_surface().clone(),
+            ))
+        } else {
+            Err(PopupCreationError::ParentMissing)
+        }
+    }
+
+    /// Creates the xdg positioner of a popup, whose size is clamped to the size limits.
+    fn xdg_positioner(&self, settings: &SctkPositioner) -> Result<XdgPositioner, GlobalError> {
+        let positioner = XdgPositioner::new(&self.xdg_shell_state)?;
+        positioner.set_anchor(settings.anchor);
+        positioner.set_anchor_rect(
+            settings.anchor_rect.x,
+            settings.anchor_rect.y,
+            settings.anchor_rect.width,
+            settings.anchor_rect.height,
+        );
+        positioner.set_constraint_adjustment(settings.constraint_adjustment);
+        positioner.set_gravity(settings.gravity);
+        positioner.set_offset(settings.offset.0, settings.offset.1);
+        if settings.reactive {
+            positioner.set_reactive();
+        }
+        let size = clamp_popup_size(&settings.size_limits, settings.size);
+        positioner.set_size(size.0 as i32, size.1 as i32);
+        Ok(positioner)
+    }
+
+    /// Moves or resizes a popup with a new positioner. The compositor confirms it with a
+    /// configure, preceded by the repositioned event with the token of the reposition.
+    ///
+    /// <https://wayland.app/protocols/xdg-shell#xdg_popup:request:reposition>
+    pub(crate) fn reposition_popup(&mut self, id: window::Id, settings: SctkPositioner) {
+        if settings.anchor_rect.width <= 0 || settings.anchor_rect.height <= 0 {
+            log::error!(
+                "Failed to reposition popup {:?}: {}",
+                id,
+                PopupCreationError::EmptyAnchorRect(settings.anchor_rect)
+            );
+            return;
+        }
+        let positioner = match self.xdg_positioner(&settings) {
+            Ok(positioner) => positioner,
+            Err(err) => {
+                log::error!("Failed to create the positioner of popup {:?}: {}", id, err);
+                return;
+            }
+        };
+        let popup = match self.popups.iter_mut().find(|p| p.id == id) {
+            Some(popup) => popup,
+            None => return,
+        };
+        // xdg_popup.reposition was only added in version 3
+        if popup.popup.xdg_popup().version() < 3 {
+            log::warn!("Failed to reposition popup {:?}, xdg_popup.reposition is not supported", id);
+            return;
+        }
+        popup.reposition_token += 1;
+        popup.requested_size = clamp_popup_size(&settings.size_limits, settings.size);
+        popup.popup.xdg_popup().reposition(&positioner, popup.reposition_token);
+        popup.positioner = settings;
+    }
+
+    /// Hints where the popup should be placed, by moving it next to the hinted position
+    /// in its parent. Popups are always shown with their parent, so the output of the hint
+    /// is ignored. Returns `false` if the surface can't be placed, which is the case for
+    /// toplevels, as clients can't position them.
+    pub(crate) fn set_placement_hint(&mut self, id: window::Id, hint: PlacementHint) -> bool {
+        if let Some(popup) = self.popups.iter().find(|p| p.id == id) {
+            let mut positioner = popup.positioner.clone();
+            if let Some((x, y)) = hint.position {
+                // the anchor rectangle must stay within the parent
+                let (x, y) = match self
+                    .surface_id(popup.parent.wl_surface())
+                    .and_then(|parent| self.configured_size(parent))
+                {
+                    Some((w, h)) => (
+                        x.clamp(0, w.saturating_sub(1) as i32),
+                        y.clamp(0, h.saturating_sub(1) as i32),
+                    ),
+                    None => (x.max(0), y.max(0)),
+                };
+                positioner.anchor_rect = Rectangle { x, y, width: 1, height: 1 };
+                positioner.anchor = xdg_positioner::Anchor::None;
+            }
+            if hint.output.is_some() {
+                log::debug!("Popup {:?} is shown on the output of its parent, the output of the hint is ignored", id);
+            }
+            positioner.gravity = hint.gravity;
+            self.reposition_popup(id, positioner);
+            return true;
+        }
+        false
+    }
+
+    pub fn get_window(&mut self, settings: SctkWindowSettings) -> (window::Id, WlSurface) {
+        let descriptor = settings.clone();
+        let SctkWindowSettings {
+            iced_settings:
+                window::Settings {
+                    size,
+                    min_size,
+                    max_size,
+                    decorations,
+                    transparent,
+                    icon,
+                    resizable,
                     ..
                 },
             window_id,
@@ -363,8 +2310,14 @@ where
         // TODO Ashley: set icon
         // TODO Ashley: save settings for window
         // TODO Ashley: decorations
+        // a window which is not resizable keeps the size it is created with
+        let (min_size, max_size) = if resizable {
+            (min_size, max_size)
+        } else {
+            (Some(size), Some(size))
+        };
         let wl_surface = self.compositor_state.create_surface(&self.queue_handle);
-        let mut builder = if let Some(app_id) = app_id {
+        let mut builder = if let Some(app_id) = app_id.or_else(|| self.app_id.clone()) {
             Window::builder().app_id(app_id)
         } else {
             Window::builder()
@@ -414,14 +2367,503 @@ where
             requested_size: Some(size),
             current_size: Some((1, 1)),
             last_configure: None,
+            configure_serial: None,
+            minimized: false,
+            inhibit_shortcuts: false,
+            shortcuts_inhibitor: None,
+            geometry: None,
+            settings: descriptor,
             pending_requests: Vec::new(),
         });
         (window_id, wl_surface)
     }
 
+    /// Asks the compositor to draw the attention of the user to the window, e.g. by
+    /// flashing it in the taskbar. Returns `false` if the window does not exist, or
+    /// xdg_activation_v1 is not supported by the compositor.
+    ///
+    /// <https://wayland.app/protocols/xdg-activation-v1#xdg_activation_v1:request:activate>
+    pub(crate) fn request_attention(&self, id: window::Id) -> bool {
+        self.activate(id, None)
+    }
+
+    /// Requests the activation of the window. With the serial of an input event of a seat,
+    /// compositors usually focus the window, otherwise they only draw attention to it.
+    fn activate(&self, id: window::Id, input: Option<(&WlSeat, u32)>) -> bool {
+        let (activation, window) = match (
+            self.activation.as_ref(),
+            self.windows.iter().find(|w| w.id == id),
+        ) {
+            (Some(activation), Some(window)) => (activation, window),
+            _ => return false,
+        };
+        let wl_surface = window.window.wl_surface();
+        // activated once the compositor sent the token
+        let token = activation.get_activation_token(&self.queue_handle, wl_surface.clone());
+        token.set_surface(wl_surface);
+        if let Some((seat, serial)) = input {
+            token.set_serial(serial, seat);
+        }
+        if let Some(app_id) = self.app_id.clone() {
+            token.set_app_id(app_id);
+        }
+        token.commit();
+        true
+    }
+
+    /// Asks the compositor to give the keyboard focus of the active seat to the surface.
+    ///
+    /// Windows are activated with the latest input of the active seat, and layer surfaces
+    /// become exclusively interactive until they get the focus, then interactive on demand
+    /// until they lose it, after which their keyboard interactivity is restored. Returns
+    /// `false` if the surface does not exist or can't request the focus, like popups, whose
+    /// focus follows their grab, or windows without input to activate them with.
+    pub(crate) fn request_keyboard_focus(&mut self, id: window::Id) -> bool {
+        let focused = self.seats.first().and_then(|s| s.kbd_focus.as_ref());
+        if focused.is_some() && focused == self.wl_surface(id) {
+            return true;
+        }
+        if self.windows.iter().any(|w| w.id == id) {
+            // without the serial of an input, compositors don't move the focus
+            let input = self
+                .seats
+                .first()
+                .and_then(|s| s.last_input_serial.map(|serial| (&s.seat, serial)));
+            return input.is_some() && self.activate(id, input);
+        }
+        self.hold_keyboard(id, KeyboardHold::FocusRequest)
+    }
+
+    /// Asks the compositor to make the window fullscreen, with the shortcuts of the
+    /// compositor inhibited on the active seat while it is if `inhibit_shortcuts` is set.
+    /// Returns the surface to commit.
+    pub(crate) fn set_fullscreen(
+        &mut self,
+        id: window::Id,
+        inhibit_shortcuts: bool,
+    ) -> Option<WlSurface> {
+        let window = self.windows.iter_mut().find(|w| w.id == id)?;
+        window.inhibit_shortcuts = inhibit_shortcuts;
+        // TODO ASHLEY: allow specific output to be requested for fullscreen?
+        window.window.set_fullscreen(None);
+        Some(window.window.wl_surface().clone())
+    }
+
+    /// Inhibits the shortcuts of the compositor for the window on the active seat while it
+    /// is fullscreen, if the application asked for it, so that it receives every key while
+    /// it has the keyboard focus, and restores them otherwise.
+    ///
+    /// <https://wayland.app/protocols/keyboard-shortcuts-inhibit-unstable-v1#zwp_keyboard_shortcuts_inhibit_manager_v1:request:inhibit_shortcuts>
+    pub(crate) fn update_shortcuts_inhibitor(&mut self, id: window::Id, fullscreen: bool) {
+        let window = match self.windows.iter_mut().find(|w| w.id == id) {
+            Some(w) => w,
+            None => return,
+        };
+        if !fullscreen || !window.inhibit_shortcuts {
+            if let Some(inhibitor) = window.shortcuts_inhibitor.take() {
+                inhibitor.destroy();
+            }
+            return;
+        }
+        if window.shortcuts_inhibitor.is_some() {
+            return;
+        }
+        match (self.shortcuts_inhibit_manager.as_ref(), self.seats.first()) {
+            (Some(manager), Some(seat)) => {
+                window.shortcuts_inhibitor = Some(manager.inhibit_shortcuts(
+                    window.window.wl_surface(),
+                    &seat.seat,
+                    &self.queue_handle,
+                    (),
+                ));
+            }
+            _ => log::warn!("Failed to inhibit shortcuts, zwp_keyboard_shortcuts_inhibit_manager_v1 or a seat is missing"),
+        }
+    }
+
+    /// Fixes the size of the window to its current size, by setting its minimum and maximum
+    /// size to it, or restores the size limits of the application. Compositors drawing the
+    /// decorations hide their resize handles for a window of a fixed size.
+    ///
+    /// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:request:set_max_size>
+    pub(crate) fn set_resizable(&mut self, id: window::Id, resizable: bool) -> Option<WlSurface> {
+        let window = self.windows.iter_mut().find(|w| w.id == id)?;
+        let settings = &mut window.settings.iced_settings;
+        settings.resizable = resizable;
+        if resizable {
+            window.window.set_min_size(settings.min_size);
+            window.window.set_max_size(settings.max_size);
+        } else {
+            let size = window
+                .last_configure
+                .as_ref()
+                .and_then(|c| c.new_size)
+                .or(window.requested_size)
+                .unwrap_or(settings.size);
+            window.window.set_min_size(Some(size));
+            window.window.set_max_size(Some(size));
+        }
+        Some(window.window.wl_surface().clone())
+    }
+
+    /// Requests the decorations preferred by the application for the window.
+    pub(crate) fn set_decorations(
+        &mut self,
+        id: window::Id,
+        decorations: Decorations,
+    ) -> Option<WlSurface> {
+        let window = self.windows.iter_mut().find(|w| w.id == id)?;
+        let (mode, server_side) = match decorations {
+            Decorations::ServerSide | Decorations::Auto => (DecorationMode::Server, true),
+            Decorations::ClientSide => (DecorationMode::Client, false),
+        };
+        // the compositor may still pick the other mode, it is reported once configured
+        window.window.request_decoration_mode(Some(mode));
+        // keep the preference if the window is recreated
+        window.settings.iced_settings.decorations = server_side;
+        Some(window.window.wl_surface().clone())
+    }
+
+    pub fn get_custom_surface(
+        &mut self,
+        id: window::Id,
+        mut handler: Box<dyn SurfaceRoleHandler>,
+    ) -> WlSurface {
+        let wl_surface = self.compositor_state.create_surface(&self.queue_handle);
+        handler.assign_role(&self.connection, &wl_surface);
+        wl_surface.commit();
+        self.custom_surfaces.push(SctkCustomSurface {
+            id,
+            wl_surface: wl_surface.clone(),
+            handler,
+            current_size: None,
+        });
+        self.sctk_events.push(SctkEvent::CustomSurfaceEvent {
+            variant: CustomSurfaceEventVariant::Created(wl_surface.id(), id),
+            id: wl_surface.clone(),
+        });
+        wl_surface
+    }
+
+    /// Recreates the windows and layer surfaces from their settings after the
+    /// connection to the compositor was re-established.
+    /// Popups can't outlive their grab, and custom roles and subsurfaces are
+    /// bound to the old connection, so they are dropped.
+    pub(crate) fn recreate_surfaces(&mut self) -> Vec<(ObjectId, SurfaceIdWrapper, WlSurface)> {
+        let mut surfaces = Vec::new();
+        self.popups.clear();
+        self.custom_surfaces.clear();
+        self.subsurfaces.clear();
+
+        for window in std::mem::take(&mut self.windows) {
+            let mut settings = window.settings;
+            if let Some(size) = window.requested_size {
+                settings.iced_settings.size = size;
+            }
+            let (id, wl_surface) = self.get_window(settings);
+            surfaces.push((wl_surface.id(), SurfaceIdWrapper::Window(id), wl_surface));
+        }
+
+        for layer_surface in std::mem::take(&mut self.layer_surfaces) {
+            let settings = SctkLayerSurfaceSettings {
+                layer: layer_surface.layer,
+                anchor: layer_surface.anchor,
+                keyboard_interactivity: layer_surface.requested_interactivity,
+                margin: layer_surface.margin,
+                exclusive_zone: layer_surface.exclusive_zone,
+                size: layer_surface.requested_size,
+                ..layer_surface.settings
+            };
+            match self.get_layer_surface(settings) {
+                Ok((id, wl_surface)) => {
+                    let recreated = self.layer_surfaces.last_mut().unwrap();
+                    // the focus and the popups were lost with the connection, the grab is
+                    // applied with the next requests of the surface
+                    recreated.keyboard_holds = layer_surface
+                        .keyboard_holds
+                        .into_iter()
+                        .filter(|h| *h == KeyboardHold::Grab)
+                        .collect();
+                    surfaces.push((wl_surface.id(), SurfaceIdWrapper::LayerSurface(id), wl_surface))
+                }
+                Err(err) => log::error!("Failed to recreate layer surface: {}", err),
+            }
+        }
+
+        surfaces
+    }
+
+    /// Turns the configures and closes of custom roles into events.
+    pub(crate) fn poll_custom_surfaces(&mut self) {
+        for custom in &mut self.custom_surfaces {
+            if custom.handler.closed() {
+                self.sctk_events.push(SctkEvent::CustomSurfaceEvent {
+                    variant: CustomSurfaceEventVariant::Done,
+                    id: custom.wl_surface.clone(),
+                });
+                continue;
+            }
+            if let Some(size) = custom.handler.configure() {
+                let first = custom.current_size.is_none();
+                custom.current_size.replace(size);
+                self.sctk_events.push(SctkEvent::CustomSurfaceEvent {
+                    variant: CustomSurfaceEventVariant::Configure(
+                        size,
+                        custom.wl_surface.clone(),
+                        first,
+                    ),
+                    id: custom.wl_surface.clone(),
+                });
+                self.sctk_events
+                    .push(SctkEvent::Draw(custom.wl_surface.clone()));
+            }
+        }
+    }
+
+    /// Queues a request changing a property of a layer surface, so that all requests
+    /// received within one iteration of the event loop are applied with a single commit.
+    pub(crate) fn queue_layer_surface_request(
+        &mut self,
+        request: platform_specific::wayland::layer_surface::Action<T>,
+    ) {
+        use platform_specific::wayland::layer_surface::Action;
+
+        let id = match &request {
+            Action::Size { id, .. }
+            | Action::Anchor { id, .. }
+            | Action::ExclusiveZone { id, .. }
+            | Action::Margin { id, .. }
+            | Action::KeyboardInteractivity { id, .. }
+            | Action::Layer { id, .. } => *id,
+            Action::LayerSurface { .. }
+            | Action::Destroy(_)
+            | Action::InputRegion { .. }
+            | Action::Raise { .. }
+            | Action::Restore(_)
+            | Action::GrabKeyboard(_)
+            | Action::ReleaseKeyboard(_) => return,
+        };
+        if let Some(layer_surface) = self.layer_surfaces.iter_mut().find(|l| l.id == id) {
+            layer_surface.pending_requests.push(request);
+        }
+    }
+
+    /// Applies the queued requests of every layer surface in order.
+    /// Returns the surfaces which must be committed for the changes to apply.
+    pub(crate) fn apply_layer_surface_requests(&mut self) -> Vec<(window::Id, WlSurface)> {
+        use platform_specific::wayland::layer_surface::Action;
+
+        let mut to_commit = Vec::new();
+        for layer_surface in &mut self.layer_surfaces {
+            if layer_surface.pending_requests.is_empty()
+                && layer_surface.keyboard_interactivity
+                    == effective_interactivity(
+                        layer_surface.requested_interactivity,
+                        &layer_surface.keyboard_holds,
+                    )
+            {
+                continue;
+            }
+            // the size and the anchor are validated together once all requests are applied,
+            // as a surface may be anchored to both edges and left unsized in any order
+            let mut size = layer_surface.requested_size;
+            let mut anchor = layer_surface.anchor;
+            for request in layer_surface.pending_requests.drain(..) {
+                match request {
+                    Action::Size { width, height, .. } => size = (width, height),
+                    Action::Anchor { anchor: a, .. } => anchor = a,
+                    Action::ExclusiveZone { exclusive_zone, .. } => {
+                        layer_surface.exclusive_zone = exclusive_zone;
+                        layer_surface.surface.set_exclusive_zone(exclusive_zone);
+                    }
+                    Action::Margin { margin, .. } => {
+                        layer_surface.margin = margin;
+                        layer_surface.surface.set_margin(
+                            margin.top,
+                            margin.right,
+                            margin.bottom,
+                            margin.left,
+                        );
+                    }
+                    Action::KeyboardInteractivity {
+                        keyboard_interactivity,
+                        ..
+                    } => {
+                        layer_surface.requested_interactivity = keyboard_interactivity;
+                    }
+                    Action::Layer { layer, .. } => {
+                        layer_surface.layer = layer;
+                        layer_surface.raised = false;
+                        if let Some(token) = layer_surface.raise_timer.take() {
+                            self.loop_handle.remove(token);
+                        }
+                        layer_surface.surface.set_layer(layer);
+                    }
+                    Action::LayerSurface { .. }
+                    | Action::Destroy(_)
+                    | Action::InputRegion { .. }
+                    | Action::Raise { .. }
+                    | Action::Restore(_)
+                    | Action::GrabKeyboard(_)
+                    | Action::ReleaseKeyboard(_) => {}
+                }
+            }
+            if let Some(dimension) = unsized_dimension(anchor, size) {
+                log::warn!("Ignoring the size and anchor of layer surface {:?}, it has no {} and is not anchored to both edges to stretch it", layer_surface.id, dimension);
+            } else {
+                if size != layer_surface.requested_size {
+                    layer_surface.requested_size = size;
+                    layer_surface
+                        .surface
+                        .set_size(size.0.unwrap_or_default(), size.1.unwrap_or_default());
+                }
+                if anchor != layer_surface.anchor {
+                    layer_surface.anchor = anchor;
+                    layer_surface.surface.set_anchor(anchor);
+                }
+            }
+            let interactivity = effective_interactivity(
+                layer_surface.requested_interactivity,
+                &layer_surface.keyboard_holds,
+            );
+            if interactivity != layer_surface.keyboard_interactivity {
+                layer_surface.keyboard_interactivity = interactivity;
+                layer_surface.surface.set_keyboard_interactivity(interactivity);
+            }
+            to_commit.push((layer_surface.id, layer_surface.surface.wl_surface().clone()));
+        }
+        to_commit
+    }
+
+    /// Grabs the popup with `seat`, using the serial of the button press of that seat which
+    /// is still held, or with the seat of the latest held press, so the popup is dismissed by
+    /// the input of that seat.
+    ///
+    /// The grab must be requested before the popup is mapped, a grab of a mapped popup is a
+    /// protocol error. To be grabbed by another seat, the popup has to be recreated.
+    pub(crate) fn grab_popup(
+        &self,
+        id: window::Id,
+        seat: Option<&WlSeat>,
+    ) -> Result<(), PopupGrabError> {
+        let popup = self
+            .popups
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or(PopupGrabError::PopupMissing)?;
+        // a popup is mapped right after its first configure
+        if popup.last_configure.is_some() {
+            return Err(PopupGrabError::AlreadyMapped);
+        }
+        let (seat, serial) = match seat {
+            Some(seat) => {
+                let seat = self
+                    .seats
+                    .iter()
+                    .find(|s| &s.seat == seat)
+                    .ok_or(PopupGrabError::SeatMissing)?;
+                let (_, _, serial) = seat.last_ptr_press.ok_or(PopupGrabError::NoInput)?;
+                (&seat.seat, serial)
+            }
+            None => self.latest_press(None).ok_or(PopupGrabError::NoInput)?,
+        };
+        popup.popup.xdg_popup().grab(seat, serial);
+        Ok(())
+    }
+
+    /// Lets a grabbed popup take the keyboard focus, which a popup of a layer surface only
+    /// can if the layer surface accepts keyboard input, by making the layer surface
+    /// interactive on demand until the popup is destroyed. Popups of windows take the
+    /// focus regardless.
+    ///
+    /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:request:set_keyboard_interactivity>
+    pub(crate) fn allow_popup_keyboard_grab(&mut self, id: window::Id) {
+        if let Some(layer_id) = self.popup_layer_surface(id) {
+            self.hold_keyboard(layer_id, KeyboardHold::Popup(id));
+        }
+    }
+
+    /// Restores the keyboard interactivity of the layer surface a destroyed popup belonged
+    /// to, which may have been removed from the popups already.
+    pub(crate) fn popup_destroyed(&mut self, id: window::Id) {
+        for layer_surface in &mut self.layer_surfaces {
+            layer_surface.keyboard_holds.retain(|h| *h != KeyboardHold::Popup(id));
+        }
+    }
+
+    /// Returns the layer surface the popup belongs to, if any.
+    fn popup_layer_surface(&self, id: window::Id) -> Option<window::Id> {
+        let toplevel = &self.popups.iter().find(|p| p.id == id)?.toplevel;
+        self.layer_surfaces
+            .iter()
+            .find(|l| l.surface.wl_surface() == toplevel)
+            .map(|l| l.id)
+    }
+
+    /// Returns the surface of the application a subsurface belongs to, or the surface itself
+    /// if it is not a subsurface.
+    fn root_surface(&self, mut id: window::Id) -> Option<&WlSurface> {
+        while let Some(subsurface) = self.subsurfaces.iter().find(|s| s.id == id) {
+            id = subsurface.parent;
+        }
+        self.wl_surface(id)
+    }
+
+    /// Imports `buffer` and attaches it to the surface, which must be committed for the
+    /// buffer to be shown. The surface should be a subsurface, as the buffers of the other
+    /// surfaces are provided by the renderer.
+    pub(crate) fn attach_dmabuf(
+        &mut self,
+        id: window::Id,
+        buffer: DmabufBuffer,
+    ) -> Result<WlSurface, DmabufError> {
+        let dmabuf = self.dmabuf.as_ref().ok_or(DmabufError::NotSupported)?;
+        let wl_surface = self.wl_surface(id).ok_or(DmabufError::SurfaceMissing)?.clone();
+        let root = self.root_surface(id).ok_or(DmabufError::SurfaceMissing)?.clone();
+        if buffer.planes.is_empty() {
+            return Err(DmabufError::NoPlanes);
+        }
+
+        let params = dmabuf.create_params(&self.queue_handle, ());
+        for (i, plane) in buffer.planes.iter().enumerate() {
+            // the fd is duplicated when the request is sent, so it stays owned by the caller
+            params.add(
+                plane.fd,
+                i as u32,
+                plane.offset,
+                plane.stride,
+                (plane.modifier >> 32) as u32,
+                plane.modifier as u32,
+            );
+        }
+        let wl_buffer = params.create_immed(
+            buffer.width,
+            buffer.height,
+            buffer.format,
+            zwp_linux_buffer_params_v1::Flags::empty(),
+            &self.queue_handle,
+            DmabufBufferData {
+                id: buffer.id,
+                surface: wl_surface.clone(),
+                surface_id: id,
+                root,
+            },
+        );
+        params.destroy();
+
+        wl_surface.attach(Some(&wl_buffer), 0, 0);
+        wl_surface.damage_buffer(0, 0, buffer.width, buffer.height);
+        Ok(wl_surface)
+    }
+
+    /// Creates a layer surface from `settings`, which is used for every layer surface,
+    /// including the initial one and the ones recreated after reconnecting.
     pub fn get_layer_surface(
         &mut self,
-        SctkLayerSurfaceSettings {
+        settings: SctkLayerSurfaceSettings,
+    ) -> Result<(iced_native::window::Id, WlSurface), LayerSurfaceCreationError> {
+        let descriptor = settings.clone();
+        let SctkLayerSurfaceSettings {
             id,
             layer,
             keyboard_interactivity,
@@ -431,37 +2873,262 @@ where
             margin,
             size,
             exclusive_zone,
-        }: SctkLayerSurfaceSettings,
-    ) -> Result<(iced_native::window::Id, WlSurface), LayerSurfaceCreationError> {
+        } = settings;
+        if let Some(dimension) = unsized_dimension(anchor, size) {
+            return Err(LayerSurfaceCreationError::Unsized(dimension));
+        }
         let layer_shell = self
             .layer_shell
             .as_ref()
             .ok_or(LayerSurfaceCreationError::LayerShellNotSupported)?;
         let wl_surface = self.compositor_state.create_surface(&self.queue_handle);
+        // the compositor picks the output, unless the output is known, which it isn't anymore
+        // after reconnecting
+        let output = match output {
+            IcedOutput::Output(output) if self.outputs.contains(&output) => Some(output),
+            IcedOutput::All | IcedOutput::Active | IcedOutput::Output(_) => None,
+        };
 
-        let layer_surface = LayerSurface::builder()
+        let mut builder = LayerSurface::builder()
             .anchor(anchor)
             .keyboard_interactivity(keyboard_interactivity)
             .margin(margin.top, margin.right, margin.bottom, margin.left)
             .size((size.0.unwrap_or_default(), size.1.unwrap_or_default()))
             .namespace(namespace)
-            .exclusive_zone(exclusive_zone)
+            .exclusive_zone(exclusive_zone);
+        if let Some(output) = output.as_ref() {
+            builder = builder.output(output);
+        }
+        let layer_surface = builder
             .map(&self.queue_handle, layer_shell, wl_surface.clone(), layer)
             .map_err(|g_err| LayerSurfaceCreationError::LayerSurfaceCreationFailed(g_err))?;
         self.layer_surfaces.push(SctkLayerSurface {
             id,
             surface: layer_surface,
-            requested_size: (None, None),
+            requested_size: size,
             current_size: None,
             layer,
             // builder needs to be refactored such that these fields are accessible
             anchor,
             keyboard_interactivity,
+            requested_interactivity: keyboard_interactivity,
+            keyboard_holds: Vec::new(),
             margin,
             exclusive_zone,
+            output,
             last_configure: None,
+            configure_serial: None,
+            raised: false,
+            raise_timer: None,
+            settings: descriptor,
             pending_requests: Vec::new(),
         });
         Ok((id, wl_surface))
     }
 }
+
+/// Returns the dimension of a layer surface which is left to the compositor, i.e. unset or
+/// `0`, without anchoring the surface to both edges along it, which is a protocol error.
+///
+/// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:request:set_size>
+fn unsized_dimension(anchor: Anchor, size: (Option<u32>, Option<u32>)) -> Option<&'static str> {
+    if size.0.unwrap_or_default() == 0 && !anchor.contains(Anchor::LEFT | Anchor::RIGHT) {
+        Some("width")
+    } else if size.1.unwrap_or_default() == 0 && !anchor.contains(Anchor::TOP | Anchor::BOTTOM) {
+        Some("height")
+    } else {
+        None
+    }
+}
+
+/// Returns the keyboard interactivity of a layer surface, the most interactive of the one
+/// requested by the application and the ones of its holds.
+pub(crate) fn effective_interactivity(
+    requested: KeyboardInteractivity,
+    holds: &[KeyboardHold],
+) -> KeyboardInteractivity {
+    let rank = |interactivity: &KeyboardInteractivity| match interactivity {
+        KeyboardInteractivity::Exclusive => 2,
+        KeyboardInteractivity::OnDemand => 1,
+        _ => 0,
+    };
+    holds
+        .iter()
+        .map(|h| h.interactivity())
+        .chain(Some(requested))
+        .max_by_key(rank)
+        .unwrap_or(requested)
+}
+
+/// Clamps the size of a popup to its minimum and maximum size.
+fn clamp_popup_size(limits: &Limits, size: (u32, u32)) -> (u32, u32) {
+    let (min, max) = (limits.min(), limits.max());
+    (
+        (size.0 as f32).max(min.width).min(max.width) as u32,
+        (size.1 as f32).max(min.height).min(max.height) as u32,
+    )
+}
+
+/// The minimum time between two redraws for the given frame rate cap.
+pub(crate) fn frame_interval(fps: Option<u32>) -> Option<Duration> {
+    fps.filter(|fps| *fps > 0).map(|fps| Duration::from_secs(1) / fps)
+}
+
+/// Converts RGBA `pixels` into the little endian and premultiplied Argb8888 of `canvas`.
+fn rgba_to_argb8888(pixels: &[u8], canvas: &mut [u8]) {
+    for (dst, src) in canvas.chunks_exact_mut(4).zip(pixels.chunks_exact(4)) {
+        let premultiply = |c: u8| (c as u16 * src[3] as u16 / 255) as u8;
+        dst[0] = premultiply(src[2]);
+        dst[1] = premultiply(src[1]);
+        dst[2] = premultiply(src[0]);
+        dst[3] = src[3];
+    }
+}
+
+/// The layer above the given one, `None` for the overlay layer.
+fn layer_above(layer: Layer) -> Option<Layer> {
+    match layer {
+        Layer::Background => Some(Layer::Bottom),
+        Layer::Bottom => Some(Layer::Top),
+        Layer::Top => Some(Layer::Overlay),
+        Layer::Overlay => None,
+    }
+}
+
+/// Guesses the name of the compositor from the interface names of its globals, by the
+/// private protocols of the known compositors. Returns `None` if none of them matches.
+pub(crate) fn compositor_name<'a>(
+    mut interfaces: impl Iterator<Item = &'a str>,
+) -> Option<&'static str> {
+    const PREFIXES: [(&str, &str); 9] = [
+        ("zcosmic_", "cosmic-comp"),
+        ("cosmic_", "cosmic-comp"),
+        ("org_kde_kwin_", "KWin"),
+        ("org_kde_plasma_", "KWin"),
+        ("gtk_shell", "Mutter"),
+        ("hyprland_", "Hyprland"),
+        ("zriver_", "river"),
+        ("weston_", "Weston"),
+        ("treeland_", "treeland"),
+    ];
+    interfaces.find_map(|interface| {
+        PREFIXES
+            .iter()
+            .find(|(prefix, _)| interface.starts_with(prefix))
+            .map(|(_, name)| *name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced_native::Size;
+
+    #[test]
+    fn frame_interval_of_the_frame_rate_cap() {
+        assert_eq!(frame_interval(Some(50)), Some(Duration::from_millis(20)));
+        assert_eq!(frame_interval(Some(0)), None);
+        assert_eq!(frame_interval(None), None);
+    }
+
+    #[test]
+    fn compositor_name_from_private_globals() {
+        let globals = ["wl_compositor", "xdg_wm_base", "zcosmic_toplevel_info_v1"];
+        assert_eq!(compositor_name(globals.into_iter()), Some("cosmic-comp"));
+        let globals = ["wl_compositor", "org_kde_plasma_shell"];
+        assert_eq!(compositor_name(globals.into_iter()), Some("KWin"));
+        assert_eq!(
+            compositor_name(["wl_compositor", "wl_shm"].into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn rgba_is_premultiplied_into_argb8888() {
+        let pixels = [255, 128, 0, 255, 255, 255, 255, 128];
+        let mut canvas = [0; 8];
+        rgba_to_argb8888(&pixels, &mut canvas);
+        // little endian, so the bytes are blue, green, red and alpha
+        assert_eq!(canvas, [0, 128, 255, 255, 128, 128, 128, 128]);
+    }
+
+    #[test]
+    fn most_interactive_of_the_request_and_the_holds() {
+        use KeyboardInteractivity::{Exclusive, OnDemand};
+
+        assert_eq!(
+            effective_interactivity(KeyboardInteractivity::None, &[]),
+            KeyboardInteractivity::None
+        );
+        let holds = [KeyboardHold::Focused];
+        assert_eq!(
+            effective_interactivity(KeyboardInteractivity::None, &holds),
+            OnDemand
+        );
+        let holds = [KeyboardHold::Focused, KeyboardHold::Grab];
+        assert_eq!(effective_interactivity(OnDemand, &holds), Exclusive);
+        assert_eq!(
+            effective_interactivity(Exclusive, &[KeyboardHold::Focused]),
+            Exclusive
+        );
+    }
+
+    #[test]
+    fn popup_size_is_clamped_to_its_limits() {
+        let limits = Limits::new(Size::new(50.0, 20.0), Size::new(300.0, 200.0));
+        assert_eq!(clamp_popup_size(&limits, (10, 10)), (50, 20));
+        assert_eq!(clamp_popup_size(&limits, (100, 100)), (100, 100));
+        assert_eq!(clamp_popup_size(&limits, (400, 500)), (300, 200));
+    }
+
+    #[test]
+    fn unsized_dimension_requires_both_edges() {
+        assert_eq!(
+            unsized_dimension(Anchor::empty(), (Some(10), Some(10))),
+            None
+        );
+        assert_eq!(
+            unsized_dimension(Anchor::LEFT, (None, Some(10))),
+            Some("width")
+        );
+        assert_eq!(
+            unsized_dimension(Anchor::LEFT | Anchor::RIGHT, (Some(0), Some(10))),
+            None
+        );
+        assert_eq!(
+            unsized_dimension(Anchor::LEFT | Anchor::RIGHT, (None, None)),
+            Some("height")
+        );
+        assert_eq!(unsized_dimension(Anchor::all(), (None, None)), None);
+    }
+
+    #[test]
+    fn shm_buffers_cycle_between_two_slots() {
+        let mut slots = ShmSlots::default();
+        let mut created = 0;
+        let mut acquire = |slots: &mut ShmSlots<u32>| {
+            let slot = slots.free()?;
+            let buffer = *slots
+                .get_or_try_insert_with(slot, || {
+                    created += 1;
+                    Ok::<_, ()>(created)
+                })
+                .unwrap();
+            slots.acquire(slot);
+            Some((slot, buffer))
+        };
+
+        assert_eq!(acquire(&mut slots), Some((0, 1)));
+        assert_eq!(acquire(&mut slots), Some((1, 2)));
+        // the compositor still reads both buffers
+        assert_eq!(acquire(&mut slots), None);
+
+        slots.release(1);
+        assert_eq!(acquire(&mut slots), Some((1, 2)));
+        slots.release(0);
+        slots.release(2);
+        assert_eq!(acquire(&mut slots), Some((0, 1)));
+
+        assert_eq!(slots.into_buffers().collect::<Vec<_>>(), [1, 2]);
+    }
+}
@@ -0,0 +1,41 @@
+use sctk::reexports::client::{protocol::wl_surface::WlSurface, Connection, Dispatch, QueueHandle};
+use std::fmt::Debug;
+use wayland_protocols::xdg::activation::v1::client::{
+    xdg_activation_token_v1::{self, XdgActivationTokenV1},
+    xdg_activation_v1::{self, XdgActivationV1},
+};
+
+use crate::event_loop::state::SctkState;
+
+impl<T: 'static + Debug> Dispatch<XdgActivationV1, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &XdgActivationV1,
+        _event: xdg_activation_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // xdg_activation_v1 has no events
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<XdgActivationTokenV1, WlSurface> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        token: &XdgActivationTokenV1,
+        event: xdg_activation_token_v1::Event,
+        wl_surface: &WlSurface,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let xdg_activation_token_v1::Event::Done { token: activation_token } = event {
+            // the token was requested without a serial, so instead of focusing the
+            // surface, the compositor marks it as demanding attention
+            if let Some(activation) = state.activation.as_ref() {
+                activation.activate(activation_token, wl_surface);
+            }
+            token.destroy();
+        }
+    }
+}
@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: MPL-2.0-only
 use sctk::{
-    compositor::CompositorHandler,
-    delegate_compositor,
-    reexports::client::{protocol::wl_surface, Connection, Proxy, QueueHandle},
+    compositor::{CompositorHandler, CompositorState, SurfaceData},
+    globals::GlobalData,
+    reexports::client::{
+        delegate_dispatch,
+        protocol::{wl_callback::WlCallback, wl_compositor::WlCompositor, wl_surface},
+        Connection, Dispatch, Proxy, QueueHandle,
+    },
 };
 use std::fmt::Debug;
 
@@ -43,6 +47,9 @@ impl<T: Debug> CompositorHandler for SctkState<T> {
                 e.scale_factor = Some(new_factor)
             }
         }
+
+        // the largest scale of the outputs the surface entered
+        self.set_surface_scale(surface, new_factor);
     }
 
     fn frame(
@@ -56,4 +63,29 @@ impl<T: Debug> CompositorHandler for SctkState<T> {
     }
 }
 
-delegate_compositor!(@<T: 'static + Debug> SctkState<T>);
+// the events of the surfaces are handled by sctk, which has no handler for the outputs they
+// enter or leave, so they are intercepted before the rest is delegated as usual
+impl<T: 'static + Debug> Dispatch<wl_surface::WlSurface, SurfaceData> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        surface: &wl_surface::WlSurface,
+        event: wl_surface::Event,
+        data: &SurfaceData,
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let outputs_changed = matches!(
+            event,
+            wl_surface::Event::Enter { .. } | wl_surface::Event::Leave { .. }
+        );
+        <CompositorState as Dispatch<wl_surface::WlSurface, SurfaceData, Self>>::event(
+            state, surface, event, data, conn, qh,
+        );
+        if outputs_changed {
+            state.surface_outputs_changed(surface);
+        }
+    }
+}
+
+delegate_dispatch!(@<T: 'static + Debug> SctkState<T>: [WlCompositor: GlobalData] => CompositorState);
+delegate_dispatch!(@<T: 'static + Debug> SctkState<T>: [WlCallback: wl_surface::WlSurface] => CompositorState);
@@ -0,0 +1,148 @@
+use sctk::reexports::client::{
+    event_created_child,
+    protocol::{
+        wl_data_device::{self, WlDataDevice},
+        wl_data_device_manager::{self, DndAction, WlDataDeviceManager},
+        wl_data_offer::WlDataOffer,
+        wl_seat::WlSeat,
+        wl_surface::WlSurface,
+    },
+    Connection, Dispatch, Proxy, QueueHandle,
+};
+use std::fmt::Debug;
+
+use super::data_offer::DataOfferData;
+use crate::{
+    event_loop::state::SctkState,
+    sctk_event::{DndOfferEventVariant, SctkEvent},
+};
+
+/// The offer of a drag which entered one of the surfaces of the application.
+#[derive(Debug)]
+pub(crate) struct DndOffer {
+    /// `None` if the drag started by the application itself has no data
+    pub(crate) offer: Option<WlDataOffer>,
+    pub(crate) surface: WlSurface,
+    /// the serial of the enter event, to accept a mime type with
+    pub(crate) serial: u32,
+    /// whether the drag was dropped, after which the offer is kept until it was read
+    pub(crate) dropped: bool,
+}
+
+impl DndOffer {
+    /// Destroys the offer, and tells the source the drop was finished if it was dropped.
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_offer:request:finish>
+    pub(crate) fn destroy(self) {
+        if let Some(offer) = self.offer {
+            if self.dropped && offer.version() >= 3 {
+                offer.finish();
+            }
+            offer.destroy();
+        }
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<WlDataDeviceManager, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlDataDeviceManager,
+        _event: wl_data_device_manager::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // wl_data_device_manager has no events
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<WlDataDevice, WlSeat> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        _data_device: &WlDataDevice,
+        event: wl_data_device::Event,
+        seat: &WlSeat,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            // the offer is introduced before it is used as the selection, its mime types
+            // are tracked by its data
+            wl_data_device::Event::DataOffer { .. } => {}
+            wl_data_device::Event::Selection { id } => {
+                if let Some(my_seat) = state.seats.iter_mut().find(|s| &s.seat == seat) {
+                    if let Some(previous) = std::mem::replace(&mut my_seat.selection, id) {
+                        previous.destroy();
+                    }
+                    state.selection_changed(seat, false);
+                } else if let Some(offer) = id {
+                    offer.destroy();
+                }
+            }
+            wl_data_device::Event::Enter {
+                serial,
+                surface,
+                x,
+                y,
+                id,
+            } => {
+                let data = id.as_ref().and_then(|offer| offer.data::<DataOfferData>());
+                let variant = DndOfferEventVariant::Enter {
+                    x,
+                    y,
+                    mime_types: data.map(|d| d.mime_types()).unwrap_or_default(),
+                    source_actions: data
+                        .map(|d| d.source_actions())
+                        .unwrap_or(DndAction::empty()),
+                };
+                let previous = state.dnd_offer.replace(DndOffer {
+                    offer: id,
+                    surface: surface.clone(),
+                    serial,
+                    dropped: false,
+                });
+                // a drop which was not read by now is abandoned
+                if let Some(previous) = previous {
+                    previous.destroy();
+                }
+                state.sctk_events.push(SctkEvent::DndOffer { variant, surface });
+            }
+            wl_data_device::Event::Motion { x, y, .. } => {
+                if let Some(dnd_offer) = state.dnd_offer.as_ref().filter(|o| !o.dropped) {
+                    state.sctk_events.push(SctkEvent::DndOffer {
+                        variant: DndOfferEventVariant::Motion { x, y },
+                        surface: dnd_offer.surface.clone(),
+                    });
+                }
+            }
+            wl_data_device::Event::Leave => {
+                if let Some(dnd_offer) = state.dnd_offer.take() {
+                    let surface = dnd_offer.surface.clone();
+                    if dnd_offer.dropped {
+                        state.dnd_offer = Some(dnd_offer);
+                    } else {
+                        dnd_offer.destroy();
+                        state.sctk_events.push(SctkEvent::DndOffer {
+                            variant: DndOfferEventVariant::Leave,
+                            surface,
+                        });
+                    }
+                }
+            }
+            wl_data_device::Event::Drop => {
+                if let Some(dnd_offer) = state.dnd_offer.as_mut() {
+                    dnd_offer.dropped = true;
+                    state.sctk_events.push(SctkEvent::DndOffer {
+                        variant: DndOfferEventVariant::Drop,
+                        surface: dnd_offer.surface.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(SctkState<T>, WlDataDevice, [
+        wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, DataOfferData::default())
+    ]);
+}
@@ -0,0 +1,49 @@
+use sctk::reexports::client::{
+    protocol::{
+        wl_data_device_manager::DndAction,
+        wl_data_offer::{self, WlDataOffer},
+    },
+    Connection, Dispatch, QueueHandle,
+};
+use std::{fmt::Debug, sync::Mutex};
+
+use crate::event_loop::state::SctkState;
+
+/// The mime types of a data offer, and the actions of the source of a drag and drop offer,
+/// which are announced right after it was created.
+#[derive(Debug, Default)]
+pub(crate) struct DataOfferData {
+    pub(crate) mime_types: Mutex<Vec<String>>,
+    pub(crate) source_actions: Mutex<Option<DndAction>>,
+}
+
+impl DataOfferData {
+    pub(crate) fn mime_types(&self) -> Vec<String> {
+        self.mime_types.lock().unwrap().clone()
+    }
+
+    pub(crate) fn source_actions(&self) -> DndAction {
+        self.source_actions.lock().unwrap().unwrap_or(DndAction::empty())
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<WlDataOffer, DataOfferData> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _offer: &WlDataOffer,
+        event: wl_data_offer::Event,
+        data: &DataOfferData,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_data_offer::Event::Offer { mime_type } => {
+                data.mime_types.lock().unwrap().push(mime_type);
+            }
+            wl_data_offer::Event::SourceActions { source_actions } => {
+                *data.source_actions.lock().unwrap() = source_actions.into_result().ok();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use sctk::reexports::client::{
+    protocol::{
+        wl_data_device_manager::DndAction,
+        wl_data_source::{self, WlDataSource},
+        wl_surface::WlSurface,
+    },
+    Connection, Dispatch, QueueHandle,
+};
+use std::{fmt::Debug, io::Write, sync::Mutex};
+
+use crate::{
+    event_loop::state::SctkState,
+    sctk_event::{DndSourceEventVariant, SctkEvent},
+};
+
+/// The contents of a drag started by the application, for each of its mime types.
+#[derive(Debug, Default)]
+pub(crate) struct DataSourceData {
+    pub(crate) contents: Vec<(String, Vec<u8>)>,
+    /// the action picked by the compositor
+    pub(crate) action: Mutex<Option<DndAction>>,
+}
+
+/// A drag started by the application, with the surface of its icon.
+#[derive(Debug)]
+pub(crate) struct DragSource {
+    pub(crate) source: WlDataSource,
+    pub(crate) icon: Option<WlSurface>,
+}
+
+impl<T: 'static + Debug> Dispatch<WlDataSource, DataSourceData> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        source: &WlDataSource,
+        event: wl_data_source::Event,
+        data: &DataSourceData,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_data_source::Event::Send { mime_type, fd } => {
+                let contents = match data.contents.iter().find(|(m, _)| m == &mime_type) {
+                    Some((_, contents)) => contents.clone(),
+                    None => return,
+                };
+                // the target may read slowly, which must not block the event loop
+                let mut file = std::fs::File::from(fd);
+                std::thread::spawn(move || {
+                    if let Err(err) = file.write_all(&contents) {
+                        log::warn!("Failed to send the {} contents of the drag: {}", mime_type, err);
+                    }
+                });
+            }
+            wl_data_source::Event::Action { dnd_action } => {
+                *data.action.lock().unwrap() = dnd_action.into_result().ok();
+            }
+            wl_data_source::Event::Cancelled => {
+                state.end_drag(source);
+                state.sctk_events.push(SctkEvent::DndSource(DndSourceEventVariant::Cancelled));
+            }
+            wl_data_source::Event::DndFinished => {
+                state.end_drag(source);
+                let action = data.action.lock().unwrap().unwrap_or(DndAction::Copy);
+                state
+                    .sctk_events
+                    .push(SctkEvent::DndSource(DndSourceEventVariant::Finished(action)));
+            }
+            _ => {}
+        }
+    }
+}
@@ -1 +1,5 @@
-// TODO after merge
+// TODO use the data device of sctk after merge
+pub mod data_device;
+pub mod data_offer;
+pub mod data_source;
+pub mod primary_selection;
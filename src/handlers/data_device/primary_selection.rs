@@ -0,0 +1,72 @@
+use sctk::reexports::client::{
+    event_created_child, protocol::wl_seat::WlSeat, Connection, Dispatch, QueueHandle,
+};
+use std::fmt::Debug;
+use wayland_protocols::wp::primary_selection::zv1::client::{
+    zwp_primary_selection_device_manager_v1::{self, ZwpPrimarySelectionDeviceManagerV1},
+    zwp_primary_selection_device_v1::{self, ZwpPrimarySelectionDeviceV1},
+    zwp_primary_selection_offer_v1::{self, ZwpPrimarySelectionOfferV1},
+};
+
+use super::data_offer::DataOfferData;
+use crate::event_loop::state::SctkState;
+
+impl<T: 'static + Debug> Dispatch<ZwpPrimarySelectionDeviceManagerV1, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpPrimarySelectionDeviceManagerV1,
+        _event: zwp_primary_selection_device_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // zwp_primary_selection_device_manager_v1 has no events
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<ZwpPrimarySelectionDeviceV1, WlSeat> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        _device: &ZwpPrimarySelectionDeviceV1,
+        event: zwp_primary_selection_device_v1::Event,
+        seat: &WlSeat,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            // like for the data device, the mime types of the offer are tracked by its data
+            zwp_primary_selection_device_v1::Event::DataOffer { .. } => {}
+            zwp_primary_selection_device_v1::Event::Selection { id } => {
+                if let Some(my_seat) = state.seats.iter_mut().find(|s| &s.seat == seat) {
+                    if let Some(previous) = std::mem::replace(&mut my_seat.primary_selection, id)
+                    {
+                        previous.destroy();
+                    }
+                    state.selection_changed(seat, true);
+                } else if let Some(offer) = id {
+                    offer.destroy();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(SctkState<T>, ZwpPrimarySelectionDeviceV1, [
+        zwp_primary_selection_device_v1::EVT_DATA_OFFER_OPCODE => (ZwpPrimarySelectionOfferV1, DataOfferData::default())
+    ]);
+}
+
+impl<T: 'static + Debug> Dispatch<ZwpPrimarySelectionOfferV1, DataOfferData> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _offer: &ZwpPrimarySelectionOfferV1,
+        event: zwp_primary_selection_offer_v1::Event,
+        data: &DataOfferData,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let zwp_primary_selection_offer_v1::Event::Offer { mime_type } = event {
+            data.mime_types.lock().unwrap().push(mime_type);
+        }
+    }
+}
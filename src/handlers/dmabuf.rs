@@ -0,0 +1,81 @@
+use sctk::reexports::client::{
+    protocol::wl_buffer::{self, WlBuffer},
+    Connection, Dispatch, Proxy, QueueHandle,
+};
+use std::fmt::Debug;
+use wayland_protocols::wp::linux_dmabuf::zv1::client::{
+    zwp_linux_buffer_params_v1::{self, ZwpLinuxBufferParamsV1},
+    zwp_linux_dmabuf_v1::{self, ZwpLinuxDmabufV1},
+};
+
+use crate::{
+    event_loop::state::{DmabufBufferData, SctkState, DRM_FORMAT_MOD_INVALID},
+    sctk_event::SctkEvent,
+};
+
+impl<T: 'static + Debug> Dispatch<ZwpLinuxDmabufV1, ()> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        proxy: &ZwpLinuxDmabufV1,
+        event: zwp_linux_dmabuf_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            // formats without a modifier are only announced by older compositors, from
+            // version 3 on they are sent for compatibility and the modifiers are authoritative
+            zwp_linux_dmabuf_v1::Event::Format { format } if proxy.version() < 3 => {
+                state.dmabuf_formats.push((format, DRM_FORMAT_MOD_INVALID));
+            }
+            zwp_linux_dmabuf_v1::Event::Modifier {
+                format,
+                modifier_hi,
+                modifier_lo,
+            } => {
+                let modifier = (modifier_hi as u64) << 32 | modifier_lo as u64;
+                state.dmabuf_formats.push((format, modifier));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<ZwpLinuxBufferParamsV1, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpLinuxBufferParamsV1,
+        event: zwp_linux_buffer_params_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // buffers are created immediately, so only a failure can be reported
+        if let zwp_linux_buffer_params_v1::Event::Failed = event {
+            log::error!("the compositor failed to import a dmabuf");
+        }
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<WlBuffer, DmabufBufferData> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        buffer: &WlBuffer,
+        event: wl_buffer::Event,
+        data: &DmabufBufferData,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            // the buffer is only used for a single attach, the planes are owned by the
+            // application, which may reuse them now
+            buffer.destroy();
+            state.sctk_events.push(SctkEvent::BufferReleased {
+                id: data.root.clone(),
+                surface: data.surface.clone(),
+                surface_id: data.surface_id,
+                buffer: data.id,
+            });
+        }
+    }
+}
@@ -1,13 +1,20 @@
 // handlers
+pub mod activation;
 pub mod compositor;
 pub mod data_device;
+pub mod dmabuf;
 pub mod output;
 pub mod seat;
 pub mod shell;
+pub mod subcompositor;
 
 use sctk::{
     delegate_registry, delegate_shm,
     output::OutputState,
+    reexports::client::{
+        protocol::wl_buffer::{self, WlBuffer},
+        Connection, Dispatch, QueueHandle,
+    },
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
     seat::SeatState,
@@ -15,7 +22,7 @@ use sctk::{
 };
 use std::fmt::Debug;
 
-use crate::event_loop::state::SctkState;
+use crate::event_loop::state::{SctkState, ShmBufferData};
 
 impl<T: Debug> ShmHandler for SctkState<T> {
     fn shm_state(&mut self) -> &mut ShmState {
@@ -23,6 +30,21 @@ impl<T: Debug> ShmHandler for SctkState<T> {
     }
 }
 
+impl<T: 'static + Debug> Dispatch<WlBuffer, ShmBufferData> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        _buffer: &WlBuffer,
+        event: wl_buffer::Event,
+        data: &ShmBufferData,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            state.shm_buffer_released(&data.surface, data.slot);
+        }
+    }
+}
+
 impl<T: Debug> ProvidesRegistryState for SctkState<T>
 where
     T: 'static,
@@ -1,4 +1,7 @@
-use crate::{event_loop::state::SctkState, sctk_event::SctkEvent};
+use crate::{
+    event_loop::state::SctkState,
+    sctk_event::{LayerSurfaceEventVariant, SctkEvent},
+};
 use sctk::{delegate_output, output::OutputHandler, reexports::client::Proxy};
 use std::fmt::Debug;
 
@@ -32,6 +35,18 @@ impl<T: Debug> OutputHandler for SctkState<T> {
                 info,
             });
         }
+
+        // the scale of the output may have changed while surfaces are on it, which the
+        // compositor does not report with an enter or leave of the surfaces
+        let surfaces: Vec<_> = self
+            .drawn_surfaces()
+            .filter(|s| self.surface_outputs(s).contains(&output))
+            .cloned()
+            .collect();
+        for surface in surfaces {
+            let scale = self.output_scale(&surface);
+            self.set_surface_scale(&surface, scale);
+        }
     }
 
     fn output_destroyed(
@@ -41,7 +56,27 @@ impl<T: Debug> OutputHandler for SctkState<T> {
         output: sctk::reexports::client::protocol::wl_output::WlOutput,
     ) {
         self.sctk_events.push(SctkEvent::RemovedOutput(output.id()));
-        // TODO clean up any layer surfaces on this output?
+        if let Some(i) = self.outputs.iter().position(|o| o == &output) {
+            self.outputs.remove(i);
+        }
+
+        // layer surfaces can't move to another output, so they are closed with it, and the
+        // application is told which ones to recreate elsewhere
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.layer_surfaces)
+            .into_iter()
+            .partition(|l| match l.output.as_ref() {
+                Some(o) => o == &output,
+                // the output picked by the compositor is only known once the surface entered it
+                None => self.surface_outputs(l.surface.wl_surface()).contains(&output),
+            });
+        self.layer_surfaces = kept;
+        for layer in removed {
+            self.destroy_subsurfaces_of(layer.id);
+            self.sctk_events.push(SctkEvent::LayerSurfaceEvent {
+                variant: LayerSurfaceEventVariant::OutputRemoved(output.clone()),
+                id: layer.surface.wl_surface().clone(),
+            });
+        }
     }
 }
 
@@ -0,0 +1,45 @@
+use sctk::reexports::client::{protocol::wl_seat::WlSeat, Connection, Dispatch, QueueHandle};
+use std::fmt::Debug;
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::{self, ExtIdleNotifierV1},
+};
+
+use crate::{
+    event_loop::state::SctkState,
+    sctk_event::{SctkEvent, SeatEventVariant},
+};
+
+impl<T: 'static + Debug> Dispatch<ExtIdleNotifierV1, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ExtIdleNotifierV1,
+        _event: ext_idle_notifier_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // ext_idle_notifier_v1 has no events
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<ExtIdleNotificationV1, WlSeat> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        _proxy: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        seat: &WlSeat,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let variant = match event {
+            ext_idle_notification_v1::Event::Idled => SeatEventVariant::Idle,
+            ext_idle_notification_v1::Event::Resumed => SeatEventVariant::Resumed,
+            _ => return,
+        };
+        state.sctk_events.push(SctkEvent::SeatEvent {
+            variant,
+            id: seat.clone(),
+        });
+    }
+}
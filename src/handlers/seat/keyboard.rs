@@ -1,9 +1,19 @@
 use crate::{
     event_loop::state::SctkState,
-    sctk_event::{KeyboardEventVariant, SctkEvent},
+    sctk_event::{KeyboardEventVariant, SctkEvent, SeatEventVariant},
 };
 
-use sctk::{delegate_keyboard, reexports::client::Proxy, seat::keyboard::KeyboardHandler};
+use sctk::{
+    reexports::client::{
+        protocol::wl_keyboard::{self, WlKeyboard},
+        Connection, Dispatch, Proxy, QueueHandle,
+    },
+    seat::{
+        keyboard::{KeyboardData, KeyboardHandler, Keymap, RepeatInfo},
+        SeatState,
+    },
+    shell::layer::KeyboardInteractivity,
+};
 use std::fmt::Debug;
 
 impl<T: Debug> KeyboardHandler for SctkState<T> {
@@ -17,6 +27,15 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
         _raw: &[u32],
         _keysyms: &[u32],
     ) {
+        // layer surfaces which don't accept keyboard input must never be routed key events
+        if matches!(
+            self.layer_keyboard_interactivity(surface),
+            Some(KeyboardInteractivity::None)
+        ) {
+            return;
+        }
+        self.keyboard_focus_changed(surface, true);
+
         let (i, mut is_active, seat) = {
             let (i, is_active, my_seat) = match self.seats.iter_mut().enumerate().find_map(|(i, s)| {
                 if s.kbd.as_ref() == Some(keyboard) {
@@ -36,7 +55,7 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
 
         // TODO Ashley: thoroughly test this
         // swap the active seat to be the current seat if the current "active" seat is not focused on the application anyway
-        if !is_active && self.seats[0].kbd_focus.is_none() {
+        if !is_active && !self.seat_pinned && self.seats[0].kbd_focus.is_none() {
             is_active = true;
             self.seats.swap(0, i);
         }
@@ -46,6 +65,7 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
                 variant: KeyboardEventVariant::Enter(surface.clone()),
                 kbd_id: keyboard.clone(),
                 seat_id: seat.clone(),
+                surface: Some(surface.clone()),
             })
         }
     }
@@ -74,21 +94,32 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
             my_seat.kbd_focus.take();
             (is_active, seat, kbd)
         };
+        if self.repeat_disabled.as_ref() == Some(&surface.id()) {
+            self.repeat_disabled = None;
+        }
+        self.keyboard_focus_changed(surface, false);
 
         if is_active {
             self.sctk_events.push(SctkEvent::KeyboardEvent {
                 variant: KeyboardEventVariant::Leave(surface.clone()),
                 kbd_id: kbd,
                 seat_id: seat,
+                surface: Some(surface.clone()),
             });
             // if there is another seat with a keyboard focused on a surface make that the new active seat
-            if let Some(i) = self.seats.iter().position(|s| s.kbd_focus.is_some()) {
+            if let Some(i) = self
+                .seats
+                .iter()
+                .position(|s| s.kbd_focus.is_some())
+                .filter(|_| !self.seat_pinned)
+            {
                 self.seats.swap(0, i);
                 let s = &self.seats[0];
                 self.sctk_events.push(SctkEvent::KeyboardEvent {
                     variant: KeyboardEventVariant::Enter(s.kbd_focus.clone().unwrap()),
                     kbd_id: s.kbd.clone().unwrap(),
                     seat_id: s.seat.clone(),
+                    surface: s.kbd_focus.clone(),
                 })
             }
         }
@@ -99,7 +130,7 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
         _conn: &sctk::reexports::client::Connection,
         _qh: &sctk::reexports::client::QueueHandle<Self>,
         keyboard: &sctk::reexports::client::protocol::wl_keyboard::WlKeyboard,
-        _serial: u32,
+        serial: u32,
         event: sctk::seat::keyboard::KeyEvent,
     ) {
         let (is_active, my_seat) = match self.seats.iter_mut().enumerate().find_map(|(i, s)| {
@@ -114,12 +145,16 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
         };
         let seat_id = my_seat.seat.clone();
         let kbd_id = keyboard.clone();
+        let surface = my_seat.kbd_focus.clone();
         my_seat.last_kbd_press.replace(event.clone());
+        my_seat.last_input_serial.replace(serial);
+        let surface = self.keyboard_target(surface);
         if is_active {
             self.sctk_events.push(SctkEvent::KeyboardEvent {
                 variant: KeyboardEventVariant::Press(event),
                 kbd_id,
                 seat_id,
+                surface,
             });
         }
     }
@@ -144,12 +179,15 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
         };
         let seat_id = my_seat.seat.clone();
         let kbd_id = keyboard.clone();
+        let surface = my_seat.kbd_focus.clone();
 
+        let surface = self.keyboard_target(surface);
         if is_active {
             self.sctk_events.push(SctkEvent::KeyboardEvent {
                 variant: KeyboardEventVariant::Release(event),
                 kbd_id,
                 seat_id,
+                surface,
             });
         }
     }
@@ -174,15 +212,137 @@ impl<T: Debug> KeyboardHandler for SctkState<T> {
         };
         let seat_id = my_seat.seat.clone();
         let kbd_id = keyboard.clone();
+        let surface = my_seat.kbd_focus.clone();
 
         if is_active {
             self.sctk_events.push(SctkEvent::KeyboardEvent {
                 variant: KeyboardEventVariant::Modifiers(modifiers),
                 kbd_id,
                 seat_id,
+                surface,
             })
         }
     }
+
+    fn update_repeat_info(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        keyboard: &sctk::reexports::client::protocol::wl_keyboard::WlKeyboard,
+        info: RepeatInfo,
+    ) {
+        if let Some(my_seat) = self.seats.iter_mut().find(|s| s.kbd.as_ref() == Some(keyboard)) {
+            my_seat.repeat_info.replace(info);
+        }
+    }
+
+    fn update_keymap(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        keyboard: &sctk::reexports::client::protocol::wl_keyboard::WlKeyboard,
+        keymap: Keymap<'_>,
+    ) {
+        let (is_active, my_seat) = match self.seats.iter_mut().enumerate().find_map(|(i, s)| {
+            if s.kbd.as_ref() == Some(keyboard) {
+                Some((i, s))
+            } else {
+                None
+            }
+        }) {
+            Some((i, s)) => (i == 0, s),
+            None => return,
+        };
+        my_seat.kbd_layouts = layout_names(&keymap.as_string());
+        update_layout(self, keyboard, is_active);
+    }
+}
+
+// the modifiers of the keyboards are handled by sctk, which doesn't report their group, so
+// they are intercepted to follow the active layout before the rest is delegated as usual
+impl<T: 'static + Debug> Dispatch<WlKeyboard, KeyboardData<Self>> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        keyboard: &WlKeyboard,
+        event: wl_keyboard::Event,
+        data: &KeyboardData<Self>,
+        conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let group = match event {
+            wl_keyboard::Event::Modifiers { group, .. } => Some(group),
+            _ => None,
+        };
+        <SeatState as Dispatch<WlKeyboard, KeyboardData<Self>, Self>>::event(
+            state, keyboard, event, data, conn, qh,
+        );
+        let group = match group {
+            Some(group) => group,
+            None => return,
+        };
+        if let Some(i) = state.seats.iter().position(|s| s.kbd.as_ref() == Some(keyboard)) {
+            state.seats[i].kbd_group = group;
+            update_layout(state, keyboard, i == 0);
+        }
+    }
+}
+
+/// Updates the active layout of the keyboard from its keymap and group, and reports it if it
+/// changed and the keyboard belongs to the active seat.
+fn update_layout<T: Debug>(state: &mut SctkState<T>, keyboard: &WlKeyboard, is_active: bool) {
+    let my_seat = match state.seats.iter_mut().find(|s| s.kbd.as_ref() == Some(keyboard)) {
+        Some(s) => s,
+        None => return,
+    };
+    let layout = my_seat.kbd_layouts.get(my_seat.kbd_group as usize).cloned();
+    if my_seat.kbd_layout == layout {
+        return;
+    }
+    my_seat.kbd_layout = layout.clone();
+    let id = my_seat.seat.clone();
+
+    if is_active {
+        state.sctk_events.push(SctkEvent::SeatEvent {
+            variant: SeatEventVariant::KeyboardLayout(layout),
+            id,
+        });
+    }
+}
+
+/// Returns the names of the layouts of an xkb keymap, indexed by their group. The names are
+/// declared in its symbols, e.g. `name[Group1]="English (US)";`.
+fn layout_names(keymap: &str) -> Vec<String> {
+    let mut names: Vec<(usize, String)> = keymap
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            let group = key
+                .trim()
+                .to_ascii_lowercase()
+                .strip_prefix("name[group")?
+                .strip_suffix(']')?
+                .parse::<usize>()
+                .ok()?;
+            let name = value.trim().trim_end_matches(';').trim_matches('"');
+            Some((group, name.to_string()))
+        })
+        .collect();
+    names.sort_by_key(|(group, _)| *group);
+    names.dedup_by_key(|(group, _)| *group);
+    names.into_iter().map(|(_, name)| name).collect()
 }
 
-delegate_keyboard!(@<T: 'static + Debug> SctkState<T>);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_names_are_ordered_by_group() {
+        let keymap = r#"xkb_symbols "pc+us+de:2" {
+            name[Group2]="German";
+            name[group1]="English (US)";
+        };"#;
+        assert_eq!(layout_names(keymap), vec!["English (US)", "German"]);
+        assert!(layout_names("xkb_keymap {};").is_empty());
+    }
+}
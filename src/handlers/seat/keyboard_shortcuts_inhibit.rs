@@ -0,0 +1,43 @@
+use sctk::reexports::client::{Connection, Dispatch, QueueHandle};
+use std::fmt::Debug;
+use wayland_protocols::wp::keyboard_shortcuts_inhibit::zv1::client::{
+    zwp_keyboard_shortcuts_inhibit_manager_v1::{self, ZwpKeyboardShortcutsInhibitManagerV1},
+    zwp_keyboard_shortcuts_inhibitor_v1::{self, ZwpKeyboardShortcutsInhibitorV1},
+};
+
+use crate::event_loop::state::SctkState;
+
+impl<T: 'static + Debug> Dispatch<ZwpKeyboardShortcutsInhibitManagerV1, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpKeyboardShortcutsInhibitManagerV1,
+        _event: zwp_keyboard_shortcuts_inhibit_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // zwp_keyboard_shortcuts_inhibit_manager_v1 has no events
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<ZwpKeyboardShortcutsInhibitorV1, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpKeyboardShortcutsInhibitorV1,
+        event: zwp_keyboard_shortcuts_inhibitor_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // the compositor may let the user restore the shortcuts at any time
+        match event {
+            zwp_keyboard_shortcuts_inhibitor_v1::Event::Active => {
+                log::debug!("keyboard shortcuts are inhibited");
+            }
+            zwp_keyboard_shortcuts_inhibitor_v1::Event::Inactive => {
+                log::debug!("keyboard shortcuts are no longer inhibited");
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,5 +1,7 @@
 // TODO support multi-seat handling
+pub mod idle_notify;
 pub mod keyboard;
+pub mod keyboard_shortcuts_inhibit;
 pub mod pointer;
 pub mod seat;
 pub mod touch;
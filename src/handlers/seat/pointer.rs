@@ -1,8 +1,12 @@
-use crate::{event_loop::state::SctkState, sctk_event::SctkEvent};
+use crate::{
+    event_loop::state::{PointerClick, PointerDrag, SctkState},
+    sctk_event::SctkEvent,
+};
 use sctk::{
     delegate_pointer,
     reexports::client::Proxy,
     seat::pointer::{PointerEventKind, PointerHandler},
+    shell::layer::KeyboardInteractivity,
 };
 use std::fmt::Debug;
 
@@ -14,32 +18,80 @@ impl<T: Debug> PointerHandler for SctkState<T> {
         pointer: &sctk::reexports::client::protocol::wl_pointer::WlPointer,
         events: &[sctk::seat::pointer::PointerEvent],
     ) {
-        let (is_active, my_seat) = match self.seats.iter_mut().enumerate().find_map(|(i, s)| {
+        let scroll_factor = self.scroll_factor();
+        let (i, is_active, my_seat) = match self.seats.iter_mut().enumerate().find_map(|(i, s)| {
             if s.ptr.as_ref() == Some(pointer) {
                 Some((i, s))
             } else {
                 None
             }
         }) {
-            Some((i, s)) => (i == 0, s),
+            Some((i, s)) => (i, i == 0, s),
             None => return,
         };
+        let mut pressed_surface = None;
+        let mut entered = None;
 
         // track events, but only forward for the active seat
         for e in events {
+            let click_count = match e.kind {
+                PointerEventKind::Press { time, button, .. } => {
+                    let click = PointerClick::next(
+                        my_seat.last_click.as_ref(),
+                        e.surface.id(),
+                        time,
+                        button,
+                        e.position,
+                    );
+                    let count = click.count();
+                    my_seat.last_click.replace(click);
+                    Some(count)
+                }
+                _ => None,
+            };
+            // a press only starts a drag once the pointer moved far enough from it
+            let drag_button = match e.kind {
+                PointerEventKind::Press { button, .. } if my_seat.ptr_drag.is_none() => {
+                    my_seat.ptr_drag.replace(PointerDrag::new(button, e.position));
+                    None
+                }
+                PointerEventKind::Release { button, .. }
+                    if my_seat.ptr_drag.as_ref().map(|d| d.button()) == Some(button) =>
+                {
+                    my_seat.ptr_drag.take();
+                    None
+                }
+                PointerEventKind::Motion { .. } => {
+                    my_seat.ptr_drag.as_mut().and_then(|d| d.motion(e.position))
+                }
+                _ => None,
+            };
             if is_active {
                 self.sctk_events.push(SctkEvent::PointerEvent {
                     variant: e.clone(),
                     ptr_id: pointer.clone(),
                     seat_id: my_seat.seat.clone(),
+                    click_count,
+                    drag_button,
+                    scroll_factor,
                 });
             }
+            if !matches!(e.kind, PointerEventKind::Leave { .. }) {
+                my_seat.ptr_position = e.position;
+            }
             match e.kind {
-                PointerEventKind::Enter { .. } => {
+                PointerEventKind::Enter { serial } => {
                     my_seat.ptr_focus.replace(e.surface.clone());
+                    my_seat.last_ptr_enter.replace(serial);
+                    entered = Some(serial);
                 }
-                PointerEventKind::Leave { .. } => {
+                // the pointer may already have entered another surface, e.g. a popup
+                // which was mapped over the surface it leaves
+                PointerEventKind::Leave { .. } if my_seat.ptr_focus.as_ref() == Some(&e.surface) => {
+                    my_seat.ptr_drag.take();
                     my_seat.ptr_focus.take();
+                    my_seat.last_ptr_enter.take();
+                    my_seat.last_ptr_press.take();
                 }
                 PointerEventKind::Press {
                     time,
@@ -47,11 +99,36 @@ impl<T: Debug> PointerHandler for SctkState<T> {
                     serial,
                 } => {
                     my_seat.last_ptr_press.replace((time, button, serial));
+                    my_seat.last_input_serial.replace(serial);
+                    pressed_surface.replace(e.surface.clone());
+                }
+                // compositors reject the serial of a press once the button is released
+                PointerEventKind::Release { button, .. }
+                    if my_seat.last_ptr_press.map(|(_, b, _)| b) == Some(button) =>
+                {
+                    my_seat.last_ptr_press.take();
                 }
                 // TODO revisit events that ought to be handled and change internal state
                 _ => {}
             }
         }
+
+        if let Some(serial) = entered {
+            self.update_cursor(pointer, serial);
+        }
+
+        // Clicking an on demand layer surface gives it keyboard focus, so the seat that clicked
+        // must become the active seat for the following keyboard enter to be routed to it.
+        if !is_active && !self.seat_pinned {
+            if let Some(surface) = pressed_surface {
+                if matches!(
+                    self.layer_keyboard_interactivity(&surface),
+                    Some(KeyboardInteractivity::OnDemand)
+                ) {
+                    self.seats.swap(0, i);
+                }
+            }
+        }
     }
 }
 
@@ -3,8 +3,18 @@ use crate::{
     sctk_event::{KeyboardEventVariant, SctkEvent, SeatEventVariant},
 };
 use iced_native::keyboard::Modifiers;
-use sctk::{delegate_seat, reexports::client::Proxy, seat::SeatHandler};
-use std::fmt::Debug;
+use sctk::{
+    delegate_seat,
+    reexports::client::{
+        protocol::{
+            wl_callback::{self, WlCallback},
+            wl_seat::WlSeat,
+        },
+        Connection, Dispatch, Proxy, QueueHandle,
+    },
+    seat::SeatHandler,
+};
+use std::{collections::HashMap, fmt::Debug};
 
 impl<T: Debug> SeatHandler for SctkState<T>
 where
@@ -16,25 +26,51 @@ where
 
     fn new_seat(
         &mut self,
-        _conn: &sctk::reexports::client::Connection,
-        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        conn: &sctk::reexports::client::Connection,
+        qh: &sctk::reexports::client::QueueHandle<Self>,
         seat: sctk::reexports::client::protocol::wl_seat::WlSeat,
     ) {
+        let name = self.seat_state.info(&seat).and_then(|i| i.name);
+        log::debug!("New seat {:?}: {:?}", seat.id(), name);
+        // `wl_seat.name` is sent right after the seat was bound and has no handler, so it
+        // is read once the compositor answered a sync sent after it
+        if name.is_none() {
+            conn.display().sync(qh, seat.clone());
+        }
         self.sctk_events.push(SctkEvent::SeatEvent {
-            variant: SeatEventVariant::New,
+            variant: SeatEventVariant::New(name.clone()),
             id: seat.clone(),
         });
+        let idle_notification = self.get_idle_notification(&seat);
+        let data_device = self.get_data_device(&seat);
+        let primary_selection_device = self.get_primary_selection_device(&seat);
         self.seats.push(SctkSeat {
             seat,
+            name,
             kbd: None,
             ptr: None,
             touch: None,
-            data_device: None,
+            touch_points: HashMap::new(),
+            touch_primary: None,
+            data_device,
+            selection: None,
+            primary_selection_device,
+            primary_selection: None,
             modifiers: Modifiers::default(),
             kbd_focus: None,
             ptr_focus: None,
+            ptr_position: (0.0, 0.0),
             last_ptr_press: None,
+            last_ptr_enter: None,
+            last_input_serial: None,
+            last_click: None,
+            ptr_drag: None,
             last_kbd_press: None,
+            repeat_info: None,
+            kbd_layout: None,
+            kbd_layouts: Vec::new(),
+            kbd_group: 0,
+            idle_notification,
         });
     }
 
@@ -45,20 +81,46 @@ where
         seat: sctk::reexports::client::protocol::wl_seat::WlSeat,
         capability: sctk::seat::Capability,
     ) {
+        let (idle_notification, data_device, primary_selection_device) =
+            if self.seats.iter().any(|s| s.seat == seat) {
+                (None, None, None)
+            } else {
+                (
+                    self.get_idle_notification(&seat),
+                    self.get_data_device(&seat),
+                    self.get_primary_selection_device(&seat),
+                )
+            };
         let my_seat = match self.seats.iter_mut().find(|s| s.seat == seat) {
             Some(s) => s,
             None => {
                 self.seats.push(SctkSeat {
                     seat: seat.clone(),
+                    name: self.seat_state.info(&seat).and_then(|i| i.name),
                     kbd: None,
                     ptr: None,
                     touch: None,
-                    data_device: None,
+                    touch_points: HashMap::new(),
+                    touch_primary: None,
+                    data_device,
+                    selection: None,
+                    primary_selection_device,
+                    primary_selection: None,
                     modifiers: Modifiers::default(),
                     kbd_focus: None,
                     ptr_focus: None,
+                    ptr_position: (0.0, 0.0),
                     last_ptr_press: None,
+                    last_ptr_enter: None,
+                    last_input_serial: None,
+                    last_click: None,
+                    ptr_drag: None,
                     last_kbd_press: None,
+                    repeat_info: None,
+                    kbd_layout: None,
+                    kbd_layouts: Vec::new(),
+                    kbd_group: 0,
+                    idle_notification,
                 });
                 self.seats.last_mut().unwrap()
             }
@@ -66,7 +128,9 @@ where
         // TODO data device
         match capability {
             sctk::seat::Capability::Keyboard => {
-                if let Ok((kbd, source)) = self.seat_state.get_keyboard_with_repeat(qh, &seat, None)
+                let keymap = self.kbd_keymap.clone();
+                if let Ok((kbd, source)) =
+                    self.seat_state.get_keyboard_with_repeat(qh, &seat, keymap)
                 {
                     self.sctk_events.push(SctkEvent::SeatEvent {
                         variant: SeatEventVariant::NewCapability(capability, kbd.id()),
@@ -75,10 +139,23 @@ where
                     let kbd_clone = kbd.clone();
                     self.loop_handle
                         .insert_source(source, move |e, _, state| {
+                            let surface = state
+                                .seats
+                                .iter()
+                                .find(|s| s.seat == seat)
+                                .and_then(|s| s.kbd_focus.clone());
+                            let surface = state.keyboard_target(surface);
+                            // the repeat is disabled while the surface is focused
+                            if surface.is_some()
+                                && surface.as_ref().map(|s| s.id()) == state.repeat_disabled
+                            {
+                                return;
+                            }
                             state.sctk_events.push(SctkEvent::KeyboardEvent {
                                 variant: KeyboardEventVariant::Repeat(e),
                                 kbd_id: kbd_clone.clone(),
                                 seat_id: seat.clone(),
+                                surface,
                             });
                         })
                         .expect("Failed to insert the repeating keyboard into the event loop");
@@ -95,7 +172,13 @@ where
                 }
             }
             sctk::seat::Capability::Touch => {
-                // TODO touch
+                if let Ok(touch) = self.seat_state.get_touch(qh, &seat) {
+                    self.sctk_events.push(SctkEvent::SeatEvent {
+                        variant: SeatEventVariant::NewCapability(capability, touch.id()),
+                        id: seat.clone(),
+                    });
+                    my_seat.touch.replace(touch);
+                }
             }
             _ => unimplemented!(),
         }
@@ -117,6 +200,7 @@ where
         match capability {
             // TODO use repeating kbd?
             sctk::seat::Capability::Keyboard => {
+                my_seat.repeat_info = None;
                 if let Some(kbd) = my_seat.kbd.take() {
                     self.sctk_events.push(SctkEvent::SeatEvent {
                         variant: SeatEventVariant::RemoveCapability(capability, kbd.id()),
@@ -133,8 +217,14 @@ where
                 }
             }
             sctk::seat::Capability::Touch => {
-                // TODO touch
-                // my_seat.touch = self.seat_state.get_touch(qh, &seat).ok();
+                my_seat.touch_points.clear();
+                my_seat.touch_primary = None;
+                if let Some(touch) = my_seat.touch.take() {
+                    self.sctk_events.push(SctkEvent::SeatEvent {
+                        variant: SeatEventVariant::RemoveCapability(capability, touch.id()),
+                        id: seat.clone(),
+                    });
+                }
             }
             _ => unimplemented!(),
         }
@@ -146,12 +236,61 @@ where
         _qh: &sctk::reexports::client::QueueHandle<Self>,
         seat: sctk::reexports::client::protocol::wl_seat::WlSeat,
     ) {
+        let i = self.seats.iter().position(|s| s.seat == seat);
+        let name = i.and_then(|i| self.seats[i].name.clone());
+        log::debug!("Removed seat {:?}: {:?}", seat.id(), name);
         self.sctk_events.push(SctkEvent::SeatEvent {
-            variant: SeatEventVariant::Remove,
+            variant: SeatEventVariant::Remove(name),
             id: seat.clone(),
         });
-        if let Some(i) = self.seats.iter().position(|s| s.seat == seat) {
-            self.seats.remove(i);
+        if let Some(i) = i {
+            let removed = self.seats.remove(i);
+            if i == 0 {
+                self.seat_pinned = false;
+            }
+            if let Some(notification) = removed.idle_notification {
+                notification.destroy();
+            }
+            if let Some(offer) = removed.selection {
+                offer.destroy();
+            }
+            // wl_data_device.release was only added in version 2
+            if let Some(data_device) = removed.data_device.filter(|d| d.version() >= 2) {
+                data_device.release();
+            }
+            if let Some(offer) = removed.primary_selection {
+                offer.destroy();
+            }
+            if let Some(device) = removed.primary_selection_device {
+                device.destroy();
+            }
+        }
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<WlCallback, WlSeat> for SctkState<T> {
+    fn event(
+        state: &mut Self,
+        _callback: &WlCallback,
+        event: wl_callback::Event,
+        seat: &WlSeat,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_callback::Event::Done { .. } = event {
+            let name = match state.seat_state.info(seat).and_then(|i| i.name) {
+                Some(name) => name,
+                None => return,
+            };
+            if let Some(my_seat) = state.seats.iter_mut().find(|s| &s.seat == seat) {
+                if my_seat.name.is_none() {
+                    my_seat.name = Some(name.clone());
+                    state.sctk_events.push(SctkEvent::SeatEvent {
+                        variant: SeatEventVariant::Name(name),
+                        id: seat.clone(),
+                    });
+                }
+            }
         }
     }
 }
@@ -1 +1,149 @@
-// TODO
+use crate::{
+    event_loop::state::SctkState,
+    sctk_event::{SctkEvent, TouchEventVariant},
+};
+use sctk::{
+    delegate_touch,
+    reexports::client::protocol::{wl_surface::WlSurface, wl_touch::WlTouch},
+    seat::touch::TouchHandler,
+};
+use std::fmt::Debug;
+
+impl<T: Debug> TouchHandler for SctkState<T> {
+    fn down(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        touch: &WlTouch,
+        serial: u32,
+        _time: u32,
+        surface: WlSurface,
+        id: i32,
+        position: (f64, f64),
+    ) {
+        let emulate_pointer = self.touch_emulates_pointer;
+        let my_seat = match self.seats.iter_mut().find(|s| s.touch.as_ref() == Some(touch)) {
+            Some(s) => s,
+            None => return,
+        };
+        my_seat.last_input_serial.replace(serial);
+        my_seat.touch_points.insert(id, (surface.clone(), position));
+        // the first touch point drives the pointer until it is lifted
+        let primary = emulate_pointer && *my_seat.touch_primary.get_or_insert(id) == id;
+        self.sctk_events.push(SctkEvent::TouchEvent {
+            variant: TouchEventVariant::Down { id, position },
+            touch_id: touch.clone(),
+            seat_id: my_seat.seat.clone(),
+            surface,
+            primary,
+        });
+    }
+
+    fn up(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        touch: &WlTouch,
+        _serial: u32,
+        _time: u32,
+        id: i32,
+    ) {
+        let emulate_pointer = self.touch_emulates_pointer;
+        let my_seat = match self.seats.iter_mut().find(|s| s.touch.as_ref() == Some(touch)) {
+            Some(s) => s,
+            None => return,
+        };
+        let (surface, position) = match my_seat.touch_points.remove(&id) {
+            Some(p) => p,
+            None => return,
+        };
+        let primary = my_seat.touch_primary == Some(id);
+        if primary {
+            my_seat.touch_primary.take();
+        }
+        self.sctk_events.push(SctkEvent::TouchEvent {
+            variant: TouchEventVariant::Up { id, position },
+            touch_id: touch.clone(),
+            seat_id: my_seat.seat.clone(),
+            surface,
+            primary: emulate_pointer && primary,
+        });
+    }
+
+    fn motion(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        touch: &WlTouch,
+        _time: u32,
+        id: i32,
+        position: (f64, f64),
+    ) {
+        let emulate_pointer = self.touch_emulates_pointer;
+        let my_seat = match self.seats.iter_mut().find(|s| s.touch.as_ref() == Some(touch)) {
+            Some(s) => s,
+            None => return,
+        };
+        let surface = match my_seat.touch_points.get_mut(&id) {
+            Some((surface, p)) => {
+                *p = position;
+                surface.clone()
+            }
+            None => return,
+        };
+        self.sctk_events.push(SctkEvent::TouchEvent {
+            variant: TouchEventVariant::Motion { id, position },
+            touch_id: touch.clone(),
+            seat_id: my_seat.seat.clone(),
+            surface,
+            primary: emulate_pointer && my_seat.touch_primary == Some(id),
+        });
+    }
+
+    fn shape(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        _touch: &WlTouch,
+        _id: i32,
+        _major: f64,
+        _minor: f64,
+    ) {
+    }
+
+    fn orientation(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        _touch: &WlTouch,
+        _id: i32,
+        _orientation: f64,
+    ) {
+    }
+
+    fn cancel(
+        &mut self,
+        _conn: &sctk::reexports::client::Connection,
+        _qh: &sctk::reexports::client::QueueHandle<Self>,
+        touch: &WlTouch,
+    ) {
+        let emulate_pointer = self.touch_emulates_pointer;
+        let my_seat = match self.seats.iter_mut().find(|s| s.touch.as_ref() == Some(touch)) {
+            Some(s) => s,
+            None => return,
+        };
+        // the compositor took over every touch point, e.g. for a gesture
+        let primary = my_seat.touch_primary.take();
+        for (id, (surface, position)) in my_seat.touch_points.drain() {
+            self.sctk_events.push(SctkEvent::TouchEvent {
+                variant: TouchEventVariant::Cancel { id, position },
+                touch_id: touch.clone(),
+                seat_id: my_seat.seat.clone(),
+                surface,
+                primary: emulate_pointer && primary == Some(id),
+            });
+        }
+    }
+}
+
+delegate_touch!(@<T: 'static + Debug> SctkState<T>);
@@ -25,6 +25,7 @@ impl<T: Debug> LayerShellHandler for SctkState<T> {
             Some(w) => self.layer_surfaces.remove(w),
             None => return,
         };
+        self.destroy_subsurfaces_of(layer.id);
 
         self.sctk_events.push(SctkEvent::LayerSurfaceEvent {
             variant: LayerSurfaceEventVariant::Done,
@@ -39,7 +40,7 @@ impl<T: Debug> LayerShellHandler for SctkState<T> {
         _qh: &sctk::reexports::client::QueueHandle<Self>,
         layer: &sctk::shell::layer::LayerSurface,
         mut configure: sctk::shell::layer::LayerSurfaceConfigure,
-        _serial: u32,
+        serial: u32,
     ) {
         let layer = match self
             .layer_surfaces
@@ -50,31 +51,47 @@ impl<T: Debug> LayerShellHandler for SctkState<T> {
             None => return,
         };
         let id = layer.surface.wl_surface().id();
+        // a dimension left to the compositor, e.g. the width of a surface anchored to the
+        // left and right edges, is sent with every configure and follows the output, so
+        // the viewport is updated without the application changing the size
+        let current_size = layer.current_size.map(|s| (s.width, s.height));
         configure.new_size.0 = if configure.new_size.0 > 0 {
             configure.new_size.0
         } else {
-            layer.requested_size.0.unwrap_or(1)
+            layer
+                .requested_size
+                .0
+                .or(current_size.map(|s| s.0))
+                .unwrap_or(1)
         };
         configure.new_size.1 = if configure.new_size.1 > 0 {
             configure.new_size.1
         } else {
-            layer.requested_size.1.unwrap_or(1)
+            layer
+                .requested_size
+                .1
+                .or(current_size.map(|s| s.1))
+                .unwrap_or(1)
         };
         layer
             .current_size
             .replace(LogicalSize::new(configure.new_size.0, configure.new_size.1));
         let first = layer.last_configure.is_none();
         layer.last_configure.replace(configure.clone());
+        let native_id = layer.id;
 
         self.sctk_events.push(SctkEvent::LayerSurfaceEvent {
             variant: LayerSurfaceEventVariant::Configure(
                 configure,
                 layer.surface.wl_surface().clone(),
                 first,
+                serial,
             ),
             id: layer.surface.wl_surface().clone(),
         });
         self.sctk_events.push(SctkEvent::Draw(layer.surface.wl_surface().clone()));
+        self.reposition_subsurfaces(native_id);
+        self.configure_received(native_id, serial);
     }
 }
 
@@ -1,9 +1,13 @@
 use crate::{
     commands::popup,
-    event_loop::state::{self, SctkState, SctkSurface},
+    event_loop::state::{self, SctkState},
     sctk_event::{PopupEventVariant, SctkEvent},
 };
-use sctk::{delegate_xdg_popup, reexports::client::Proxy, shell::xdg::popup::PopupHandler};
+use sctk::{
+    delegate_xdg_popup,
+    reexports::client::Proxy,
+    shell::xdg::popup::{ConfigureKind, PopupHandler},
+};
 use std::fmt::Debug;
 
 impl<T: Debug> PopupHandler for SctkState<T> {
@@ -23,18 +27,30 @@ impl<T: Debug> PopupHandler for SctkState<T> {
             None => return,
         };
         let first = sctk_popup.last_configure.is_none();
+        // the size limits only apply to the size requested with the positioner, the popup must
+        // follow the size the compositor adjusted it to
         sctk_popup.last_configure.replace(configure.clone());
+        let native_id = sctk_popup.id;
+        let serial = configure.serial;
+        let parent_id = sctk_popup.parent.wl_surface().clone();
+
+        if let ConfigureKind::Reposition { token } = configure.kind {
+            self.sctk_events.push(SctkEvent::PopupEvent {
+                variant: PopupEventVariant::RepositionionedPopup { token },
+                id: popup.wl_surface().clone(),
+                toplevel_id: sctk_popup.toplevel.clone(),
+                parent_id: parent_id.clone(),
+            });
+        }
 
         self.sctk_events.push(SctkEvent::PopupEvent {
             variant: PopupEventVariant::Configure(configure, popup.wl_surface().clone(), first),
             id: popup.wl_surface().clone(),
             toplevel_id: sctk_popup.toplevel.clone(),
-            parent_id: match &sctk_popup.parent {
-                SctkSurface::LayerSurface(s) => s.clone(),
-                SctkSurface::Window(s) => s.clone(),
-                SctkSurface::Popup(s) => s.clone(),
-            },
-        })
+            parent_id,
+        });
+        self.reposition_subsurfaces(native_id);
+        self.configure_received(native_id, serial);
     }
 
     fn done(
@@ -69,6 +85,8 @@ impl<T: Debug> PopupHandler for SctkState<T> {
             }
         }
         for popup in to_destroy.into_iter().rev() {
+            self.destroy_subsurfaces_of(popup.id);
+            self.popup_destroyed(popup.id);
             self.sctk_events.push(SctkEvent::PopupEvent {
                 variant: PopupEventVariant::Done,
                 toplevel_id: popup.toplevel.clone(),
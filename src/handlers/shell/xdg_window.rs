@@ -15,17 +15,18 @@ impl<T: Debug> WindowHandler for SctkState<T> {
         _qh: &sctk::reexports::client::QueueHandle<Self>,
         window: &sctk::shell::xdg::window::Window,
     ) {
+        // the window is only destroyed once the application agrees to close it
         let window = match self
             .windows
             .iter()
-            .position(|s| s.window.wl_surface() == window.wl_surface())
+            .find(|s| s.window.wl_surface() == window.wl_surface())
         {
-            Some(w) => self.windows.remove(w),
+            Some(w) => w,
             None => return,
         };
 
         self.sctk_events.push(SctkEvent::WindowEvent {
-            variant: WindowEventVariant::Close,
+            variant: WindowEventVariant::CloseRequested,
             id: window.window.wl_surface().clone(),
         })
         // TODO popup cleanup
@@ -37,7 +38,7 @@ impl<T: Debug> WindowHandler for SctkState<T> {
         _qh: &sctk::reexports::client::QueueHandle<Self>,
         window: &sctk::shell::xdg::window::Window,
         mut configure: sctk::shell::xdg::window::WindowConfigure,
-        _serial: u32,
+        serial: u32,
     ) {
         let window = match self
             .windows
@@ -48,19 +49,66 @@ impl<T: Debug> WindowHandler for SctkState<T> {
             None => return,
         };
 
+        // without a size, the compositor leaves it to the window
+        let requested = configure.new_size.is_none() || configure.new_size == window.requested_size;
         if configure.new_size.is_none() {
             configure.new_size = Some(window.requested_size.unwrap_or((300, 500)));
         };
 
+        // there is no event for restoring a minimized window, the compositor configures it
+        // again instead, so make sure it is drawn as soon as it is visible again
+        let restored = std::mem::take(&mut window.minimized);
         let wl_surface = window.window.wl_surface();
         let id = wl_surface.clone();
         let first = window.last_configure.is_none();
+        let decorations_changed = window
+            .last_configure
+            .as_ref()
+            .map_or(true, |last| last.decoration_mode != configure.decoration_mode);
+        let decoration_mode = configure.decoration_mode;
+        let resize_finished = window
+            .last_configure
+            .as_ref()
+            .map_or(false, |last| last.is_resizing())
+            && !configure.is_resizing();
+        let new_size = configure.new_size.unwrap();
+        let fullscreen = configure.is_fullscreen();
         window.last_configure.replace(configure.clone());
+        let native_id = window.id;
 
         self.sctk_events.push(SctkEvent::WindowEvent {
-            variant: WindowEventVariant::Configure(configure, wl_surface.clone(), first),
+            variant: WindowEventVariant::Configure {
+                configure,
+                surface: wl_surface.clone(),
+                first,
+                serial: Some(serial),
+                requested,
+            },
             id,
-        })
+        });
+        if decorations_changed {
+            self.sctk_events.push(SctkEvent::WindowEvent {
+                variant: WindowEventVariant::Decorations(decoration_mode),
+                id: wl_surface.clone(),
+            });
+        }
+        if resize_finished {
+            self.sctk_events.push(SctkEvent::WindowEvent {
+                variant: WindowEventVariant::ResizeFinished {
+                    width: new_size.0,
+                    height: new_size.1,
+                },
+                id: wl_surface.clone(),
+            });
+        }
+        if restored {
+            self.sctk_events.push(SctkEvent::Draw(wl_surface.clone()));
+        }
+        self.reposition_subsurfaces(native_id);
+        // the shortcuts are restored whenever the window is not fullscreen, also if the
+        // compositor refused to make it fullscreen, or the user made it leave
+        self.update_shortcuts_inhibitor(native_id, fullscreen);
+        self.configure_received(native_id, serial);
     }
 }
 
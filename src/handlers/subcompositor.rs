@@ -0,0 +1,36 @@
+use sctk::reexports::client::{
+    protocol::{
+        wl_subcompositor::{self, WlSubcompositor},
+        wl_subsurface::{self, WlSubsurface},
+    },
+    Connection, Dispatch, QueueHandle,
+};
+use std::fmt::Debug;
+
+use crate::event_loop::state::SctkState;
+
+impl<T: 'static + Debug> Dispatch<WlSubcompositor, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSubcompositor,
+        _event: wl_subcompositor::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // wl_subcompositor has no events
+    }
+}
+
+impl<T: 'static + Debug> Dispatch<WlSubsurface, ()> for SctkState<T> {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSubsurface,
+        _event: wl_subsurface::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // wl_subsurface has no events
+    }
+}
@@ -1,6 +1,7 @@
 pub use iced_native::*;
 
 pub mod application;
+pub mod backend;
 pub mod commands;
 pub mod conversion;
 pub mod dpi;
@@ -14,7 +15,11 @@ pub mod settings;
 pub mod util;
 pub mod window;
 
-pub use application::{run, Application};
+pub use application::{run, Application, Decorations, KeyFilter, KeyState, RawKeyEvent};
+#[cfg(feature = "wgpu")]
+pub use application::run_wgpu;
+#[cfg(feature = "wgpu")]
+pub use iced_wgpu;
 pub use clipboard::Clipboard;
 pub use error::Error;
 pub use event_loop::proxy::Proxy;
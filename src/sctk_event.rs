@@ -3,9 +3,10 @@ use std::{collections::HashMap, time::Instant};
 use crate::{
     application::SurfaceIdWrapper,
     conversion::{
-        keysym_to_vkey, modifiers_to_native, pointer_axis_to_native, pointer_button_to_native,
+        keysym_to_vkey, modifiers_to_native, pointer_axis_stopped, pointer_axis_to_native,
+        pointer_button_to_native,
     },
-    dpi::{LogicalSize, PhysicalSize},
+    dpi::LogicalSize,
 };
 use iced_graphics::Point;
 use iced_native::{
@@ -14,12 +15,12 @@ use iced_native::{
         PlatformSpecific,
     },
     keyboard::{self, KeyCode},
-    mouse,
+    mouse, touch,
     window::{self, Id as SurfaceId},
 };
 use sctk::{
     output::OutputInfo,
-    reexports::client::{backend::ObjectId, protocol::{wl_surface::WlSurface, wl_seat::{self, WlSeat}, wl_pointer::WlPointer, wl_keyboard::WlKeyboard, wl_output::WlOutput}, Proxy},
+    reexports::client::{backend::ObjectId, protocol::{wl_data_device_manager::DndAction, wl_surface::WlSurface, wl_seat::{self, WlSeat}, wl_pointer::WlPointer, wl_keyboard::WlKeyboard, wl_touch::WlTouch, wl_output::{self, WlOutput}}, Proxy},
     seat::{
         keyboard::{KeyEvent, Modifiers},
         pointer::{PointerEvent, PointerEventKind},
@@ -27,7 +28,7 @@ use sctk::{
     },
     shell::{
         layer::LayerSurfaceConfigure,
-        xdg::{popup::PopupConfigure, window::WindowConfigure},
+        xdg::{popup::PopupConfigure, window::{DecorationMode, WindowConfigure}},
     },
 };
 
@@ -104,13 +105,33 @@ pub enum SctkEvent {
         variant: PointerEvent,
         ptr_id: WlPointer,
         seat_id: WlSeat,
+        /// the number of consecutive clicks, if the event is a button press
+        click_count: Option<u32>,
+        /// the held button, if the event is the motion which moved the pointer far
+        /// enough from where it was pressed to start a drag
+        drag_button: Option<u32>,
+        /// the multipliers of the horizontal and vertical scroll deltas, negative if the
+        /// scroll direction is inverted
+        scroll_factor: (f32, f32),
     },
     KeyboardEvent {
         variant: KeyboardEventVariant,
         kbd_id: WlKeyboard,
         seat_id: WlSeat,
+        /// the surface which had keyboard focus when the event was received
+        surface: Option<WlSurface>,
     },
-    // TODO data device & touch
+    TouchEvent {
+        variant: TouchEventVariant,
+        touch_id: WlTouch,
+        seat_id: WlSeat,
+        /// the surface the touch point went down on
+        surface: WlSurface,
+        /// whether the touch point also drives the pointer, for widgets which only
+        /// handle the mouse
+        primary: bool,
+    },
+    // TODO data device
 
     //
     // Surface Events
@@ -132,6 +153,10 @@ pub enum SctkEvent {
         /// the id of this popup
         id: WlSurface,
     },
+    CustomSurfaceEvent {
+        variant: CustomSurfaceEventVariant,
+        id: WlSurface,
+    },
 
     //
     // output events
@@ -150,19 +175,119 @@ pub enum SctkEvent {
     // compositor events
     //
     Draw(WlSurface),
+    /// a buffer attached by the application was released by the compositor
+    BufferReleased {
+        /// the surface of the application the event is delivered to, which is the parent of
+        /// `surface` if it is a subsurface
+        id: WlSurface,
+        surface: WlSurface,
+        surface_id: SurfaceId,
+        /// the id of the buffer given by the application
+        buffer: u64,
+    },
+    /// the application changed the transform of the buffers of a surface, which is
+    /// applied with the next buffer
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:request:set_buffer_transform>
+    BufferTransformChanged {
+        id: WlSurface,
+        transform: wl_output::Transform,
+    },
+    /// the application requested the surface of the renderer to be recreated, keeping
+    /// the wayland surface and its state
+    SurfaceRecreateRequested { id: WlSurface },
+    /// the compositor prefers another integer scale for the buffers of a surface
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:event:preferred_buffer_scale>
     ScaleFactorChanged {
         factor: f64,
-        id: WlOutput,
-        inner_size: PhysicalSize<u32>,
+        id: WlSurface,
+    },
+    /// the surface entered or left an output, and is now displayed on the listed outputs
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_surface:event:enter>
+    SurfaceOutputsChanged {
+        id: WlSurface,
+        outputs: Vec<WlOutput>,
     },
+
+    //
+    // drag and drop events
+    //
+    /// a drag entered, moved over, left or was dropped on a surface of the application
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_device:event:enter>
+    DndOffer {
+        variant: DndOfferEventVariant,
+        surface: WlSurface,
+    },
+
+    /// the drag started by the application ended
+    ///
+    /// <https://wayland.app/protocols/wayland#wl_data_source:event:dnd_finished>
+    DndSource(DndSourceEventVariant),
+
+    //
+    // connection events
+    //
+    /// the connection to the compositor was re-established, and the listed surfaces were recreated
+    Reconnected(Vec<(ObjectId, SurfaceIdWrapper, WlSurface)>),
+}
+
+/// The position of a drag is in surface local coordinates.
+#[derive(Debug, Clone)]
+pub enum DndOfferEventVariant {
+    /// the drag entered the surface, with the mime types of its offer and the actions
+    /// supported by its source
+    Enter {
+        x: f64,
+        y: f64,
+        mime_types: Vec<String>,
+        source_actions: DndAction,
+    },
+    Motion { x: f64, y: f64 },
+    Leave,
+    /// the drag was dropped on the surface, its offer can be read until the next drag
+    Drop,
+}
+
+#[derive(Debug, Clone)]
+pub enum DndSourceEventVariant {
+    /// the drag was cancelled, or the target did not accept it
+    Cancelled,
+    /// the target is done with the drop, which was performed with the given action
+    Finished(DndAction),
 }
 
 #[derive(Debug, Clone)]
 pub enum SeatEventVariant {
-    New,
-    Remove,
+    /// a new seat, with its name if it is already known
+    New(Option<String>),
+    /// the seat was removed, with its name if it was known
+    Remove(Option<String>),
+    /// the name of the seat was received after the seat was announced
+    Name(String),
     NewCapability(Capability, ObjectId),
     RemoveCapability(Capability, ObjectId),
+    /// the seat has been inactive for the configured idle timeout
+    Idle,
+    /// the seat became active again after being idle
+    Resumed,
+    /// the selection, or primary selection, of the active seat changed, with the mime
+    /// types it is offered as. Empty if the selection was cleared.
+    Selection { primary: bool, mime_types: Vec<String> },
+    /// the active layout of the keyboard of the active seat changed, after its keymap changed
+    /// or the user switched to another layout of the keymap, with the name of the layout
+    KeyboardLayout(Option<String>),
+}
+
+/// The position of a touch point is in surface local coordinates.
+#[derive(Debug, Clone)]
+pub enum TouchEventVariant {
+    Down { id: i32, position: (f64, f64) },
+    Up { id: i32, position: (f64, f64) },
+    Motion { id: i32, position: (f64, f64) },
+    Cancel { id: i32, position: (f64, f64) },
 }
 
 #[derive(Debug, Clone)]
@@ -178,7 +303,11 @@ pub enum KeyboardEventVariant {
 #[derive(Debug, Clone)]
 pub enum WindowEventVariant {
     Created(ObjectId, SurfaceId),
+    /// the compositor asked to close the window, which stays alive until it is destroyed
+    ///
     /// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:event:close>
+    CloseRequested,
+    /// the window was destroyed
     Close,
     /// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:event:wm_capabilities>
     WmCapabilities(Vec<u32>),
@@ -187,8 +316,27 @@ pub enum WindowEventVariant {
         width: u32,
         height: u32,
     },
+    /// the decorations chosen by the compositor changed
+    ///
+    /// <https://wayland.app/protocols/xdg-decoration-unstable-v1#zxdg_toplevel_decoration_v1:event:configure>
+    Decorations(DecorationMode),
+    /// the user stopped resizing the window interactively, and it has its final size
+    ResizeFinished {
+        width: u32,
+        height: u32,
+    },
     /// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:event:configure>
-    Configure(WindowConfigure, WlSurface, bool),
+    Configure {
+        configure: WindowConfigure,
+        surface: WlSurface,
+        /// whether it is the first configure of the window
+        first: bool,
+        /// `None` if the configure was not sent by the compositor
+        serial: Option<u32>,
+        /// whether the size is the one last requested by the application, rather than
+        /// one chosen by the compositor or the user
+        requested: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -198,6 +346,8 @@ pub enum PopupEventVariant {
     Done,
     /// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:event:wm_capabilities>
     WmCapabilities(Vec<u32>),
+    /// the configure, the surface and whether it is the first configure
+    ///
     /// <https://wayland.app/protocols/xdg-shell#xdg_popup:event:configure>
     Configure(PopupConfigure, WlSurface, bool),
     /// <https://wayland.app/protocols/xdg-shell#xdg_popup:event:repositioned>
@@ -208,12 +358,28 @@ pub enum PopupEventVariant {
 
 #[derive(Debug, Clone)]
 pub enum LayerSurfaceEventVariant {
-    /// sent after creation of the layer surface
-    Created(ObjectId, SurfaceId),
+    /// sent after creation of the layer surface, with its size if it is not left to the
+    /// compositor
+    Created(ObjectId, SurfaceId, Option<(u32, u32)>),
     /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:event:closed>
     Done,
+    /// the layer surface was closed because the output it was on was removed
+    OutputRemoved(WlOutput),
+    /// the configure, the surface, whether it is the first configure, and the serial
+    /// of the configure
+    ///
     /// <https://wayland.app/protocols/wlr-layer-shell-unstable-v1#zwlr_layer_surface_v1:event:configure>
-    Configure(LayerSurfaceConfigure, WlSurface, bool),
+    Configure(LayerSurfaceConfigure, WlSurface, bool, u32),
+}
+
+#[derive(Debug, Clone)]
+pub enum CustomSurfaceEventVariant {
+    /// sent after creation of the custom surface
+    Created(ObjectId, SurfaceId),
+    /// the role of the surface was closed
+    Done,
+    /// the role of the surface was configured with a new logical size
+    Configure((u32, u32), WlSurface, bool),
 }
 
 /// Describes the reason the event loop is resuming.
@@ -281,8 +447,41 @@ impl SctkEvent {
     ) -> Vec<iced_native::Event> {
         match self {
             // TODO Ashley: Platform specific multi-seat events?
-            SctkEvent::SeatEvent { .. } => Default::default(),
-            SctkEvent::PointerEvent { variant, .. } => match variant.kind {
+            SctkEvent::SeatEvent { variant, id } => match variant {
+                SeatEventVariant::Idle => vec![iced_native::Event::PlatformSpecific(
+                    PlatformSpecific::Wayland(wayland::Event::Idle(wayland::IdleEvent::Idle, id)),
+                )],
+                SeatEventVariant::Resumed => vec![iced_native::Event::PlatformSpecific(
+                    PlatformSpecific::Wayland(wayland::Event::Idle(
+                        wayland::IdleEvent::Resumed,
+                        id,
+                    )),
+                )],
+                SeatEventVariant::KeyboardLayout(name) => {
+                    vec![iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                        wayland::Event::Keyboard(wayland::KeyboardEvent::LayoutChanged(name), id),
+                    ))]
+                }
+                SeatEventVariant::Selection { primary, mime_types } => {
+                    let event = if primary {
+                        wayland::SelectionEvent::Primary(mime_types)
+                    } else {
+                        wayland::SelectionEvent::Clipboard(mime_types)
+                    };
+                    vec![iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                        wayland::Event::Selection(event, id),
+                    ))]
+                }
+                _ => Default::default(),
+            },
+            SctkEvent::PointerEvent {
+                variant,
+                ptr_id,
+                click_count,
+                drag_button,
+                scroll_factor,
+                ..
+            } => match variant.kind {
                 PointerEventKind::Enter { .. } => {
                     vec![iced_native::Event::Mouse(mouse::Event::CursorEntered)]
                 }
@@ -290,17 +489,41 @@ impl SctkEvent {
                     vec![iced_native::Event::Mouse(mouse::Event::CursorLeft)]
                 }
                 PointerEventKind::Motion { .. } => {
-                    vec![iced_native::Event::Mouse(mouse::Event::CursorMoved {
+                    // widgets which start drags, like drag and drop sources, listen for this
+                    // instead of the first motion after a press, so jitter stays a click
+                    let drag = drag_button.and_then(pointer_button_to_native).map(|b| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::Pointer(
+                                wayland::PointerEvent::DragStarted { button: b },
+                                ptr_id,
+                            ),
+                        ))
+                    });
+                    std::iter::once(iced_native::Event::Mouse(mouse::Event::CursorMoved {
                         position: Point::new(variant.position.0 as f32, variant.position.1 as f32),
-                    })]
+                    }))
+                    .chain(drag)
+                    .collect()
                 }
                 PointerEventKind::Press {
                     time: _,
                     button,
                     serial: _,
                 } => pointer_button_to_native(button)
-                    .map(|b| iced_native::Event::Mouse(mouse::Event::ButtonPressed(b)))
                     .into_iter()
+                    .flat_map(|b| {
+                        // widgets which react to double clicks can listen for the click count
+                        let click = click_count.map(|count| {
+                            iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                                wayland::Event::Pointer(
+                                    wayland::PointerEvent::Click { button: b, count },
+                                    ptr_id.clone(),
+                                ),
+                            ))
+                        });
+                        std::iter::once(iced_native::Event::Mouse(mouse::Event::ButtonPressed(b)))
+                            .chain(click)
+                    })
                     .collect(), // TODO Ashley: conversion
                 PointerEventKind::Release {
                     time: _,
@@ -315,15 +538,64 @@ impl SctkEvent {
                     horizontal,
                     vertical,
                     source,
-                } => pointer_axis_to_native(source, horizontal, vertical)
-                    .map(|a| iced_native::Event::Mouse(mouse::Event::WheelScrolled { delta: a }))
-                    .into_iter()
-                    .collect(), // TODO Ashley: conversion
+                } => {
+                    let stopped = pointer_axis_stopped(horizontal, vertical).then(|| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::Pointer(wayland::PointerEvent::ScrollStopped, ptr_id),
+                        ))
+                    });
+                    pointer_axis_to_native(source, horizontal, vertical, scroll_factor)
+                        .map(|a| iced_native::Event::Mouse(mouse::Event::WheelScrolled { delta: a }))
+                        .into_iter()
+                        .chain(stopped)
+                        .collect()
+                }
             },
+            SctkEvent::TouchEvent {
+                variant, primary, ..
+            } => {
+                let (id, position) = match variant {
+                    TouchEventVariant::Down { id, position }
+                    | TouchEventVariant::Up { id, position }
+                    | TouchEventVariant::Motion { id, position }
+                    | TouchEventVariant::Cancel { id, position } => (id, position),
+                };
+                let finger = touch::Finger(id as u64);
+                let position = Point::new(position.0 as f32, position.1 as f32);
+                let event = iced_native::Event::Touch(match variant {
+                    TouchEventVariant::Down { .. } => {
+                        touch::Event::FingerPressed { id: finger, position }
+                    }
+                    TouchEventVariant::Up { .. } => touch::Event::FingerLifted { id: finger, position },
+                    TouchEventVariant::Motion { .. } => {
+                        touch::Event::FingerMoved { id: finger, position }
+                    }
+                    TouchEventVariant::Cancel { .. } => touch::Event::FingerLost { id: finger, position },
+                });
+                // the pointer is moved to the touch point before it presses the button
+                let emulated = primary
+                    .then(|| {
+                        let moved = iced_native::Event::Mouse(mouse::Event::CursorMoved { position });
+                        let button = match variant {
+                            TouchEventVariant::Down { .. } => {
+                                Some(mouse::Event::ButtonPressed(mouse::Button::Left))
+                            }
+                            TouchEventVariant::Up { .. } | TouchEventVariant::Cancel { .. } => {
+                                Some(mouse::Event::ButtonReleased(mouse::Button::Left))
+                            }
+                            TouchEventVariant::Motion { .. } => None,
+                        };
+                        std::iter::once(moved).chain(button.map(iced_native::Event::Mouse))
+                    })
+                    .into_iter()
+                    .flatten();
+                std::iter::once(event).chain(emulated).collect()
+            }
             SctkEvent::KeyboardEvent {
                 variant,
                 kbd_id: _,
                 seat_id: _,
+                surface: _,
             } => match variant {
                 KeyboardEventVariant::Leave(surface) => surface_ids
                     .get(&surface.id())
@@ -333,7 +605,7 @@ impl SctkEvent {
                                 wayland::Event::Layer(LayerEvent::Unfocused, surface, id.inner()),
                             ))
                         }
-                        SurfaceIdWrapper::Window(id) => {
+                        SurfaceIdWrapper::Window(id) | SurfaceIdWrapper::Custom(id) => {
                             iced_native::Event::Window(*id, window::Event::Unfocused)
                         }
                         SurfaceIdWrapper::Popup(_id) => {
@@ -352,7 +624,7 @@ impl SctkEvent {
                                 wayland::Event::Layer(LayerEvent::Focused, surface, id.inner()),
                             ))
                         }
-                        SurfaceIdWrapper::Window(id) => {
+                        SurfaceIdWrapper::Window(id) | SurfaceIdWrapper::Custom(id) => {
                             iced_native::Event::Window(*id, window::Event::Focused)
                         }
                         SurfaceIdWrapper::Popup(_id) => {
@@ -442,32 +714,81 @@ impl SctkEvent {
             SctkEvent::WindowEvent { variant, id: surface } => match variant {
                 // TODO Ashley: platform specific events for window
                 WindowEventVariant::Created(..) => Default::default(),
-                WindowEventVariant::Close => destroyed_surface_ids
+                WindowEventVariant::CloseRequested => surface_ids
                     .get(&surface.id())
                     .map(|id| iced_native::Event::Window(id.inner(), window::Event::CloseRequested))
                     .into_iter()
                     .collect(),
-                WindowEventVariant::WmCapabilities(_) => Default::default(),
+                WindowEventVariant::Close => Default::default(),
+                WindowEventVariant::WmCapabilities(capabilities) => surface_ids
+                    .get(&surface.id())
+                    .map(|id| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::Window(
+                                wayland::WindowEvent::WmCapabilities(wm_capabilities_to_native(
+                                    &capabilities,
+                                )),
+                                surface,
+                                id.inner(),
+                            ),
+                        ))
+                    })
+                    .into_iter()
+                    .collect(),
                 WindowEventVariant::ConfigureBounds { .. } => Default::default(),
-                WindowEventVariant::Configure(configure, surface, _) => {
-                    if configure.is_resizing() {
+                WindowEventVariant::ResizeFinished { width, height } => surface_ids
+                    .get(&surface.id())
+                    .map(|id| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::Window(
+                                wayland::WindowEvent::ResizeFinished { width, height },
+                                surface,
+                                id.inner(),
+                            ),
+                        ))
+                    })
+                    .into_iter()
+                    .collect(),
+                WindowEventVariant::Decorations(mode) => surface_ids
+                    .get(&surface.id())
+                    .map(|id| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::Window(
+                                wayland::WindowEvent::ServerSideDecorations(
+                                    mode == DecorationMode::Server,
+                                ),
+                                surface,
+                                id.inner(),
+                            ),
+                        ))
+                    })
+                    .into_iter()
+                    .collect(),
+                WindowEventVariant::Configure {
+                    configure,
+                    surface,
+                    serial,
+                    requested,
+                    ..
+                } => {
+                    // the application already knows about the sizes it requested itself
+                    let resized = if !requested {
                         let new_size = configure.new_size.unwrap();
-                        surface_ids
-                            .get(&surface.id())
-                            .map(|id| {
-                                iced_native::Event::Window(
-                                    id.inner(),
-                                    window::Event::Resized {
-                                        width: new_size.0,
-                                        height: new_size.1,
-                                    },
-                                )
-                            })
-                            .into_iter()
-                            .collect()
+                        surface_ids.get(&surface.id()).map(|id| {
+                            iced_native::Event::Window(
+                                id.inner(),
+                                window::Event::Resized {
+                                    width: new_size.0,
+                                    height: new_size.1,
+                                },
+                            )
+                        })
                     } else {
-                        Default::default()
-                    }
+                        None
+                    };
+                    let configured = serial
+                        .and_then(|serial| configure_to_native(surface_ids, surface, serial));
+                    resized.into_iter().chain(configured).collect()
                 }
             },
             SctkEvent::LayerSurfaceEvent { variant, id: surface } => match variant {
@@ -480,6 +801,24 @@ impl SctkEvent {
                     })
                     .into_iter()
                     .collect(),
+                LayerSurfaceEventVariant::OutputRemoved(output) => destroyed_surface_ids
+                    .get(&surface.id())
+                    .map(|id| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::Layer(
+                                LayerEvent::OutputRemoved(output),
+                                surface,
+                                id.inner(),
+                            ),
+                        ))
+                    })
+                    .into_iter()
+                    .collect(),
+                LayerSurfaceEventVariant::Configure(_, surface, _, serial) => {
+                    configure_to_native(surface_ids, surface, serial)
+                        .into_iter()
+                        .collect()
+                }
                 _ => Default::default(),
             },
             SctkEvent::PopupEvent { variant, id:surface, .. } => {
@@ -494,20 +833,157 @@ impl SctkEvent {
                         .into_iter()
                         .collect(),
                     PopupEventVariant::Created(_, _) => Default::default(), // TODO
-                    PopupEventVariant::WmCapabilities(_) => Default::default(), // TODO
-                    PopupEventVariant::Configure(_, _, _) => Default::default(), // TODO
-                    PopupEventVariant::RepositionionedPopup { token } => Default::default(), // TODO
+                    PopupEventVariant::WmCapabilities(capabilities) => surface_ids
+                        .get(&surface.id())
+                        .map(|id| {
+                            iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                                wayland::Event::Popup(
+                                    PopupEvent::WmCapabilities(wm_capabilities_to_native(
+                                        &capabilities,
+                                    )),
+                                    surface,
+                                    id.inner(),
+                                ),
+                            ))
+                        })
+                        .into_iter()
+                        .collect(),
+                    PopupEventVariant::Configure(configure, surface, _) => {
+                        configure_to_native(surface_ids, surface, configure.serial)
+                            .into_iter()
+                            .collect()
+                    }
+                    PopupEventVariant::RepositionionedPopup { token } => surface_ids
+                        .get(&surface.id())
+                        .map(|id| {
+                            iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                                wayland::Event::Popup(
+                                    PopupEvent::Repositioned { token },
+                                    surface,
+                                    id.inner(),
+                                ),
+                            ))
+                        })
+                        .into_iter()
+                        .collect(),
                 }
             }
+            SctkEvent::CustomSurfaceEvent { .. } => Default::default(),
             SctkEvent::NewOutput { id, info } => Default::default(),
             SctkEvent::UpdateOutput { id, info } => Default::default(),
             SctkEvent::RemovedOutput(_) => Default::default(),
             SctkEvent::Draw(_) => Default::default(),
-            SctkEvent::ScaleFactorChanged {
-                factor,
-                id,
-                inner_size,
-            } => Default::default(),
+            SctkEvent::ScaleFactorChanged { .. } => Default::default(),
+            SctkEvent::BufferTransformChanged { .. } => Default::default(),
+            SctkEvent::SurfaceRecreateRequested { .. } => Default::default(),
+            SctkEvent::SurfaceOutputsChanged { id, outputs } => surface_ids
+                .get(&id.id())
+                .map(|surface_id| {
+                    iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                        wayland::Event::Surface(
+                            wayland::SurfaceEvent::Outputs(outputs),
+                            id,
+                            surface_id.inner(),
+                        ),
+                    ))
+                })
+                .into_iter()
+                .collect(),
+            SctkEvent::BufferReleased {
+                surface,
+                surface_id,
+                buffer,
+                ..
+            } => vec![iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                wayland::Event::Buffer(wayland::BufferEvent::Released(buffer), surface, surface_id),
+            ))],
+            SctkEvent::DndOffer { variant, surface } => {
+                let event = match variant {
+                    DndOfferEventVariant::Enter {
+                        x,
+                        y,
+                        mime_types,
+                        source_actions,
+                    } => wayland::DndOfferEvent::Enter {
+                        position: Point::new(x as f32, y as f32),
+                        mime_types,
+                        source_actions,
+                    },
+                    DndOfferEventVariant::Motion { x, y } => wayland::DndOfferEvent::Motion {
+                        position: Point::new(x as f32, y as f32),
+                    },
+                    DndOfferEventVariant::Leave => wayland::DndOfferEvent::Leave,
+                    DndOfferEventVariant::Drop => wayland::DndOfferEvent::Drop,
+                };
+                surface_ids
+                    .get(&surface.id())
+                    .map(|surface_id| {
+                        iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                            wayland::Event::DndOffer(event, surface, surface_id.inner()),
+                        ))
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            SctkEvent::DndSource(variant) => {
+                let event = match variant {
+                    DndSourceEventVariant::Cancelled => wayland::DndSourceEvent::Cancelled,
+                    DndSourceEventVariant::Finished(action) => {
+                        wayland::DndSourceEvent::Finished(action)
+                    }
+                };
+                vec![iced_native::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                    wayland::Event::DndSource(event),
+                ))]
+            }
+            SctkEvent::Reconnected(_) => Default::default(),
+        }
+    }
+}
+
+/// Converts the raw capabilities of the `wm_capabilities` event into the actions offered by the
+/// compositor. Unknown capabilities are ignored.
+///
+/// <https://wayland.app/protocols/xdg-shell#xdg_toplevel:enum:wm_capabilities>
+pub(crate) fn wm_capabilities_to_native(capabilities: &[u32]) -> wayland::window::WmCapabilities {
+    let mut native = wayland::window::WmCapabilities::default();
+    for capability in capabilities {
+        match capability {
+            1 => native.window_menu = true,
+            2 => native.maximize = true,
+            3 => native.fullscreen = true,
+            4 => native.minimize = true,
+            _ => {}
         }
     }
+    native
+}
+
+/// Notifies the application of a configure it may hold back the redraws for.
+fn configure_to_native(
+    surface_ids: &HashMap<ObjectId, SurfaceIdWrapper>,
+    surface: WlSurface,
+    serial: u32,
+) -> Option<iced_native::Event> {
+    let id = surface_ids.get(&surface.id())?.inner();
+    Some(iced_native::Event::PlatformSpecific(
+        PlatformSpecific::Wayland(wayland::Event::Configure(serial, surface, id)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wm_capabilities_ignore_unknown_values() {
+        let native = wm_capabilities_to_native(&[2, 4, 7]);
+        assert!(!native.window_menu);
+        assert!(native.maximize);
+        assert!(!native.fullscreen);
+        assert!(native.minimize);
+
+        let native = wm_capabilities_to_native(&[]);
+        assert!(!native.window_menu && !native.maximize && !native.fullscreen && !native.minimize);
+    }
 }
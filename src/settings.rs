@@ -1,7 +1,28 @@
-use iced_native::command::platform_specific::wayland::{
-    layer_surface::SctkLayerSurfaceSettings, window::SctkWindowSettings,
+use std::time::Duration;
+
+use iced_native::{
+    command::platform_specific::wayland::{
+        layer_surface::SctkLayerSurfaceSettings, window::SctkWindowSettings,
+    },
+    window,
 };
 
+use crate::event_loop::custom_surface::SurfaceRoleHandler;
+
+pub use sctk::seat::keyboard::RMLVO;
+
+/// The settings of an application.
+///
+/// New settings are added over time, so the ones which are not needed should be left to
+/// [`Settings::default`]:
+///
+/// ```ignore
+/// let settings = Settings {
+///     flags: (),
+///     exit_on_close_request: false,
+///     ..Default::default()
+/// };
+/// ```
 #[derive(Debug)]
 pub struct Settings<Flags> {
     /// The data needed to initialize an [`Application`].
@@ -10,12 +31,113 @@ pub struct Settings<Flags> {
     pub flags: Flags,
     /// optional keyboard repetition config
     pub kbd_repeat: Option<u32>,
+    /// whether the first touch point also produces mouse events, for widgets which only
+    /// handle the mouse, in addition to the touch events
+    pub touch_emulates_pointer: bool,
+    /// whether the scroll direction of the pointers is inverted, i.e. natural scrolling, for
+    /// wheels and fingers alike, on top of an inversion by the compositor
+    pub invert_scroll: bool,
+    /// optional multipliers of the horizontal and vertical scroll deltas of the pointers,
+    /// for wheels and fingers alike. `None` keeps the deltas provided by the compositor.
+    pub scroll_sensitivity: Option<(f32, f32)>,
+    /// optional xkb rules, model, layout, variant and options of the keymap, which
+    /// override the keymap provided by the compositor
+    pub kbd_keymap: Option<RMLVO>,
     /// optional name and size of a custom pointer theme
     pub ptr_theme: Option<(String, u32)>,
+    /// optional app id of the application, e.g. `com.example.App`, which is set on every
+    /// window without an app id of its own and on the activation requests of the application
+    pub app_id: Option<String>,
     /// surface
     pub surface: InitialSurface,
+    /// optional output the initial layer surface is created on, instead of the one picked by
+    /// the compositor. It is ignored if no output matches, and for an initial window.
+    pub initial_output: Option<OutputSelector>,
+    /// whether layer surfaces with a fixed size are laid out at that size right away, so
+    /// they are drawn as soon as they are configured. Nothing is drawn before the first
+    /// configure of a surface either way.
+    pub draw_before_configure: bool,
     /// whether the application should exit on close of all windows
     pub exit_on_close_request: bool,
+    /// optional maximum number of frames per second of every surface, independent of the
+    /// refresh rate of the outputs. Redraws which would exceed it are delayed.
+    pub max_frame_rate: Option<u32>,
+    /// whether the debug overlay can be toggled with `F12`, or is always shown or hidden
+    pub debug_overlay: DebugOverlay,
+    /// inactivity after which the application is notified that the user is idle,
+    /// `None` disables idle notifications
+    pub idle_timeout: Option<Duration>,
+    /// how often reconnecting to the compositor should be attempted after the
+    /// connection was lost, `0` exits the application instead. The renderer is created
+    /// again with the default settings of its compositor.
+    pub reconnect_attempts: u32,
+    /// the buffers requested from EGL by the OpenGL backend of [`run`]
+    ///
+    /// [`run`]: crate::run
+    pub egl: EglSettings,
+    /// additional surfaces with roles which are provided by the application
+    pub custom_surfaces: Vec<(window::Id, Box<dyn SurfaceRoleHandler>)>,
+}
+
+impl<Flags: Default> Default for Settings<Flags> {
+    fn default() -> Self {
+        Self {
+            flags: Default::default(),
+            kbd_repeat: None,
+            touch_emulates_pointer: false,
+            invert_scroll: false,
+            scroll_sensitivity: None,
+            kbd_keymap: None,
+            ptr_theme: None,
+            app_id: None,
+            surface: Default::default(),
+            initial_output: None,
+            draw_before_configure: false,
+            exit_on_close_request: true,
+            max_frame_rate: None,
+            debug_overlay: Default::default(),
+            idle_timeout: None,
+            reconnect_attempts: 0,
+            egl: Default::default(),
+            custom_surfaces: Vec::new(),
+        }
+    }
+}
+
+/// The buffers of the EGL config, which the surfaces of the OpenGL backend are drawn to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EglSettings {
+    /// the number of samples per pixel for multisampling, `0` disables it
+    pub samples: u8,
+    /// the bits of the depth buffer, `0` requests none
+    pub depth: u8,
+    /// the bits of the stencil buffer, `0` requests none
+    pub stencil: u8,
+    /// whether the surfaces are drawn in the sRGB color space
+    pub srgb: bool,
+}
+
+/// Selects one of the outputs advertised by the compositor at launch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputSelector {
+    /// The output with the given connector name, e.g. `DP-1`.
+    Name(String),
+    /// The output advertised first, which compositors usually treat as the primary one.
+    First,
+    /// The output at the left of the others in the compositor's space.
+    Leftmost,
+}
+
+/// How the debug overlay of the `debug` feature is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebugOverlay {
+    /// The overlay is hidden, and toggled by pressing `F12`.
+    #[default]
+    Toggle,
+    /// The overlay is always shown.
+    AlwaysOn,
+    /// The overlay is never shown, e.g. in a kiosk.
+    AlwaysOff,
 }
 
 #[derive(Debug, Clone)]